@@ -1,6 +1,9 @@
 #![allow(clippy::type_complexity)]
 
-use bevy::prelude::*;
+use bevy::{
+    math::{DQuat, DVec3},
+    prelude::*,
+};
 use big_space::prelude::*;
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use std::{iter::repeat_with, ops::Neg};
@@ -9,9 +12,11 @@ use turborand::prelude::*;
 criterion_group!(
     benches,
     global_transform,
+    translation_to_grid,
     spatial_hashing,
     hash_filtering,
     deep_hierarchy,
+    local_origin_rotation,
     wide_hierarchy,
     vs_bevy,
 );
@@ -30,6 +35,36 @@ fn global_transform(c: &mut Criterion) {
     });
 }
 
+fn translation_to_grid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("translation_to_grid");
+    let grid = Grid::<i64>::default();
+    let rng = Rng::with_seed(342525);
+    let positions: Vec<_> = repeat_with(|| {
+        DVec3::new(
+            rng.f64_normalized(),
+            rng.f64_normalized(),
+            rng.f64_normalized(),
+        ) * 1e12
+    })
+    .take(100_000)
+    .collect();
+    let mut out = Vec::with_capacity(positions.len());
+
+    group.bench_function("serial population 100_000", |b| {
+        b.iter(|| {
+            out.clear();
+            out.extend(positions.iter().map(|&p| grid.translation_to_grid(p)));
+            black_box(&out);
+        });
+    });
+    group.bench_function("batch population 100_000", |b| {
+        b.iter(|| {
+            grid.translation_to_grid_batch(&positions, &mut out);
+            black_box(&out);
+        });
+    });
+}
+
 #[allow(clippy::unit_arg)]
 fn deep_hierarchy(c: &mut Criterion) {
     /// Total number of entities to spawn
@@ -38,7 +73,7 @@ fn deep_hierarchy(c: &mut Criterion) {
     let mut group = c.benchmark_group(format!("deep_hierarchy {N_SPAWN}"));
 
     fn setup(mut commands: Commands) {
-        commands.spawn_big_space::<i32>(Grid::new(10000.0, 0.0), |root| {
+        commands.spawn_big_space::<i32>(Grid::new_uniform(10000.0, 0.0), |root| {
             let mut parent = root.spawn_grid_default(()).id();
             for _ in 0..N_SPAWN {
                 let child = root.commands().spawn(BigGridBundle::<i32>::default()).id();
@@ -72,6 +107,80 @@ fn deep_hierarchy(c: &mut Criterion) {
     });
 }
 
+/// Compares updating [`LocalFloatingOrigin`]s that share the rotation of the floating origin's
+/// grid, which skips the matrix inverse, with rotated ones, which take the general matrix inverse
+/// as every local origin did before. This is measured for a single update, and for a deep hierarchy
+/// of grids.
+#[allow(clippy::unit_arg)]
+fn local_origin_rotation(c: &mut Criterion) {
+    /// Number of nested grids
+    const N_GRIDS: usize = 1_000;
+
+    let mut group = c.benchmark_group(format!("local_origin_rotation {N_GRIDS}"));
+
+    for (name, rotation) in [
+        ("set identity rotation", DQuat::IDENTITY),
+        ("set rotated", DQuat::from_rotation_y(0.1)),
+    ] {
+        let mut origin = LocalFloatingOrigin::<i32>::default();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                black_box(origin.set(
+                    black_box(GridCell::new(1, 2, 3)),
+                    black_box(Vec3::new(4.0, 5.0, 6.0)),
+                    black_box(rotation),
+                ));
+            });
+        });
+    }
+
+    let build = |rotation: Quat| {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, BigSpacePlugin::<i32>::default()));
+        app.world_mut()
+            .commands()
+            .spawn_big_space_default::<i32>(|root| {
+                let mut parent = root.spawn_grid_default(()).id();
+                for _ in 0..N_GRIDS {
+                    let child = root
+                        .commands()
+                        .spawn(BigGridBundle::<i32> {
+                            transform: Transform::from_rotation(rotation),
+                            ..default()
+                        })
+                        .id();
+                    root.commands().entity(parent).add_child(child);
+                    parent = child;
+                }
+                // The floating origin is in the deepest grid, so every grid is visited.
+                let origin = root
+                    .commands()
+                    .spawn((BigSpatialBundle::<i32>::default(), FloatingOrigin))
+                    .id();
+                root.commands().entity(parent).add_child(origin);
+            });
+        app.update();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(LocalFloatingOrigin::<i32>::compute_all);
+        (app, schedule)
+    };
+
+    let (mut app, mut schedule) = build(Quat::IDENTITY);
+    group.bench_function("identity rotation", |b| {
+        b.iter(|| {
+            black_box(schedule.run(app.world_mut()));
+        });
+    });
+
+    let (mut app, mut schedule) = build(Quat::from_rotation_y(0.1));
+    group.bench_function("rotated", |b| {
+        b.iter(|| {
+            black_box(schedule.run(app.world_mut()));
+        });
+    });
+}
+
 #[allow(clippy::unit_arg)]
 fn wide_hierarchy(c: &mut Criterion) {
     /// Total number of entities to spawn
@@ -80,7 +189,7 @@ fn wide_hierarchy(c: &mut Criterion) {
     let mut group = c.benchmark_group(format!("wide_hierarchy {N_SPAWN}"));
 
     fn setup(mut commands: Commands) {
-        commands.spawn_big_space::<i32>(Grid::new(10000.0, 0.0), |root| {
+        commands.spawn_big_space::<i32>(Grid::new_uniform(10000.0, 0.0), |root| {
             for _ in 0..N_SPAWN {
                 root.spawn_spatial(());
             }
@@ -122,7 +231,7 @@ fn spatial_hashing(c: &mut Criterion) {
     const N_MOVE: usize = 1_000;
 
     fn setup(mut commands: Commands) {
-        commands.spawn_big_space::<i32>(Grid::new(1.0, 0.0), |root| {
+        commands.spawn_big_space::<i32>(Grid::new_uniform(1.0, 0.0), |root| {
             let rng = Rng::with_seed(342525);
             let values: Vec<_> = repeat_with(|| {
                 [
@@ -205,7 +314,7 @@ fn spatial_hashing(c: &mut Criterion) {
     // });
 
     fn setup_uniform<const HALF_EXTENT: i32>(mut commands: Commands) {
-        commands.spawn_big_space::<i32>(Grid::new(1.0, 0.0), |root| {
+        commands.spawn_big_space::<i32>(Grid::new_uniform(1.0, 0.0), |root| {
             for x in HALF_EXTENT.neg()..HALF_EXTENT {
                 for y in HALF_EXTENT.neg()..HALF_EXTENT {
                     for z in HALF_EXTENT.neg()..HALF_EXTENT {
@@ -228,7 +337,7 @@ fn spatial_hashing(c: &mut Criterion) {
         .query_filtered::<Entity, With<BigSpace>>()
         .single(app.world());
     let spatial_map = app.world().resource::<GridHashMap<i32>>();
-    let hash = GridHash::__new_manual(parent, &GridCell { x: 0, y: 0, z: 0 });
+    let hash = GridHash::new_for(parent, GridCell::ZERO);
     let entry = spatial_map.get(&hash).unwrap();
 
     assert_eq!(spatial_map.nearby(entry).count(), 27);
@@ -256,7 +365,7 @@ fn spatial_hashing(c: &mut Criterion) {
         .query_filtered::<Entity, With<BigSpace>>()
         .single(app.world());
     let spatial_map = app.world().resource::<GridHashMap<i32>>();
-    let hash = GridHash::__new_manual(parent, &GridCell { x: 0, y: 0, z: 0 });
+    let hash = GridHash::new_for(parent, GridCell::ZERO);
     let entry = spatial_map.get(&hash).unwrap();
 
     assert_eq!(spatial_map.nearby(entry).count(), 27);
@@ -270,6 +379,11 @@ fn spatial_hashing(c: &mut Criterion) {
     group.bench_function("nearby flood population 1_000_000", |b| {
         b.iter(|| black_box(spatial_map.flood(&hash, None).count()));
     });
+
+    assert_eq!(spatial_map.flood_par(&hash).count(), 1_000_000);
+    group.bench_function("nearby flood_par population 1_000_000", |b| {
+        b.iter(|| black_box(spatial_map.flood_par(&hash).count()));
+    });
 }
 
 #[allow(clippy::unit_arg)]
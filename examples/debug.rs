@@ -64,7 +64,7 @@ fn setup(
         ..default()
     });
 
-    commands.spawn_big_space::<i64>(Grid::new(1.0, 0.01), |root| {
+    commands.spawn_big_space::<i64>(Grid::new_uniform(1.0, 0.01), |root| {
         root.spawn_spatial((
             Mesh3d(mesh_handle.clone()),
             MeshMaterial3d(matl_handle.clone()),
@@ -79,7 +79,7 @@ fn setup(
             Mover::<2>,
         ));
 
-        root.with_grid(Grid::new(0.2, 0.01), |new_grid| {
+        root.with_grid(Grid::new_uniform(0.2, 0.01), |new_grid| {
             new_grid.insert((
                 Mesh3d(mesh_handle.clone()),
                 MeshMaterial3d(matl_handle.clone()),
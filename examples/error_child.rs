@@ -37,7 +37,7 @@ fn setup_scene(
 ) {
     let mesh_handle = meshes.add(Sphere::new(SPHERE_RADIUS).mesh());
 
-    commands.spawn_big_space::<i64>(Grid::new(SPHERE_RADIUS * 100.0, 0.0), |root_grid| {
+    commands.spawn_big_space::<i64>(Grid::new_uniform(SPHERE_RADIUS * 100.0, 0.0), |root_grid| {
         root_grid.spawn_spatial((
             Mesh3d(mesh_handle.clone()),
             MeshMaterial3d(materials.add(Color::from(palettes::css::BLUE))),
@@ -45,32 +45,35 @@ fn setup_scene(
         ));
 
         let parent = root_grid.grid().translation_to_grid(DISTANT);
-        root_grid.with_grid(Grid::new(SPHERE_RADIUS * 100.0, 0.0), |parent_grid| {
-            // This function introduces a small amount of error, because it can only work up
-            // to double precision floats. (f64).
-            let child = parent_grid
-                .grid()
-                .translation_to_grid(-DISTANT + NEARBY.as_dvec3());
-            parent_grid.insert((
-                Mesh3d(mesh_handle.clone()),
-                MeshMaterial3d(materials.add(Color::from(palettes::css::RED))),
-                Transform::from_translation(parent.1),
-            ));
-            parent_grid.insert(parent.0);
+        root_grid.with_grid(
+            Grid::new_uniform(SPHERE_RADIUS * 100.0, 0.0),
+            |parent_grid| {
+                // This function introduces a small amount of error, because it can only work up
+                // to double precision floats. (f64).
+                let child = parent_grid
+                    .grid()
+                    .translation_to_grid(-DISTANT + NEARBY.as_dvec3());
+                parent_grid.insert((
+                    Mesh3d(mesh_handle.clone()),
+                    MeshMaterial3d(materials.add(Color::from(palettes::css::RED))),
+                    Transform::from_translation(parent.1),
+                ));
+                parent_grid.insert(parent.0);
 
-            // A green sphere that is a child of the sphere very far from the origin. This
-            // child is very far from its parent, and should be located exactly at the
-            // NEARBY position (if there was no floating point error). The distance from the
-            // green sphere to the blue sphere is the error caused by float imprecision.
-            // Note that the sphere does not have any rendering artifacts, its position just
-            // has a fixed error.
-            parent_grid.spawn((
-                Mesh3d(mesh_handle),
-                MeshMaterial3d(materials.add(Color::from(palettes::css::GREEN))),
-                Transform::from_translation(child.1),
-                child.0,
-            ));
-        });
+                // A green sphere that is a child of the sphere very far from the origin. This
+                // child is very far from its parent, and should be located exactly at the
+                // NEARBY position (if there was no floating point error). The distance from the
+                // green sphere to the blue sphere is the error caused by float imprecision.
+                // Note that the sphere does not have any rendering artifacts, its position just
+                // has a fixed error.
+                parent_grid.spawn((
+                    Mesh3d(mesh_handle),
+                    MeshMaterial3d(materials.add(Color::from(palettes::css::GREEN))),
+                    Transform::from_translation(child.1),
+                    child.0,
+                ));
+            },
+        );
 
         root_grid.spawn_spatial((
             DirectionalLight::default(),
@@ -94,8 +94,8 @@ fn draw_partitions(
             .filter(|hash| *hash != camera.single())
             .for_each(|h| {
                 let center = [h.cell().x, h.cell().y, h.cell().z];
-                let local_trans = Transform::from_translation(IVec3::from(center).as_vec3() * l)
-                    .with_scale(Vec3::splat(l));
+                let local_trans =
+                    Transform::from_translation(IVec3::from(center).as_vec3() * l).with_scale(l);
                 gizmos.cuboid(
                     transform.mul_transform(local_trans),
                     Hsla::new(hue, 1.0, 0.5, 0.2),
@@ -193,7 +193,7 @@ fn move_player(
         // let grid = grid.get(entry.grid).unwrap();
         // let transform = grid.global_transform(
         //     &entry.cell,
-        //     &Transform::from_scale(Vec3::splat(grid.cell_edge_length() * 0.99)),
+        //     &Transform::from_scale(grid.cell_edge_length() * 0.99),
         // );
         // gizmos.cuboid(transform, Color::linear_rgba(1.0, 1.0, 1.0, 0.2));
     });
@@ -276,7 +276,7 @@ fn spawn(
             .unwrap(),
     );
 
-    commands.spawn_big_space::<i32>(Grid::new(CELL_WIDTH, 0.0), |root| {
+    commands.spawn_big_space::<i32>(Grid::new_uniform(CELL_WIDTH, 0.0), |root| {
         root.spawn_spatial((
             FloatingOrigin,
             Camera3d::default(),
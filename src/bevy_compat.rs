@@ -0,0 +1,96 @@
+//! Helpers for using bevy's built in features, like scenes and GLTFs, with `big_space`.
+
+use crate::prelude::*;
+use bevy_asset::Handle;
+use bevy_ecs::{prelude::*, system::EntityCommands};
+use bevy_hierarchy::prelude::*;
+use bevy_scene::{Scene, SceneRoot};
+use bevy_transform::prelude::*;
+
+/// Adds scene spawning commands to bevy's `Commands`.
+pub trait SceneCommands {
+    /// Spawn a [`Scene`], like a GLTF, at a high precision position in a [`Grid`].
+    ///
+    /// The scene root is spawned as a high precision spatial entity with a [`GridCell`], as a child
+    /// of the `grid` entity. The entities in the scene are low precision children of the scene
+    /// root, and will have their transforms propagated relative to the root, just like any other
+    /// [`LowPrecisionRoot`](crate::grid::propagation::LowPrecisionRoot) in a [`BigSpace`].
+    fn spawn_scene_at<P: GridPrecision>(
+        &mut self,
+        scene: Handle<Scene>,
+        grid: Entity,
+        cell: GridCell<P>,
+        transform: Transform,
+    ) -> EntityCommands<'_>;
+}
+
+impl SceneCommands for Commands<'_, '_> {
+    fn spawn_scene_at<P: GridPrecision>(
+        &mut self,
+        scene: Handle<Scene>,
+        grid: Entity,
+        cell: GridCell<P>,
+        transform: Transform,
+    ) -> EntityCommands<'_> {
+        let mut entity_commands = self.spawn((SceneRoot(scene), cell, transform));
+        entity_commands.set_parent(grid);
+        entity_commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{prelude::*, scene::ScenePlugin};
+
+    #[test]
+    fn scene_at_far_cell() {
+        #[derive(Component, Reflect, Default)]
+        #[reflect(Component)]
+        struct SceneChild;
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            ScenePlugin,
+            BigSpacePlugin::<i64>::default(),
+        ))
+        .register_type::<SceneChild>();
+
+        let mut scene_world = World::new();
+        scene_world.spawn((Transform::from_xyz(1.0, 2.0, 3.0), SceneChild));
+        let scene = app
+            .world_mut()
+            .resource_mut::<Assets<Scene>>()
+            .add(Scene::new(scene_world));
+
+        let root = app
+            .world_mut()
+            .spawn(BigSpaceRootBundle::<i64>::default())
+            .id();
+        app.world_mut()
+            .spawn((GridCell::<i64>::new(1_000_000_000, 0, 0), FloatingOrigin))
+            .set_parent(root);
+
+        let mut commands = app.world_mut().commands();
+        commands.spawn_scene_at(
+            scene,
+            root,
+            GridCell::<i64>::new(1_000_000_001, 0, 0),
+            Transform::from_xyz(10.0, 0.0, 0.0),
+        );
+        app.world_mut().flush();
+
+        app.update();
+        app.update();
+
+        let mut children = app
+            .world_mut()
+            .query_filtered::<&GlobalTransform, With<SceneChild>>();
+        let child = children.single(app.world());
+        // One cell away from the floating origin, plus the scene root and child transforms.
+        let expected = Vec3::new(2_000.0 + 10.0 + 1.0, 2.0, 3.0);
+        assert!(child.translation().distance(expected) < 1e-3);
+    }
+}
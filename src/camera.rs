@@ -10,6 +10,7 @@ use bevy_input::{mouse::MouseMotion, prelude::*};
 use bevy_math::{prelude::*, DQuat, DVec3};
 use bevy_reflect::prelude::*;
 use bevy_render::{
+    camera::{Camera, ViewportConversionError},
     primitives::Aabb,
     view::{InheritedVisibility, RenderLayers},
 };
@@ -36,6 +37,8 @@ impl<P: GridPrecision> Plugin for CameraControllerPlugin<P> {
 }
 
 /// Per-camera settings for the `big_space` floating origin camera controller.
+///
+/// To restrict the motion of the camera to a surface, add a [`CameraConstraint`].
 #[derive(Clone, Debug, Reflect, Component)]
 #[reflect(Component)]
 pub struct CameraController {
@@ -55,6 +58,12 @@ pub struct CameraController {
     pub speed_bounds: [f64; 2],
     /// Whether the camera should slow down when approaching an entity's [`Aabb`].
     pub slow_near_objects: bool,
+    /// Whether the camera should accumulate its translation in double precision. When enabled, the
+    /// camera's [`Transform`] is only written from the double precision translation, so rounding
+    /// errors do not accumulate over time.
+    pub high_precision: bool,
+    /// The translation of the camera in its cell, if `high_precision` is enabled.
+    precise_translation: Option<DVec3>,
     nearest_object: Option<(Entity, f64)>,
     vel_translation: DVec3,
     vel_rotation: DQuat,
@@ -74,6 +83,12 @@ impl CameraController {
         self
     }
 
+    /// Sets the `high_precision` parameter of the controller, and returns the modified result.
+    pub fn with_high_precision(mut self, high_precision: bool) -> Self {
+        self.high_precision = high_precision;
+        self
+    }
+
     /// Sets the speed of the controller, and returns the modified result.
     pub fn with_speed(mut self, speed: f64) -> Self {
         self.speed = speed;
@@ -126,6 +141,8 @@ impl Default for CameraController {
             speed_roll: 1.0,
             speed_bounds: [1e-17, 1e30],
             slow_near_objects: true,
+            high_precision: false,
+            precise_translation: None,
             nearest_object: None,
             vel_translation: DVec3::ZERO,
             vel_rotation: DQuat::IDENTITY,
@@ -133,6 +150,67 @@ impl Default for CameraController {
     }
 }
 
+/// Add this alongside a [`CameraController`] to constrain the camera to move along a surface, for
+/// example to walk on the surface of a planet. After the free-fly motion of the camera is computed,
+/// its high precision position is projected onto the surface.
+///
+/// The surface is positioned with a [`GridCell`] and an offset within that cell in the camera's
+/// [`Grid`], so it stays precise even when the surface is very far from the origin.
+#[derive(Clone, Debug, Reflect, Component)]
+#[reflect(Component)]
+pub enum CameraConstraint<P: GridPrecision> {
+    /// Keep the camera on the surface of a sphere.
+    Sphere {
+        /// The cell containing the center of the sphere.
+        cell: GridCell<P>,
+        /// The position of the center of the sphere, relative to the center of the `cell`.
+        offset: DVec3,
+        /// The radius of the sphere.
+        radius: f64,
+    },
+    /// Keep the camera on a plane.
+    Plane {
+        /// The cell containing a point on the plane.
+        cell: GridCell<P>,
+        /// The position of a point on the plane, relative to the center of the `cell`.
+        offset: DVec3,
+        /// The normal of the plane. Does not need to be normalized.
+        normal: DVec3,
+    },
+}
+
+impl<P: GridPrecision> CameraConstraint<P> {
+    /// Project the position `translation` in `cell` onto the constraint surface, returning the
+    /// displacement needed to move it onto the surface.
+    pub fn correction(&self, grid: &Grid<P>, cell: &GridCell<P>, translation: DVec3) -> DVec3 {
+        match self {
+            CameraConstraint::Sphere {
+                cell: center_cell,
+                offset,
+                radius,
+            } => {
+                let from_center =
+                    grid.cell_to_float(&(*cell - *center_cell)) + translation - *offset;
+                match from_center.try_normalize() {
+                    Some(direction) => direction * *radius - from_center,
+                    None => DVec3::ZERO,
+                }
+            }
+            CameraConstraint::Plane {
+                cell: plane_cell,
+                offset,
+                normal,
+            } => {
+                let Some(normal) = normal.try_normalize() else {
+                    return DVec3::ZERO;
+                };
+                let from_plane = grid.cell_to_float(&(*cell - *plane_cell)) + translation - *offset;
+                -normal * from_plane.dot(normal)
+            }
+        }
+    }
+}
+
 /// ButtonInput state used to command camera motion. Reset every time the values are read to update
 /// the camera. Allows you to map any input to camera motions. Uses aircraft principle axes
 /// conventions.
@@ -210,6 +288,24 @@ pub fn default_camera_inputs(
     }
 }
 
+/// Compute a high precision [`GridRay`](crate::grid::raycast::GridRay) in a `grid`, through a
+/// position in the viewport of a `camera`. Use this to pick distant objects with the cursor.
+///
+/// The ray from the camera is computed from its [`GlobalTransform`], which is relative to the
+/// floating origin. This is converted into the grid with [`Grid::ray_to_grid`], which accounts for
+/// the floating origin's cell in the grid. The result can be traversed with
+/// [`Grid::grid_ray_cells`], or tested against the [`GridHashMap`].
+pub fn viewport_to_grid_ray<P: GridPrecision>(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    viewport_position: Vec2,
+    grid: &Grid<P>,
+) -> Result<crate::grid::raycast::GridRay<P>, ViewportConversionError> {
+    camera
+        .viewport_to_world(camera_transform, viewport_position)
+        .map(|ray| grid.ray_to_grid(ray))
+}
+
 /// Find the object nearest the camera, within the same grid as the camera.
 pub fn nearest_objects_in_grid<P: GridPrecision>(
     objects: Query<(
@@ -269,9 +365,10 @@ pub fn camera_controller<P: GridPrecision>(
         &mut GridCell<P>,
         &mut Transform,
         &mut CameraController,
+        Option<&CameraConstraint<P>>,
     )>,
 ) {
-    for (camera, mut cell, mut transform, mut controller) in camera.iter_mut() {
+    for (camera, mut cell, mut transform, mut controller, constraint) in camera.iter_mut() {
         let Some(grid) = grids.parent_grid(camera) else {
             continue;
         };
@@ -293,11 +390,36 @@ pub fn camera_controller<P: GridPrecision>(
         let cam_rot = transform.rotation.as_dquat();
         let vel_t_next = cam_rot * vel_t_target; // Orients the translation to match the camera
         let vel_t_next = vel_t_current.lerp(vel_t_next, lerp_translation);
-        // Convert the high precision translation to a grid cell and low precision translation
-        let (cell_offset, new_translation) = grid.translation_to_grid(vel_t_next);
-        let new = *cell.bypass_change_detection() + cell_offset;
-        cell.set_if_neq(new);
-        transform.translation += new_translation;
+        // The correction is not stored in the velocity, so that snapping onto the surface does not
+        // cause the camera to overshoot on the following frames.
+        let displacement = match constraint {
+            Some(constraint) => {
+                let translation = transform.translation.as_dvec3() + vel_t_next;
+                vel_t_next + constraint.correction(grid, &cell, translation)
+            }
+            None => vel_t_next,
+        };
+        if controller.high_precision {
+            // Continue from the double precision translation, unless the transform was moved by
+            // something other than this controller.
+            let translation = controller
+                .precise_translation
+                .filter(|precise| precise.as_vec3() == transform.translation)
+                .unwrap_or(transform.translation.as_dvec3())
+                + displacement;
+            let (cell_offset, _) = grid.translation_to_grid(translation);
+            let precise = translation - grid.cell_to_float(&cell_offset);
+            let new = *cell.bypass_change_detection() + cell_offset;
+            cell.set_if_neq(new);
+            transform.translation = precise.as_vec3();
+            controller.precise_translation = Some(precise);
+        } else {
+            // Convert the high precision translation to a grid cell and low precision translation
+            let (cell_offset, new_translation) = grid.translation_to_grid(displacement);
+            let new = *cell.bypass_change_detection() + cell_offset;
+            cell.set_if_neq(new);
+            transform.translation += new_translation;
+        }
 
         let new_rotation = vel_r_current.slerp(vel_r_target, lerp_rotation);
         transform.rotation *= new_rotation.as_quat();
@@ -309,3 +431,99 @@ pub fn camera_controller<P: GridPrecision>(
         input.reset();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn high_precision_far_from_origin() {
+        // Moves a camera 0.1 units at a time, 4e6 units from the center of a cell that is itself
+        // far from the origin. A step is smaller than the spacing between `f32`s at that distance.
+        let run = |high_precision: bool| {
+            let mut app = App::new();
+            app.init_resource::<Time>()
+                .init_resource::<CameraInput>()
+                .add_plugins(BigSpacePlugin::<i64>::default())
+                .add_systems(
+                    PostUpdate,
+                    (
+                        (|mut input: ResMut<CameraInput>| input.right = 1.0)
+                            .before(camera_controller::<i64>),
+                        camera_controller::<i64>.before(TransformSystem::TransformPropagate),
+                    ),
+                );
+
+            let mut camera = Entity::PLACEHOLDER;
+            app.world_mut().commands().spawn_big_space::<i64>(
+                Grid::new_uniform(1e7, 0.0),
+                |root| {
+                    camera = root
+                        .spawn_spatial((
+                            GridCell::<i64>::new(1_000_000_000_000, 0, 0),
+                            Transform::from_xyz(4e6, 0.0, 0.0),
+                            FloatingOrigin,
+                            CameraController::default()
+                                .with_smoothness(0.0, 0.0)
+                                .with_slowing(false)
+                                .with_speed_bounds([0.1, 0.1])
+                                .with_high_precision(high_precision),
+                        ))
+                        .id();
+                },
+            );
+            app.update();
+
+            for _ in 0..100 {
+                app.world_mut()
+                    .resource_mut::<Time>()
+                    .advance_by(Duration::from_secs(1));
+                app.update();
+            }
+
+            let world = app.world();
+            assert_eq!(
+                world.get::<GridCell<i64>>(camera),
+                Some(&GridCell::new(1_000_000_000_000, 0, 0))
+            );
+            let controller = world.get::<CameraController>(camera).unwrap();
+            let translation = world.get::<Transform>(camera).unwrap().translation;
+            (controller.precise_translation, translation)
+        };
+
+        let (precise, translation) = run(true);
+        let precise = precise.unwrap();
+        assert!(precise.distance(DVec3::new(4_000_010.0, 0.0, 0.0)) < 1e-6);
+        assert_eq!(translation, precise.as_vec3());
+
+        // Each step is rounded away in single precision.
+        let (precise, translation) = run(false);
+        assert_eq!(precise, None);
+        assert_eq!(translation, Vec3::new(4e6, 0.0, 0.0));
+    }
+
+    #[test]
+    fn constraint_far_from_origin() {
+        let grid = Grid::<i64>::new_uniform(10_000.0, 0.0);
+        let center = GridCell::new(100_000, 0, 0); // 1e9 units from the origin
+        let sphere = CameraConstraint::Sphere {
+            cell: center,
+            offset: DVec3::new(1.5, 0.0, 0.0),
+            radius: 6_000.0,
+        };
+        // 10 units above the surface
+        let cell = center + IVec3::Y;
+        let translation = DVec3::new(1.5, -3_990.0, 0.0);
+        let correction = sphere.correction(&grid, &cell, translation);
+        assert!(correction.distance(DVec3::new(0.0, -10.0, 0.0)) < 1e-9);
+
+        let plane = CameraConstraint::Plane {
+            cell: center,
+            offset: DVec3::new(0.0, 0.25, 0.0),
+            normal: DVec3::Y * 2.0,
+        };
+        let correction = plane.correction(&grid, &cell, DVec3::new(7.0, -9_000.0, 3.0));
+        assert!(correction.distance(DVec3::new(0.0, -999.75, 0.0)) < 1e-9);
+    }
+}
@@ -3,24 +3,105 @@
 use crate::prelude::*;
 use bevy_ecs::prelude::*;
 use bevy_hierarchy::prelude::*;
+use bevy_math::{DVec3, Vec3};
 use bevy_transform::prelude::*;
 use smallvec::SmallVec;
 use std::marker::PhantomData;
 
 /// Adds `big_space` commands to bevy's `Commands`.
 pub trait BigSpaceCommands {
-    /// Spawn a root [`BigSpace`] [`Grid`].
+    /// Spawn a root [`BigSpace`] [`Grid`], returning the root entity.
     fn spawn_big_space<P: GridPrecision>(
         &mut self,
         root_grid: Grid<P>,
         child_builder: impl FnOnce(&mut GridCommands<P>),
-    );
+    ) -> Entity;
 
-    /// Spawn a root [`BigSpace`] with default [`Grid`] settings.
+    /// Spawn a root [`BigSpace`] with default [`Grid`] settings, returning the root entity.
     fn spawn_big_space_default<P: GridPrecision>(
         &mut self,
         child_builder: impl FnOnce(&mut GridCommands<P>),
+    ) -> Entity;
+
+    /// Move a high precision `entity`, along with its low precision children, into the
+    /// `target_grid` of the [`BigSpace`] with the `target_root` entity, for example when passing
+    /// through a portal between two big spaces.
+    ///
+    /// The entity is placed at `placement`, relative to the origin of the target grid, and its
+    /// [`GridCell`] and [`Transform`] translation are computed from this position. The rotation and
+    /// scale of the entity are preserved, as are any other components, like velocities. The
+    /// [`GridHash`] of the entity is updated like any other change of parent, so the entity is
+    /// removed from its old cell and added to the new one in the [`GridHashMap`] on the next
+    /// update. A [`BigSpaceTransfer`] event is sent once the entity has moved.
+    ///
+    /// The [`GlobalTransform`] is recomputed during the same frame, so there is no visible jump as
+    /// long as the `placement` matches where the entity appears to be in the target space.
+    ///
+    /// A [`BigSpace`] can only have one [`FloatingOrigin`]. If the entity is or contains a
+    /// [`FloatingOrigin`], and the target space already has one, a warning is logged and the entity
+    /// is not moved. To move the camera into another space, remove the [`FloatingOrigin`] of the
+    /// target space first, and add one to the space the camera left if it is still rendered.
+    fn transfer_to_big_space<P: GridPrecision>(
+        &mut self,
+        entity: Entity,
+        target_root: Entity,
+        target_grid: Entity,
+        placement: DVec3,
     );
+
+    /// Split the [`BigSpace`] with the `root` entity in two along a plane, moving everything on
+    /// the positive side of the plane into a new [`BigSpace`], and returning the new root entity.
+    /// This is useful when groups of entities diverge so far that a single [`FloatingOrigin`] can
+    /// no longer serve both, for example to stream each group independently.
+    ///
+    /// The plane passes through `plane_origin` with the normal `plane_normal`, both in the root
+    /// [`Grid`]. Each high precision child of the root is moved, along with its descendants, if its
+    /// position is on the side of the plane that `plane_normal` points to. The new root uses the
+    /// same [`Grid`] settings as the old one, with its origin at the center of the cell containing
+    /// `plane_origin`. The [`GridCell`] of each moved entity is shifted by this offset, so its
+    /// [`Transform`] and any child grids are unchanged.
+    ///
+    /// The [`LocalFloatingOrigin`] of the new root is seeded from the old root, so the
+    /// [`GlobalTransform`] of every moved entity is preserved, and nothing visibly moves. Every
+    /// [`BigSpace`] needs exactly one [`FloatingOrigin`], so the space that is left without one gets
+    /// a new high precision entity with a [`FloatingOrigin`], placed in the cell of the old floating
+    /// origin relative to that space. Move it to control what that space is rendered relative to,
+    /// or replace it with your own [`FloatingOrigin`], like a second camera, by despawning it in the
+    /// same frame. If the old space had no [`FloatingOrigin`], neither space gets one.
+    ///
+    /// If `root` is not the root of a [`BigSpace`], a warning is logged and nothing is split. The
+    /// returned entity is despawned in this case. A [`BigSpaceTransfer`] event is sent for every
+    /// moved entity.
+    fn split_big_space<P: GridPrecision>(
+        &mut self,
+        root: Entity,
+        plane_origin: DVec3,
+        plane_normal: DVec3,
+    ) -> Entity;
+
+    /// Change the edge length of the cells in the existing [`Grid`] on the `grid` entity to
+    /// `cell_edge_length`, and recompute the [`GridCell`] and [`Transform`] translation of every
+    /// direct child with a [`GridCell`], so their positions in the grid are preserved. The
+    /// switching threshold of the grid is kept.
+    ///
+    /// This visits every child of the grid, and is meant to be used explicitly, for example when
+    /// experimenting with cell sizes during development. A [`Grid`] cannot be changed by mutating
+    /// the component. To rescale a grid while spawning it, use [`GridCommands::rescale`].
+    ///
+    /// If `grid` does not have a [`Grid<P>`], a warning is logged and nothing is changed.
+    fn rescale_grid<P: GridPrecision>(&mut self, grid: Entity, cell_edge_length: Vec3);
+}
+
+/// Sent when an entity is moved into another [`BigSpace`] with
+/// [`BigSpaceCommands::transfer_to_big_space`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BigSpaceTransfer {
+    /// The entity that was moved.
+    pub entity: Entity,
+    /// The root of the [`BigSpace`] the entity was in, if it was in one.
+    pub from: Option<Entity>,
+    /// The root of the [`BigSpace`] the entity is now in.
+    pub to: Entity,
 }
 
 impl BigSpaceCommands for Commands<'_, '_> {
@@ -28,22 +109,224 @@ impl BigSpaceCommands for Commands<'_, '_> {
         &mut self,
         grid: Grid<P>,
         root_grid: impl FnOnce(&mut GridCommands<P>),
-    ) {
+    ) -> Entity {
         let mut entity_commands = self.spawn(BigSpaceRootBundle::<P>::default());
+        let entity = entity_commands.id();
         let mut cmd = GridCommands {
-            entity: entity_commands.id(),
+            entity,
             commands: entity_commands.commands(),
             grid,
             children: Default::default(),
         };
         root_grid(&mut cmd);
+        entity
     }
 
     fn spawn_big_space_default<P: GridPrecision>(
         &mut self,
         child_builder: impl FnOnce(&mut GridCommands<P>),
+    ) -> Entity {
+        self.spawn_big_space(Grid::default(), child_builder)
+    }
+
+    fn transfer_to_big_space<P: GridPrecision>(
+        &mut self,
+        entity: Entity,
+        target_root: Entity,
+        target_grid: Entity,
+        placement: DVec3,
     ) {
-        self.spawn_big_space(Grid::default(), child_builder);
+        self.queue(move |world: &mut World| {
+            let big_space_root = |world: &World, entity: Entity| {
+                std::iter::successors(Some(entity), |&e| world.get::<Parent>(e).map(|p| p.get()))
+                    .find(|&e| world.get::<BigSpace>(e).is_some())
+            };
+            if big_space_root(world, target_grid) != Some(target_root) {
+                tracing::warn!(
+                    "Unable to transfer {entity:?}, the grid {target_grid:?} is not in the BigSpace {target_root:?}"
+                );
+                return;
+            }
+            let Some(grid) = world.get::<Grid<P>>(target_grid) else {
+                tracing::warn!("Unable to transfer {entity:?}, {target_grid:?} is not a Grid");
+                return;
+            };
+            let (cell, translation) = grid.translation_to_grid(placement);
+            let from = big_space_root(world, entity);
+
+            let (mut moving_origin, mut target_has_origin) = (false, false);
+            for origin in world
+                .query_filtered::<Entity, With<FloatingOrigin>>()
+                .iter(world)
+            {
+                let mut ancestors = std::iter::successors(Some(origin), |&e| {
+                    world.get::<Parent>(e).map(|p| p.get())
+                });
+                if ancestors.any(|e| e == entity) {
+                    moving_origin = true;
+                } else if big_space_root(world, origin) == Some(target_root) {
+                    target_has_origin = true;
+                }
+            }
+            if moving_origin && target_has_origin {
+                tracing::warn!(
+                    "Unable to transfer {entity:?}, it has a FloatingOrigin, and the BigSpace {target_root:?} already has one"
+                );
+                return;
+            }
+
+            let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+                return;
+            };
+            let transform = entity_mut
+                .get::<Transform>()
+                .copied()
+                .unwrap_or_default()
+                .with_translation(translation);
+            entity_mut
+                .insert((cell, transform))
+                .set_parent(target_grid);
+            world.send_event(BigSpaceTransfer {
+                entity,
+                from,
+                to: target_root,
+            });
+        });
+    }
+
+    fn split_big_space<P: GridPrecision>(
+        &mut self,
+        root: Entity,
+        plane_origin: DVec3,
+        plane_normal: DVec3,
+    ) -> Entity {
+        let new_root = self.spawn(BigSpaceRootBundle::<P>::default()).id();
+        self.queue(move |world: &mut World| {
+            let (Some(grid), Some(_)) = (world.get::<Grid<P>>(root), world.get::<BigSpace>(root))
+            else {
+                tracing::warn!("Unable to split {root:?}, it is not the root of a BigSpace");
+                world.despawn(new_root);
+                return;
+            };
+            let (offset, _) = grid.translation_to_grid(plane_origin);
+            let mut new_grid = grid.clone();
+            let origin = grid.local_floating_origin();
+            new_grid.local_floating_origin_mut().set(
+                origin.cell() - offset,
+                origin.translation(),
+                origin.rotation(),
+            );
+
+            let children = world.get::<Children>(root).into_iter().flatten();
+            let moved: Vec<_> = children
+                .filter_map(|&child| {
+                    let cell = world.get::<GridCell<P>>(child)?;
+                    let transform = world.get::<Transform>(child)?;
+                    let position = grid.grid_position_double(cell, transform);
+                    ((position - plane_origin).dot(plane_normal) > 0.0).then_some((child, *cell))
+                })
+                .collect();
+
+            // Find which child of the root the floating origin belongs to, if there is one.
+            let origin_child = world
+                .query_filtered::<Entity, With<FloatingOrigin>>()
+                .iter(world)
+                .find_map(|origin| {
+                    let mut ancestors = std::iter::successors(Some(origin), |&e| {
+                        world.get::<Parent>(e).map(|p| p.get())
+                    });
+                    ancestors.find(|&e| world.get::<Parent>(e).map(|p| p.get()) == Some(root))
+                });
+            let without_origin = origin_child.map(|origin_child| {
+                match moved.iter().any(|&(entity, _)| entity == origin_child) {
+                    true => root,
+                    false => new_root,
+                }
+            });
+
+            world.entity_mut(new_root).insert(new_grid);
+            for (entity, cell) in moved {
+                world
+                    .entity_mut(entity)
+                    .insert(cell - offset)
+                    .set_parent(new_root);
+                world.send_event(BigSpaceTransfer {
+                    entity,
+                    from: Some(root),
+                    to: new_root,
+                });
+            }
+
+            // Place a new floating origin in the origin cell of the space without one, so its
+            // transforms keep updating, and nothing visibly moves.
+            let Some(space) = without_origin else {
+                return;
+            };
+            let Some(origin) = world
+                .get::<Grid<P>>(space)
+                .map(|grid| grid.local_floating_origin().clone())
+            else {
+                return;
+            };
+            world
+                .spawn((
+                    #[cfg(feature = "bevy_render")]
+                    bevy_render::view::Visibility::default(),
+                    origin.cell(),
+                    Transform::from_translation(origin.translation())
+                        .with_rotation(origin.rotation().as_quat()),
+                    FloatingOrigin,
+                ))
+                .set_parent(space);
+        });
+        new_root
+    }
+
+    fn rescale_grid<P: GridPrecision>(&mut self, grid: Entity, cell_edge_length: Vec3) {
+        self.queue(move |world: &mut World| {
+            let Some(old_grid) = world.get::<Grid<P>>(grid).cloned() else {
+                tracing::warn!("Unable to rescale {grid:?}, it is not a Grid");
+                return;
+            };
+            let new_grid = rescaled_grid(&old_grid, cell_edge_length);
+            let children: Vec<_> = world
+                .get::<Children>(grid)
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+            rescale_children(world, children, &old_grid, &new_grid);
+            world.entity_mut(grid).insert(new_grid);
+        });
+    }
+}
+
+/// A copy of `grid` with cells that have an edge length of `cell_edge_length`, and the same
+/// switching threshold.
+fn rescaled_grid<P: GridPrecision>(grid: &Grid<P>, cell_edge_length: Vec3) -> Grid<P> {
+    let threshold = grid.maximum_distance_from_origin() - grid.cell_edge_length() / 2.0;
+    Grid::new_with_max_distance(cell_edge_length, cell_edge_length / 2.0 + threshold)
+}
+
+/// Move each of the `children` with a [`GridCell`] from the `old_grid` into the `new_grid`,
+/// preserving their positions.
+fn rescale_children<P: GridPrecision>(
+    world: &mut World,
+    children: impl IntoIterator<Item = Entity>,
+    old_grid: &Grid<P>,
+    new_grid: &Grid<P>,
+) {
+    for child in children {
+        let Ok(mut child) = world.get_entity_mut(child) else {
+            continue;
+        };
+        let Some(cell) = child.get::<GridCell<P>>().copied() else {
+            continue;
+        };
+        let transform = child.get::<Transform>().copied().unwrap_or_default();
+        let position = old_grid.grid_position_double(&cell, &transform);
+        let (cell, translation) = new_grid.translation_to_grid(position);
+        child.insert((cell, transform.with_translation(translation)));
     }
 }
 
@@ -67,6 +350,52 @@ impl<'a, P: GridPrecision> GridCommands<'a, P> {
         self
     }
 
+    /// Change the edge length of the cells in this grid to `cell_edge_length`, and recompute the
+    /// [`GridCell`] and [`Transform`] translation of every direct child with a [`GridCell`], so
+    /// their positions in the grid are preserved. The switching threshold of the grid is kept.
+    ///
+    /// This applies to children spawned with these commands before calling this method, as well as
+    /// children that were already in the grid. Children spawned afterward are placed in the
+    /// rescaled grid.
+    ///
+    /// This visits every child of the grid, and is meant to be used explicitly, for example when
+    /// experimenting with cell sizes during development. A [`Grid`] cannot be changed by mutating
+    /// the component. To rescale a grid that has already been spawned, use
+    /// [`BigSpaceCommands::rescale_grid`].
+    pub fn rescale(&mut self, cell_edge_length: Vec3) -> &mut Self {
+        let old_grid = self.grid.clone();
+        let new_grid = rescaled_grid(&old_grid, cell_edge_length);
+        self.grid = new_grid.clone();
+
+        let entity = self.entity;
+        let mut children = self.children.clone();
+        self.commands.queue(move |world: &mut World| {
+            if let Some(existing) = world.get::<Children>(entity) {
+                let existing: Vec<_> = existing
+                    .iter()
+                    .filter(|child| !children.contains(child))
+                    .copied()
+                    .collect();
+                children.extend(existing);
+            }
+            rescale_children(world, children, &old_grid, &new_grid);
+        });
+        self
+    }
+
+    /// Reserve capacity for `additional` entities in the [`GridHashMap<P>`], if it exists. This is
+    /// a size hint to avoid growing the map many times when spawning a large number of spatial
+    /// entities at once. Maps with a custom [`GridHashMapFilter`](crate::hash::GridHashMapFilter)
+    /// can be reserved with [`GridHashMap::reserve`].
+    pub fn reserve_spatial_hash(&mut self, additional: usize) -> &mut Self {
+        self.commands.queue(move |world: &mut World| {
+            if let Some(mut map) = world.get_resource_mut::<GridHashMap<P>>() {
+                map.reserve(additional);
+            }
+        });
+        self
+    }
+
     /// Spawn an entity in this grid.
     pub fn spawn(&mut self, bundle: impl Bundle) -> SpatialEntityCommands<P> {
         let entity = self.commands.spawn(bundle).id();
@@ -101,6 +430,66 @@ impl<'a, P: GridPrecision> GridCommands<'a, P> {
         }
     }
 
+    /// Add a high-precision spatial entity to this grid at the absolute `position` in this grid,
+    /// and insert the provided bundle.
+    ///
+    /// The position is split into a [`GridCell`] and [`Transform::translation`] with
+    /// [`Grid::translation_to_grid`]. These override any [`GridCell`] or translation in the
+    /// `bundle`, but the rotation and scale of a [`Transform`] in the `bundle` are kept.
+    pub fn spawn_spatial_at(
+        &mut self,
+        position: DVec3,
+        bundle: impl Bundle,
+    ) -> SpatialEntityCommands<'_, P> {
+        let (cell, translation) = self.grid.translation_to_grid(position);
+        let mut spatial = self.spawn_spatial(bundle);
+        let entity = spatial.id();
+        spatial
+            .commands()
+            .entity(entity)
+            .queue(move |mut entity: EntityWorldMut| {
+                entity.insert(cell);
+                if let Some(mut transform) = entity.get_mut::<Transform>() {
+                    transform.translation = translation;
+                }
+            });
+        spatial
+    }
+
+    /// Add an existing entity to this grid as a high-precision spatial entity ([`GridCell`]), and
+    /// insert the provided bundle.
+    ///
+    /// Unlike [`Self::spawn_spatial`], this does not spawn a new entity. The [`GridCell`] and
+    /// [`Transform`] are only inserted if the entity does not already have them, otherwise they are
+    /// left as-is, or updated if they are in the `bundle`. Calling this multiple times with the
+    /// same entity will not create duplicates. This is useful for adding entities that were
+    /// spawned elsewhere, for example by a networking library, into a grid.
+    pub fn upsert_spatial(
+        &mut self,
+        entity: Entity,
+        bundle: impl Bundle,
+    ) -> SpatialEntityCommands<'_, P> {
+        self.commands
+            .entity(entity)
+            .insert_if_new((
+                #[cfg(feature = "bevy_render")]
+                bevy_render::view::Visibility::default(),
+                Transform::default(),
+                GridCell::<P>::default(),
+            ))
+            .insert(bundle);
+
+        if !self.children.contains(&entity) {
+            self.children.push(entity);
+        }
+
+        SpatialEntityCommands {
+            entity,
+            commands: self.commands.reborrow(),
+            phantom: PhantomData,
+        }
+    }
+
     /// Returns the [`Entity``] id of the entity.
     pub fn id(&self) -> Entity {
         self.entity
@@ -117,20 +506,21 @@ impl<'a, P: GridPrecision> GridCommands<'a, P> {
         self
     }
 
-    /// Add a high-precision spatial entity ([`GridCell`]) to this grid, and apply entity commands
-    /// to it via the closure. This allows you to insert bundles on this new spatial entities, and
-    /// add more children to it.
+    /// Add a child [`Grid`] to this grid, and apply grid commands to it via the closure. This
+    /// allows you to insert bundles on the new grid, and add children to it. Returns the [`Entity`]
+    /// of the new grid.
     pub fn with_grid(
         &mut self,
         new_grid: Grid<P>,
         builder: impl FnOnce(&mut GridCommands<P>),
-    ) -> &mut Self {
-        builder(&mut self.spawn_grid(new_grid, ()));
-        self
+    ) -> Entity {
+        let mut grid = self.spawn_grid(new_grid, ());
+        builder(&mut grid);
+        grid.id()
     }
 
     /// Same as [`Self::with_grid`], but using the default [`Grid`] value.
-    pub fn with_grid_default(&mut self, builder: impl FnOnce(&mut GridCommands<P>)) -> &mut Self {
+    pub fn with_grid_default(&mut self, builder: impl FnOnce(&mut GridCommands<P>)) -> Entity {
         self.with_grid(Grid::default(), builder)
     }
 
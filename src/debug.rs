@@ -1,13 +1,14 @@
 //! Contains tools for debugging the floating origin.
 
-use std::marker::PhantomData;
+use std::{collections::VecDeque, fmt, marker::PhantomData};
 
-use crate::prelude::*;
+use crate::{hash::partition::GridPartitionMap, prelude::*};
 use bevy_app::prelude::*;
 use bevy_color::prelude::*;
-use bevy_ecs::prelude::*;
-use bevy_gizmos::prelude::*;
-use bevy_math::prelude::*;
+use bevy_ecs::{prelude::*, system::SystemParam};
+use bevy_gizmos::{config::DefaultGizmoConfigGroup, prelude::*};
+use bevy_hierarchy::prelude::*;
+use bevy_math::{prelude::*, DVec3, Isometry3d};
 use bevy_reflect::Reflect;
 use bevy_transform::prelude::*;
 
@@ -20,13 +21,350 @@ impl<P: GridPrecision> Plugin for FloatingOriginDebugPlugin<P> {
             .add_systems(Startup, setup_gizmos)
             .add_systems(
                 PostUpdate,
-                (update_debug_bounds::<P>, update_grid_axes::<P>)
+                (
+                    update_debug_bounds::<P>,
+                    update_grid_axes::<P>,
+                    update_hysteresis_bounds::<P>,
+                )
                     .chain()
                     .after(bevy_transform::TransformSystem::TransformPropagate),
             );
     }
 }
 
+/// This plugin will render the bounding box of each [`GridPartition`] near the floating origin,
+/// colored by its [`GridPartitionId`]. This makes it easy to see partitions form, merge, and split
+/// as entities move. Requires the [`GridPartitionPlugin`].
+pub struct GridPartitionDebugPlugin<P: GridPrecision> {
+    /// Partitions further than this many cells from the floating origin's cell, along any axis,
+    /// are not drawn.
+    pub max_distance: u64,
+    phantom: PhantomData<P>,
+}
+
+impl<P: GridPrecision> GridPartitionDebugPlugin<P> {
+    /// Only draw partitions within `max_distance` cells of the floating origin.
+    pub fn new(max_distance: u64) -> Self {
+        Self {
+            max_distance,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<P: GridPrecision> Default for GridPartitionDebugPlugin<P> {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+impl<P: GridPrecision> Plugin for GridPartitionDebugPlugin<P> {
+    fn build(&self, app: &mut App) {
+        let max_distance = self.max_distance;
+        app.init_gizmo_group::<BigSpaceGizmoConfig>().add_systems(
+            PostUpdate,
+            (move |gizmos: Gizmos<BigSpaceGizmoConfig>,
+                   grids: Query<&Grid<P>>,
+                   partitions: Res<GridPartitionMap<P>>| {
+                update_partition_bounds(gizmos, grids, partitions, max_distance);
+            })
+            .run_if(resource_exists::<GridPartitionMap<P>>)
+            .after(bevy_transform::TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// A [`SystemParam`] wrapping [`Gizmos`] to draw at high precision positions.
+///
+/// [`Gizmos`] are drawn in render space, relative to the floating origin. Converting a far away
+/// high precision position into an absolute single precision position first makes the gizmo jitter
+/// or disappear. Instead, each point is converted directly into render space with
+/// [`Grid::global_transform`], so gizmos are drawn correctly at any distance.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use big_space::prelude::*;
+/// # #[derive(Component)]
+/// # struct Target(Entity);
+/// fn draw_targeting_lines(
+///     mut gizmos: BigSpaceGizmos<i64>,
+///     grids: Grids<i64>,
+///     ships: Query<(Entity, &GridCell<i64>, &Transform, &Target)>,
+///     targets: Query<(&GridCell<i64>, &Transform)>,
+/// ) {
+///     for (ship, cell, transform, target) in &ships {
+///         let (Some(grid), Ok((target_cell, target_transform))) =
+///             (grids.parent_grid(ship), targets.get(target.0))
+///         else {
+///             continue;
+///         };
+///         gizmos.line(
+///             grid,
+///             cell,
+///             transform.translation,
+///             target_cell,
+///             target_transform.translation,
+///             Color::WHITE,
+///         );
+///     }
+/// }
+/// ```
+///
+/// All points passed to a single method must be in the same `grid`.
+#[derive(SystemParam)]
+pub struct BigSpaceGizmos<'w, 's, P, Config = DefaultGizmoConfigGroup>
+where
+    P: GridPrecision,
+    Config: GizmoConfigGroup,
+{
+    gizmos: Gizmos<'w, 's, Config>,
+    spooky: PhantomData<P>,
+}
+
+impl<'w, 's, P: GridPrecision, Config: GizmoConfigGroup> BigSpaceGizmos<'w, 's, P, Config> {
+    /// Convert a high precision position in the `grid` into render space.
+    fn render_space(grid: &Grid<P>, cell: &GridCell<P>, translation: Vec3) -> Vec3 {
+        grid.global_transform(cell, &Transform::from_translation(translation))
+            .translation()
+    }
+
+    /// Draw a line between two high precision positions in the `grid`.
+    pub fn line(
+        &mut self,
+        grid: &Grid<P>,
+        start_cell: &GridCell<P>,
+        start: Vec3,
+        end_cell: &GridCell<P>,
+        end: Vec3,
+        color: impl Into<Color>,
+    ) {
+        self.gizmos.line(
+            Self::render_space(grid, start_cell, start),
+            Self::render_space(grid, end_cell, end),
+            color,
+        );
+    }
+
+    /// Draw a line through a list of high precision positions in the `grid`.
+    pub fn linestrip(
+        &mut self,
+        grid: &Grid<P>,
+        points: impl IntoIterator<Item = (GridCell<P>, Vec3)>,
+        color: impl Into<Color>,
+    ) {
+        let points = points
+            .into_iter()
+            .map(|(cell, translation)| Self::render_space(grid, &cell, translation));
+        self.gizmos.linestrip(points, color);
+    }
+
+    /// Draw a wireframe sphere at a high precision position in the `grid`.
+    pub fn sphere(
+        &mut self,
+        grid: &Grid<P>,
+        cell: &GridCell<P>,
+        center: Vec3,
+        radius: f32,
+        color: impl Into<Color>,
+    ) {
+        let center = Self::render_space(grid, cell, center);
+        self.gizmos
+            .sphere(Isometry3d::from_translation(center), radius, color);
+    }
+
+    /// The wrapped [`Gizmos`], to draw in render space.
+    pub fn gizmos(&mut self) -> &mut Gizmos<'w, 's, Config> {
+        &mut self.gizmos
+    }
+}
+
+/// Records the path of the [`FloatingOrigin`] of every [`BigSpace`] into the
+/// [`FloatingOriginTrail`] resource, and draws it with gizmos if they are enabled.
+///
+/// This is useful for diagnosing precision drift over long sessions: the recorded positions are
+/// computed in double precision from the [`GridCell`] of the origin, so a path that wanders when
+/// the origin should be still, or that jumps when it changes cells, points to accumulating error.
+pub struct FloatingOriginTrailPlugin<P: GridPrecision> {
+    /// The maximum number of positions recorded for each big space. The oldest are dropped first.
+    pub capacity: usize,
+    phantom: PhantomData<P>,
+}
+
+impl<P: GridPrecision> FloatingOriginTrailPlugin<P> {
+    /// Record at most `capacity` positions of the floating origin of each big space.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<P: GridPrecision> Default for FloatingOriginTrailPlugin<P> {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl<P: GridPrecision> Plugin for FloatingOriginTrailPlugin<P> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(FloatingOriginTrail::<P> {
+            capacity: self.capacity,
+            trails: Default::default(),
+        })
+        .add_systems(
+            PostUpdate,
+            (
+                FloatingOriginTrail::<P>::record.after(FloatingOriginSystem::LocalFloatingOrigins),
+                FloatingOriginTrail::<P>::draw
+                    .run_if(resource_exists::<GizmoConfigStore>)
+                    .after(bevy_transform::TransformSystem::TransformPropagate),
+            ),
+        );
+    }
+}
+
+/// A position of the floating origin recorded by the [`FloatingOriginTrailPlugin`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrailPoint<P: GridPrecision> {
+    /// The [`GridCell`] of the floating origin, in its own [`Grid`].
+    pub cell: GridCell<P>,
+    /// The position of the floating origin relative to the origin of the root [`Grid`] of its
+    /// [`BigSpace`].
+    pub position: DVec3,
+}
+
+/// The recent path of the floating origin of each [`BigSpace`], recorded every frame by the
+/// [`FloatingOriginTrailPlugin`] in a bounded ring buffer.
+#[derive(Resource, Debug)]
+pub struct FloatingOriginTrail<P: GridPrecision> {
+    capacity: usize,
+    trails: bevy_utils::HashMap<Entity, VecDeque<TrailPoint<P>>>,
+}
+
+impl<P: GridPrecision> FloatingOriginTrail<P> {
+    /// The recorded positions of the floating origin of the big space with the `root` entity,
+    /// from oldest to newest.
+    pub fn history(&self, root: Entity) -> impl Iterator<Item = &TrailPoint<P>> {
+        self.trails.get(&root).into_iter().flatten()
+    }
+
+    /// The maximum number of positions recorded for each big space.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Forget all recorded positions.
+    pub fn clear(&mut self) {
+        self.trails.clear();
+    }
+
+    /// Record the position of the floating origin of every big space.
+    fn record(
+        mut trail: ResMut<Self>,
+        roots: Query<(Entity, &Grid<P>, &BigSpace)>,
+        origins: Query<(&GridCell<P>, &Transform)>,
+    ) {
+        let capacity = trail.capacity;
+        trail.trails.retain(|root, _| roots.contains(*root));
+        for (root, grid, space) in &roots {
+            let Some((cell, transform)) = space
+                .floating_origin
+                .and_then(|origin| origins.get(origin).ok())
+            else {
+                continue;
+            };
+            // The local floating origin is the origin of the floating origin's cell, as seen from
+            // the root grid. The floating origin is offset from it by its transform.
+            let local_origin = grid.local_floating_origin();
+            let position = grid.grid_position_double(
+                &local_origin.cell(),
+                &Transform::from_translation(local_origin.translation()),
+            ) + local_origin.rotation() * transform.translation.as_dvec3();
+            let history = trail.trails.entry(root).or_default();
+            if history.len() >= capacity {
+                history.pop_front();
+            }
+            if capacity > 0 {
+                history.push_back(TrailPoint {
+                    cell: *cell,
+                    position,
+                });
+            }
+        }
+    }
+
+    /// Draw the recorded path of each floating origin.
+    fn draw(
+        mut gizmos: BigSpaceGizmos<P>,
+        trail: Res<Self>,
+        roots: Query<&Grid<P>, With<BigSpace>>,
+    ) {
+        for (root, history) in &trail.trails {
+            let Ok(grid) = roots.get(*root) else {
+                continue;
+            };
+            // Split into cells in double precision, to draw precisely even far from the origin of
+            // the grid.
+            let points = history
+                .iter()
+                .map(|point| grid.translation_to_grid(point.position));
+            gizmos.linestrip(grid, points, Color::linear_rgb(1.0, 0.5, 0.0));
+        }
+    }
+}
+
+/// Draw the bounds of partitions near the floating origin.
+fn update_partition_bounds<P: GridPrecision>(
+    mut gizmos: Gizmos<BigSpaceGizmoConfig>,
+    grids: Query<&Grid<P>>,
+    partitions: Res<GridPartitionMap<P>>,
+    max_distance: u64,
+) {
+    // The distance from the origin cell to the nearest cell in the box from `min` to `max`.
+    let distance = |min: GridCell<P>, max: GridCell<P>, origin: GridCell<P>| {
+        [
+            (min.x.as_i128(), max.x.as_i128(), origin.x.as_i128()),
+            (min.y.as_i128(), max.y.as_i128(), origin.y.as_i128()),
+            (min.z.as_i128(), max.z.as_i128(), origin.z.as_i128()),
+        ]
+        .into_iter()
+        .map(|(min, max, origin)| (min - origin).max(origin - max).max(0))
+        .max()
+        .unwrap_or_default()
+    };
+
+    for (id, partition) in partitions.iter() {
+        let Ok(grid) = grids.get(partition.grid()) else {
+            continue;
+        };
+        let origin = grid.local_floating_origin().cell();
+        // Partitions are connected, so no cell is further than the number of cells from any other
+        // cell. Skip far partitions using any one cell, before visiting every cell for the bounds.
+        let Some(cell) = partition.iter().next().map(GridHash::cell) else {
+            continue;
+        };
+        let reach = max_distance as i128 + partition.num_cells() as i128;
+        if distance(cell, cell, origin) > reach {
+            continue;
+        }
+        let (min, max) = partition.cell_bounds();
+        if distance(min, max, origin) > max_distance as i128 {
+            continue;
+        }
+
+        let size = grid.cell_to_float(&(max - min));
+        let transform = Transform::from_translation((size / 2.0).as_vec3())
+            .with_scale(size.as_vec3() + grid.cell_edge_length());
+        // Spread the hues of consecutive ids using the golden angle.
+        let hue = (id.id() as f32 * 137.507_77) % 360.0;
+        gizmos.cuboid(
+            grid.global_transform(&min, &transform),
+            Color::hsl(hue, 0.9, 0.6),
+        );
+    }
+}
+
 fn setup_gizmos(mut store: ResMut<GizmoConfigStore>) {
     let (config, _) = store.config_mut::<BigSpaceGizmoConfig>();
     config.line_perspective = false;
@@ -46,7 +384,7 @@ fn update_debug_bounds<P: GridPrecision>(
         };
         let transform = grid.global_transform(
             cell,
-            &Transform::from_scale(Vec3::splat(grid.cell_edge_length() * 0.999)),
+            &Transform::from_scale(grid.cell_edge_length() * 0.999),
         );
         if origin.is_none() {
             gizmos.cuboid(transform, Color::linear_rgb(0.0, 1.0, 0.0))
@@ -56,11 +394,51 @@ fn update_debug_bounds<P: GridPrecision>(
     }
 }
 
+/// The gizmo config group used by the [`FloatingOriginDebugPlugin`]. Use the
+/// [`GizmoConfigStore`] to change how the debug gizmos are drawn, or to toggle them.
 #[derive(Default, Reflect)]
-struct BigSpaceGizmoConfig;
+pub struct BigSpaceGizmoConfig {
+    /// When set to the entity of a [`Grid`], the recentering hysteresis of every occupied cell in
+    /// that grid is drawn: the boundary of the cell, and the larger box around the cell center
+    /// that entities can move within before they are recentered into a new cell, set by
+    /// [`Grid::maximum_distance_from_origin`]. Useful for tuning the switching threshold of a
+    /// grid. Disabled by default.
+    pub hysteresis_grid: Option<Entity>,
+}
 
 impl GizmoConfigGroup for BigSpaceGizmoConfig {}
 
+/// Draw the recentering hysteresis of occupied cells in the [`BigSpaceGizmoConfig`]'s grid.
+fn update_hysteresis_bounds<P: GridPrecision>(
+    mut gizmos: Gizmos<BigSpaceGizmoConfig>,
+    grids: Query<(&Grid<P>, &Children)>,
+    cells: Query<&GridCell<P>>,
+    mut drawn: Local<bevy_utils::HashSet<GridCell<P>>>,
+) {
+    let Some(grid_entity) = gizmos.config_ext.hysteresis_grid else {
+        return;
+    };
+    let Ok((grid, children)) = grids.get(grid_entity) else {
+        return;
+    };
+    drawn.clear();
+    for cell in cells.iter_many(children) {
+        if !drawn.insert(*cell) {
+            continue;
+        }
+        let cell_bounds = Transform::from_scale(grid.cell_edge_length());
+        let dead_zone = Transform::from_scale(grid.maximum_distance_from_origin() * 2.0);
+        gizmos.cuboid(
+            grid.global_transform(cell, &cell_bounds),
+            Color::linear_rgb(1.0, 1.0, 0.0),
+        );
+        gizmos.cuboid(
+            grid.global_transform(cell, &dead_zone),
+            Color::linear_rgb(1.0, 0.0, 1.0),
+        );
+    }
+}
+
 /// Draw axes for grids.
 fn update_grid_axes<P: GridPrecision>(
     mut gizmos: Gizmos<BigSpaceGizmoConfig>,
@@ -69,7 +447,7 @@ fn update_grid_axes<P: GridPrecision>(
     for (transform, grid) in grids.iter() {
         let start = transform.translation();
         // Scale with distance
-        let len = (start.length().powf(0.9)).max(grid.cell_edge_length()) * 0.5;
+        let len = (start.length().powf(0.9)).max(grid.cell_edge_length().max_element()) * 0.5;
         gizmos.ray(
             start,
             transform.right() * len,
@@ -87,3 +465,210 @@ fn update_grid_axes<P: GridPrecision>(
         );
     }
 }
+
+/// A summary of the size of every [`BigSpace`] in the world, useful for understanding the shape of
+/// a loaded world while profiling. Use [`BigSpaceReport::new`] to build a report, or add the
+/// [`BigSpaceReport::log`] system to print one, e.g. when a key is pressed.
+///
+/// The report is printable with [`Display`](fmt::Display).
+#[derive(Debug, Clone, Default)]
+pub struct BigSpaceReport {
+    /// A summary of each [`BigSpace`] in the world.
+    pub big_spaces: Vec<BigSpaceSummary>,
+    /// The approximate heap memory used by the [`GridHashMap`], in bytes, if it exists.
+    pub hash_map_bytes: Option<usize>,
+}
+
+/// The counts for a single [`BigSpace`] in a [`BigSpaceReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigSpaceSummary {
+    /// The root entity of the [`BigSpace`].
+    pub root: Entity,
+    /// The number of [`Grid`]s in the big space, including the root.
+    pub grids: usize,
+    /// The number of high precision entities, with a [`GridCell`], in the big space.
+    pub spatial_entities: usize,
+    /// The number of cells in the [`GridHashMap`] that contain at least one entity.
+    pub occupied_cells: usize,
+    /// The number of partitions in the [`GridPartitionMap`].
+    pub partitions: usize,
+}
+
+impl BigSpaceReport {
+    /// Build a report for all [`BigSpace`]s in the `world`. Occupied cells and partitions are read
+    /// from the [`GridHashMap<P>`] and [`GridPartitionMap<P>`], and are zero if those resources do
+    /// not exist.
+    pub fn new<P: GridPrecision>(world: &mut World) -> Self {
+        let mut big_spaces: Vec<BigSpaceSummary> = world
+            .query_filtered::<Entity, With<BigSpace>>()
+            .iter(world)
+            .map(|root| BigSpaceSummary {
+                root,
+                grids: 0,
+                spatial_entities: 0,
+                occupied_cells: 0,
+                partitions: 0,
+            })
+            .collect();
+        big_spaces.sort_by_key(|summary| summary.root);
+
+        let root_of = |world: &World, mut entity: Entity| loop {
+            if world.get::<BigSpace>(entity).is_some() {
+                return big_spaces.binary_search_by_key(&entity, |s| s.root).ok();
+            }
+            entity = world.get::<Parent>(entity)?.get();
+        };
+
+        let mut counts = vec![(0, 0, 0, 0); big_spaces.len()];
+        for entity in world.query_filtered::<Entity, With<Grid<P>>>().iter(world) {
+            if let Some(i) = root_of(world, entity) {
+                counts[i].0 += 1;
+            }
+        }
+        for entity in world
+            .query_filtered::<Entity, With<GridCell<P>>>()
+            .iter(world)
+        {
+            if let Some(i) = root_of(world, entity) {
+                counts[i].1 += 1;
+            }
+        }
+        if let Some(map) = world.get_resource::<GridHashMap<P>>() {
+            for hash in map.all_entries().map(|(hash, _)| hash) {
+                if let Some(i) = root_of(world, hash.grid()) {
+                    counts[i].2 += 1;
+                }
+            }
+        }
+        if let Some(partitions) = world.get_resource::<GridPartitionMap<P>>() {
+            for (_, partition) in partitions.iter() {
+                if let Some(i) = root_of(world, partition.grid()) {
+                    counts[i].3 += 1;
+                }
+            }
+        }
+
+        for (summary, (grids, spatial_entities, occupied_cells, partitions)) in
+            big_spaces.iter_mut().zip(counts)
+        {
+            summary.grids = grids;
+            summary.spatial_entities = spatial_entities;
+            summary.occupied_cells = occupied_cells;
+            summary.partitions = partitions;
+        }
+
+        Self {
+            big_spaces,
+            hash_map_bytes: world
+                .get_resource::<GridHashMap<P>>()
+                .map(GridHashMap::approximate_heap_size),
+        }
+    }
+
+    /// An exclusive system that logs a [`BigSpaceReport`].
+    pub fn log<P: GridPrecision>(world: &mut World) {
+        tracing::info!("\n{}", Self::new::<P>(world));
+    }
+}
+
+impl fmt::Display for BigSpaceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for summary in &self.big_spaces {
+            writeln!(
+                f,
+                "BigSpace {}: {} grids, {} spatial entities, {} occupied cells, {} partitions",
+                summary.root,
+                summary.grids,
+                summary.spatial_entities,
+                summary.occupied_cells,
+                summary.partitions,
+            )?;
+        }
+        match self.hash_map_bytes {
+            Some(bytes) => write!(f, "GridHashMap: ~{:.1} KiB", bytes as f64 / 1024.0),
+            None => write!(f, "GridHashMap: not present"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report() {
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+            GridPartitionPlugin::<i32>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                root.spawn_spatial(FloatingOrigin);
+                root.spawn_spatial(GridCell::<i32>::new(1, 0, 0));
+                root.spawn_spatial(GridCell::<i32>::new(10, 0, 0));
+                root.with_grid_default(|grid| {
+                    grid.spawn_spatial(GridCell::<i32>::new(10, 0, 0));
+                });
+            });
+            commands.spawn_big_space_default::<i32>(|root| {
+                root.spawn_spatial(FloatingOrigin);
+            });
+        });
+        app.update();
+
+        let report = BigSpaceReport::new::<i32>(app.world_mut());
+        let mut summaries = report.big_spaces.clone();
+        summaries.sort_by_key(|summary| summary.spatial_entities);
+        let counts: Vec<_> = summaries
+            .iter()
+            .map(|s| (s.grids, s.spatial_entities, s.occupied_cells, s.partitions))
+            .collect();
+        // The child grid is itself a spatial entity in the root grid.
+        assert_eq!(counts, vec![(1, 1, 1, 1), (2, 5, 4, 3)]);
+        assert!(report.hash_map_bytes.unwrap() > 0);
+        assert!(report.to_string().contains("5 spatial entities"));
+    }
+
+    #[test]
+    fn floating_origin_trail() {
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i64>::default(),
+            FloatingOriginTrailPlugin::<i64>::new(3),
+        ));
+        let mut origin = Entity::PLACEHOLDER;
+        let root = app.world_mut().commands().spawn_big_space::<i64>(
+            Grid::new_uniform(10.0, 0.0),
+            |root| {
+                origin = root
+                    .spawn_spatial((
+                        FloatingOrigin,
+                        GridCell::<i64>::new(1_000_000_000_000, 0, 0),
+                    ))
+                    .id();
+            },
+        );
+        for _ in 0..5 {
+            app.update();
+            app.world_mut()
+                .get_mut::<Transform>(origin)
+                .unwrap()
+                .translation
+                .x += 4.0;
+        }
+
+        let trail = app.world().resource::<FloatingOriginTrail<i64>>();
+        let history: Vec<_> = trail.history(root).copied().collect();
+        assert_eq!(history.len(), 3);
+        // The origin moved 4 units every frame, and was recentered into the next cell.
+        let start = DVec3::X * 1e13;
+        let positions: Vec<_> = history.iter().map(|point| point.position - start).collect();
+        assert_eq!(
+            positions,
+            [8.0, 12.0, 16.0].map(|x| DVec3::new(x, 0.0, 0.0))
+        );
+        assert_eq!(history[2].cell, GridCell::new(1_000_000_000_002, 0, 0));
+    }
+}
@@ -1,6 +1,6 @@
 //! A floating origin for camera-relative rendering, to maximize precision when converting to f32.
 
-use bevy_ecs::prelude::*;
+use bevy_ecs::{prelude::*, system::SystemParam};
 use bevy_hierarchy::prelude::*;
 use bevy_reflect::prelude::*;
 use bevy_utils::HashMap;
@@ -20,6 +20,17 @@ use bevy_utils::HashMap;
 #[reflect(Component)]
 pub struct FloatingOrigin;
 
+/// Marks a high precision entity as the preferred [`FloatingOrigin`] of its [`BigSpace`] when
+/// automatic assignment is enabled with
+/// [`BigSpacePlugin::with_auto_floating_origin`](crate::plugin::BigSpacePlugin::with_auto_floating_origin).
+///
+/// If a big space with no floating origin contains exactly one entity with this marker, it is
+/// used, even if the big space contains many cameras. This is useful to pick the primary camera,
+/// or to use an entity that is not a camera, like the player, as the floating origin.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct FloatingOriginCandidate;
+
 /// A "big space" is a hierarchy of high precision [`Grid`](crate::Grid)s, rendered relative to a
 /// [`FloatingOrigin`]. This component marks the root of a high precision hierarchy, and tracks the
 /// [`FloatingOrigin`] inside this hierarchy.
@@ -33,6 +44,11 @@ pub struct FloatingOrigin;
 /// [`GlobalTransform`](bevy_transform::components::GlobalTransform) of all spatial entities within
 /// that [`BigSpace`]. This is needed for features like split screen, where you may need to render
 /// the world from viewpoints that are very far from each other.
+///
+/// An entity can only be in a single big space, so it only has a single `GlobalTransform`, computed
+/// relative to the floating origin of that big space. To render the same object in multiple big
+/// spaces, spawn a copy in each of them with a [`MirrorPosition`](crate::mirror::MirrorPosition) to
+/// keep it in the same position as the original.
 #[derive(Debug, Default, Component, Reflect)]
 #[reflect(Component)]
 // We do not require Grid, because we want more control over when the grid is inserted, especially
@@ -102,4 +118,203 @@ impl BigSpace {
             tracing::error!("BigSpace {space:#} has no floating origins. There must be exactly one. Transform propagation will not work until there is a FloatingOrigin in the hierarchy.",)
         }
     }
+
+    /// Inserts a [`FloatingOrigin`] on the camera in a [`BigSpace`] that has no floating origin, if
+    /// it contains exactly one high precision entity with a
+    /// [`Camera`](bevy_render::camera::Camera). Enable this with
+    /// [`BigSpacePlugin::with_auto_floating_origin`](crate::plugin::BigSpacePlugin::with_auto_floating_origin).
+    ///
+    /// If the big space contains entities marked with [`FloatingOriginCandidate`], only those are
+    /// considered instead of cameras. Nothing is assigned when the choice is ambiguous.
+    ///
+    /// If another [`FloatingOrigin`] is later added to the same [`BigSpace`], the automatically
+    /// assigned one is removed, so the origin set by the user always takes priority.
+    #[cfg(feature = "bevy_render")]
+    pub fn assign_camera_floating_origin(
+        mut commands: Commands,
+        mut auto_assigned: Local<bevy_utils::HashSet<Entity>>,
+        floating_origins: Query<Entity, With<FloatingOrigin>>,
+        candidates: Query<
+            (Entity, Has<FloatingOriginCandidate>),
+            (
+                Or<(
+                    With<bevy_render::camera::Camera>,
+                    With<FloatingOriginCandidate>,
+                )>,
+                With<crate::GridCellAny>,
+            ),
+        >,
+        parent_query: Query<&Parent>,
+        big_spaces: Query<(), With<BigSpace>>,
+    ) {
+        let root_of = |entity| {
+            parent_query
+                .iter_ancestors(entity)
+                .last()
+                .filter(|root| big_spaces.contains(*root))
+        };
+
+        auto_assigned.retain(|entity| floating_origins.contains(*entity));
+
+        let mut origins: HashMap<Entity, Vec<Entity>> = HashMap::new();
+        for origin in &floating_origins {
+            if let Some(root) = root_of(origin) {
+                origins.entry(root).or_default().push(origin);
+            }
+        }
+        for space_origins in origins.values().filter(|origins| origins.len() > 1) {
+            for origin in space_origins {
+                if !auto_assigned.remove(origin) {
+                    continue;
+                }
+                tracing::info!("Removing the automatically assigned FloatingOrigin from camera {origin}, because another FloatingOrigin was added to its BigSpace.");
+                commands.entity(*origin).remove::<FloatingOrigin>();
+            }
+        }
+
+        let mut space_candidates: HashMap<Entity, Vec<(Entity, bool)>> = HashMap::new();
+        for (candidate, marked) in &candidates {
+            if let Some(root) = root_of(candidate).filter(|root| !origins.contains_key(root)) {
+                space_candidates
+                    .entry(root)
+                    .or_default()
+                    .push((candidate, marked));
+            }
+        }
+        for (root, mut space_candidates) in space_candidates {
+            if space_candidates.iter().any(|(_, marked)| *marked) {
+                space_candidates.retain(|(_, marked)| *marked);
+            }
+            let [(camera, _)] = space_candidates[..] else {
+                continue;
+            };
+            tracing::info!("BigSpace {root} has no FloatingOrigin, automatically using {camera} as the floating origin.");
+            commands.entity(camera).insert(FloatingOrigin);
+            auto_assigned.insert(camera);
+        }
+    }
+}
+
+/// A system param for finding every [`BigSpace`] in the world, and its [`FloatingOrigin`].
+///
+/// ```
+/// # use big_space::prelude::*;
+/// # use bevy::prelude::*;
+/// fn log_origins(big_spaces: BigSpaces) {
+///     for (root, origin) in big_spaces.iter() {
+///         info!("BigSpace {root} has the floating origin {origin:?}");
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct BigSpaces<'w, 's> {
+    big_spaces: Query<'w, 's, (Entity, &'static BigSpace)>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+
+impl BigSpaces<'_, '_> {
+    /// Iterate over the root entities of all [`BigSpace`]s.
+    pub fn roots(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.big_spaces.iter().map(|(root, _)| root)
+    }
+
+    /// Get the [`FloatingOrigin`] of the [`BigSpace`] with the `root` entity. Returns `None` if
+    /// `root` is not a big space, or if it does not have exactly one floating origin in its
+    /// hierarchy.
+    ///
+    /// This is the same floating origin used for transform propagation, found by
+    /// [`BigSpace::find_floating_origin`], so it is only updated once per frame.
+    pub fn floating_origin(&self, root: Entity) -> Option<Entity> {
+        let (_, space) = self.big_spaces.get(root).ok()?;
+        space.validate_floating_origin(root, &self.parents)
+    }
+
+    /// Iterate over the root entity of every [`BigSpace`], paired with its [`FloatingOrigin`]. See
+    /// [`Self::floating_origin`].
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Option<Entity>)> + '_ {
+        self.roots().map(|root| (root, self.floating_origin(root)))
+    }
+}
+
+#[cfg(all(test, feature = "bevy_render"))]
+mod tests {
+    use crate::prelude::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn auto_assign_camera_origin() {
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default().with_auto_floating_origin(true));
+
+        let root = app
+            .world_mut()
+            .spawn(BigSpaceRootBundle::<i32>::default())
+            .id();
+        let camera = app
+            .world_mut()
+            .spawn((Camera3d::default(), GridCell::<i32>::ZERO))
+            .set_parent(root)
+            .id();
+
+        app.update();
+        assert!(app.world().get::<FloatingOrigin>(camera).is_some());
+        assert_eq!(
+            app.world().get::<BigSpace>(root).unwrap().floating_origin,
+            Some(camera)
+        );
+
+        let user_origin = app
+            .world_mut()
+            .spawn((FloatingOrigin, GridCell::<i32>::ZERO))
+            .set_parent(root)
+            .id();
+
+        app.update();
+        app.update();
+        assert!(app.world().get::<FloatingOrigin>(camera).is_none());
+        assert_eq!(
+            app.world().get::<BigSpace>(root).unwrap().floating_origin,
+            Some(user_origin)
+        );
+    }
+
+    #[test]
+    fn auto_assign_ambiguous() {
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default().with_auto_floating_origin(true));
+
+        let root = app
+            .world_mut()
+            .spawn(BigSpaceRootBundle::<i32>::default())
+            .id();
+        let spawn_camera = |app: &mut App| {
+            app.world_mut()
+                .spawn((Camera3d::default(), GridCell::<i32>::ZERO))
+                .set_parent(root)
+                .id()
+        };
+        let first = spawn_camera(&mut app);
+        let second = spawn_camera(&mut app);
+        // Apply the commands queued by the `GridCell` hooks.
+        app.world_mut().flush();
+
+        app.update();
+        assert!(app.world().get::<FloatingOrigin>(first).is_none());
+        assert!(app.world().get::<FloatingOrigin>(second).is_none());
+
+        // A marker resolves the ambiguity, and does not need to be a camera.
+        let player = app
+            .world_mut()
+            .spawn((FloatingOriginCandidate, GridCell::<i32>::ZERO))
+            .set_parent(root)
+            .id();
+        app.update();
+        app.update();
+        assert!(app.world().get::<FloatingOrigin>(first).is_none());
+        assert!(app.world().get::<FloatingOrigin>(second).is_none());
+        assert_eq!(
+            app.world().get::<BigSpace>(root).unwrap().floating_origin,
+            Some(player)
+        );
+    }
 }
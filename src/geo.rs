@@ -0,0 +1,104 @@
+//! Conversions between geographic coordinates (latitude, longitude, altitude) and grid positions.
+//!
+//! ## Model
+//!
+//! These helpers use a *spherical* model of the planet, not an ellipsoid like WGS84. Geographic
+//! coordinates are first converted into an earth-centered, earth-fixed (ECEF) position in double
+//! precision, which is then split into a [`GridCell`] and [`Transform`] with
+//! [`Grid::translation_to_grid`].
+//!
+//! The ECEF frame used here follows the usual convention:
+//!
+//! - `+X` points from the center of the planet to latitude 0°, longitude 0°.
+//! - `+Y` points from the center of the planet to latitude 0°, longitude 90°.
+//! - `+Z` points from the center of the planet to the north pole.
+//!
+//! Note that this is *not* the same as bevy's Y-up convention. If your planet's grid is Y-up, you
+//! will need to rotate the planet's grid or the returned positions accordingly.
+//!
+//! ## Accuracy
+//!
+//! The earth is an oblate spheroid; its equatorial radius is ~21 km larger than its polar radius. A
+//! sphere cannot represent both, so depending on the latitude and the chosen radius, altitudes can
+//! be off by 10-20 km, and latitudes by up to ~0.2° when compared to a WGS84 conversion. This is
+//! fine for visualization and gameplay, but not for surveying or navigation.
+//!
+//! The conversion itself is computed in `f64`, so the position of a point on the surface of an
+//! earth-sized planet is precise to roughly a nanometer before being converted into a grid cell.
+
+use crate::prelude::*;
+use bevy_math::DVec3;
+use bevy_transform::prelude::*;
+
+/// Convert a geographic position into a [`GridCell`] and [`Transform`] within `grid`, using a
+/// spherical planet model centered at the origin of `grid`. See the [module docs](self).
+///
+/// - `latitude` and `longitude` are in degrees.
+/// - `altitude` is the height above the surface of the sphere, in the same units as `radius`.
+/// - `radius` is the radius of the sphere.
+pub fn geo_to_grid<P: GridPrecision>(
+    latitude: f64,
+    longitude: f64,
+    altitude: f64,
+    radius: f64,
+    grid: &Grid<P>,
+) -> (GridCell<P>, Transform) {
+    let (cell, translation) =
+        grid.translation_to_grid(geo_to_ecef(latitude, longitude, altitude, radius));
+    (cell, Transform::from_translation(translation))
+}
+
+/// Convert a [`GridCell`] and [`Transform`] within `grid` into a geographic position, using a
+/// spherical planet model centered at the origin of `grid`. This is the inverse of
+/// [`geo_to_grid`].
+///
+/// Returns `(latitude, longitude, altitude)`, with the latitude and longitude in degrees, and the
+/// altitude in the same units as `radius`.
+pub fn grid_to_geo<P: GridPrecision>(
+    cell: &GridCell<P>,
+    transform: &Transform,
+    radius: f64,
+    grid: &Grid<P>,
+) -> (f64, f64, f64) {
+    ecef_to_geo(grid.grid_position_double(cell, transform), radius)
+}
+
+/// Convert a geographic position into an earth-centered, earth-fixed position on a sphere. See the
+/// [module docs](self).
+pub fn geo_to_ecef(latitude: f64, longitude: f64, altitude: f64, radius: f64) -> DVec3 {
+    let (sin_lat, cos_lat) = latitude.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = longitude.to_radians().sin_cos();
+    let r = radius + altitude;
+    DVec3::new(r * cos_lat * cos_lon, r * cos_lat * sin_lon, r * sin_lat)
+}
+
+/// Convert an earth-centered, earth-fixed position on a sphere into a geographic position. See the
+/// [module docs](self).
+///
+/// Returns `(latitude, longitude, altitude)`, with the latitude and longitude in degrees.
+pub fn ecef_to_geo(position: DVec3, radius: f64) -> (f64, f64, f64) {
+    let r = position.length();
+    let horizontal = position.x.hypot(position.y);
+    let latitude = position.z.atan2(horizontal).to_degrees();
+    let longitude = position.y.atan2(position.x).to_degrees();
+    (latitude, longitude, r - radius)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geo_round_trip() {
+        const EARTH_RADIUS_M: f64 = 6_371_000.0;
+        let grid = Grid::<i64>::new_uniform(10_000.0, 0.0);
+
+        let (lat, lon, alt) = (47.6062, -122.3321, 56.0);
+        let (cell, transform) = geo_to_grid(lat, lon, alt, EARTH_RADIUS_M, &grid);
+        let (lat_2, lon_2, alt_2) = grid_to_geo(&cell, &transform, EARTH_RADIUS_M, &grid);
+
+        assert!((lat - lat_2).abs() < 1e-7);
+        assert!((lon - lon_2).abs() < 1e-7);
+        assert!((alt - alt_2).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,179 @@
+//! Limits the cells that entities can occupy in a [`Grid`], for worlds with a fixed size.
+
+use crate::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::prelude::*;
+use bevy_math::prelude::*;
+use bevy_reflect::prelude::*;
+use bevy_transform::prelude::*;
+
+/// Add this to an entity with a [`Grid`] to limit the cells its children can occupy to the box
+/// between [`Self::min`] and [`Self::max`], inclusive.
+///
+/// When a child's [`GridCell`] changes to a cell outside of the bounds, an [`OutOfGridBounds`]
+/// event is sent, and the entity is handled according to the [`GridBoundsMode`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct GridBounds<P: GridPrecision> {
+    /// The minimum cell, inclusive.
+    pub min: GridCell<P>,
+    /// The maximum cell, inclusive.
+    pub max: GridCell<P>,
+    /// What to do with entities that move out of bounds.
+    pub mode: GridBoundsMode,
+}
+
+/// How [`GridBounds`] handle entities that move outside of the bounds.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum GridBoundsMode {
+    /// Move the entity back into the nearest cell inside the bounds, stopping it at the boundary
+    /// cell.
+    #[default]
+    Clamp,
+    /// Leave the entity where it is. Use the [`OutOfGridBounds`] event to handle it, for example,
+    /// by despawning it.
+    Reject,
+}
+
+/// Sent when an entity's [`GridCell`] is changed to a cell outside of its grid's [`GridBounds`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfGridBounds(pub Entity);
+
+impl<P: GridPrecision> GridBounds<P> {
+    /// Create new bounds between the `a` and `b` corners, inclusive.
+    pub fn new(a: GridCell<P>, b: GridCell<P>, mode: GridBoundsMode) -> Self {
+        Self {
+            min: GridCell::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: GridCell::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+            mode,
+        }
+    }
+
+    /// Returns `true` if the `cell` is inside these bounds.
+    pub fn contains(&self, cell: &GridCell<P>) -> bool {
+        (self.min.x..=self.max.x).contains(&cell.x)
+            && (self.min.y..=self.max.y).contains(&cell.y)
+            && (self.min.z..=self.max.z).contains(&cell.z)
+    }
+
+    /// The cell inside these bounds that is nearest to `cell`.
+    pub fn clamp(&self, cell: &GridCell<P>) -> GridCell<P> {
+        GridCell::new(
+            cell.x.clamp(self.min.x, self.max.x),
+            cell.y.clamp(self.min.y, self.max.y),
+            cell.z.clamp(self.min.z, self.max.z),
+        )
+    }
+
+    /// Enforce the [`GridBounds`] of every grid on the children whose [`GridCell`] changed.
+    ///
+    /// The [`BigSpacePlugin`] only runs this system while a [`GridBounds`] exists.
+    pub fn enforce(
+        grids: Query<(&GridBounds<P>, &Grid<P>)>,
+        mut changed: Query<
+            (Entity, &mut GridCell<P>, &mut Transform, &Parent),
+            Changed<GridCell<P>>,
+        >,
+        mut out_of_bounds: EventWriter<OutOfGridBounds>,
+    ) {
+        for (entity, mut cell, mut transform, parent) in &mut changed {
+            let Ok((bounds, grid)) = grids.get(parent.get()) else {
+                continue;
+            };
+            if bounds.contains(&cell) {
+                continue;
+            }
+            out_of_bounds.send(OutOfGridBounds(entity));
+            if bounds.mode == GridBoundsMode::Reject {
+                continue;
+            }
+
+            let clamped = bounds.clamp(&cell);
+            let moved = BVec3::new(
+                clamped.x != cell.x,
+                clamped.y != cell.y,
+                clamped.z != cell.z,
+            );
+            let offset = grid.cell_to_float(&(*cell - clamped)).as_vec3() + transform.translation;
+            let half_cell = grid.cell_edge_length() / 2.0;
+            transform.translation = Vec3::select(
+                moved,
+                offset.clamp(-half_cell, half_cell),
+                transform.translation,
+            );
+            *cell = clamped;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn clamp_and_reject() {
+        #[derive(Resource)]
+        struct Entities {
+            clamped: Entity,
+            rejecting_grid: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i16>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space::<i16>(Grid::new_uniform(10.0, 0.0), |root| {
+                    root.insert(GridBounds::<i16>::new(
+                        GridCell::new(2, 2, 2),
+                        GridCell::new(-2, -2, -2),
+                        GridBoundsMode::Clamp,
+                    ));
+                    root.spawn_spatial(FloatingOrigin);
+                    let clamped = root.spawn_spatial(GridCell::<i16>::new(2, 0, 0)).id();
+                    let mut rejecting = root.spawn_grid(Grid::new_uniform(10.0, 0.0), ());
+                    rejecting.insert(GridBounds::<i16>::new(
+                        GridCell::ZERO,
+                        GridCell::ZERO,
+                        GridBoundsMode::Reject,
+                    ));
+                    let rejecting_grid = rejecting.id();
+                    drop(rejecting);
+                    root.commands().insert_resource(Entities {
+                        clamped,
+                        rejecting_grid,
+                    });
+                });
+            });
+        app.update();
+
+        let entities = app.world().resource::<Entities>();
+        let (clamped, rejecting_grid) = (entities.clamped, entities.rejecting_grid);
+        let flagged = |app: &App| -> Vec<Entity> {
+            let events = app.world().resource::<Events<OutOfGridBounds>>();
+            events.get_cursor().read(events).map(|e| e.0).collect()
+        };
+
+        // Walk into the wall, the entity should stop at the edge of the boundary cell.
+        for _ in 0..4 {
+            let mut transform = app.world_mut().get_mut::<Transform>(clamped).unwrap();
+            transform.translation.x += 8.0;
+            app.update();
+        }
+        let cell = *app.world().get::<GridCell<i16>>(clamped).unwrap();
+        let transform = app.world().get::<Transform>(clamped).unwrap();
+        assert_eq!(cell, GridCell::new(2, 0, 0));
+        assert_eq!(transform.translation.x, 5.0);
+        assert!(flagged(&app).contains(&clamped));
+
+        // Rejected entities are left in place, and flagged.
+        let rejected = app
+            .world_mut()
+            .spawn((GridCell::<i16>::new(0, 3, 0), Transform::default()))
+            .set_parent(rejecting_grid)
+            .id();
+        app.update();
+        let cell = *app.world().get::<GridCell<i16>>(rejected).unwrap();
+        assert_eq!(cell, GridCell::new(0, 3, 0));
+        assert!(flagged(&app).contains(&rejected));
+    }
+}
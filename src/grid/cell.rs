@@ -6,7 +6,9 @@ use bevy_hierarchy::prelude::*;
 use bevy_math::{DVec3, IVec3};
 use bevy_reflect::prelude::*;
 use bevy_transform::prelude::*;
-use bevy_utils::Instant;
+use bevy_utils::{Instant, Parallel};
+
+use super::propagation::ChangedGridEntities;
 
 /// Marks entities with any generic [`GridCell`] component. Allows you to query for high precision
 /// spatial entities of any [`GridPrecision`].
@@ -36,6 +38,24 @@ pub struct GridCellAny;
 ///
 /// [`BigSpace`]s are only allowed to have a single type of `GridCell`, you cannot mix
 /// [`GridPrecision`]s.
+///
+/// ## Ordering
+///
+/// Cells are ordered lexicographically, by `x`, then `y`, then `z`, so they can be used as keys in
+/// ordered collections like a `BTreeMap`. This ordering is not spatially meaningful: cells that
+/// are next to each other in this order may be far apart. See
+/// [`GridHashMap::entries_z_order`](crate::hash::map::GridHashMap::entries_z_order) for an order
+/// with better locality.
+///
+/// ## Serialization
+///
+/// With the `serde` feature enabled, a [`GridCell`] is serialized as a compact sequence of its
+/// three indices, `[x, y, z]`. This is the format to use for save files and network messages,
+/// where the cell of every entity is written.
+///
+/// Reflection-based serialization, used by bevy scenes, does not use this implementation, and
+/// writes the struct form `(x: 1, y: 2, z: 3)` instead. Both forms can only be read back by the
+/// same path that wrote them.
 #[derive(Component, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Reflect)]
 #[reflect(Component, Default, PartialEq)]
 #[require(Transform, GlobalTransform)]
@@ -78,47 +98,116 @@ impl<P: GridPrecision> GridCell<P> {
         z: P::ONE,
     };
 
+    /// Iterate over every cell in the box between the `min` and `max` corners, inclusive.
+    ///
+    /// If `min` is larger than `max` along an axis, the two are swapped for that axis. Cells are
+    /// yielded in row-major order: `x` changes fastest, then `y`, then `z`. This never computes a
+    /// cell outside of the box, so it is safe to use with corners at the limits of `P`.
+    pub fn range(min: Self, max: Self) -> GridCellRange<P> {
+        let (min, max) = (
+            GridCell::new(min.x.min(max.x), min.y.min(max.y), min.z.min(max.z)),
+            GridCell::new(min.x.max(max.x), min.y.max(max.y), min.z.max(max.z)),
+        );
+        GridCellRange {
+            min,
+            max,
+            next: Some(min),
+        }
+    }
+
     /// Convert this grid cell to a floating point translation within this `grid`.
     pub fn as_dvec3(&self, grid: &Grid<P>) -> DVec3 {
-        DVec3 {
-            x: self.x.as_f64() * grid.cell_edge_length() as f64,
-            y: self.y.as_f64() * grid.cell_edge_length() as f64,
-            z: self.z.as_f64() * grid.cell_edge_length() as f64,
+        grid.cell_to_float(self)
+    }
+
+    /// Immediately move this cell and the `transform` of the same entity into the nearest cell of
+    /// its `grid`, if the translation is larger than the grid's
+    /// [`maximum_distance_from_origin`](Grid::maximum_distance_from_origin).
+    ///
+    /// This is useful right after teleporting an entity by setting a large translation, to avoid
+    /// a frame where the entity has a large [`Transform`] and a stale cell, before
+    /// [`Self::recenter_large_transforms`] runs. Both use the same threshold, so recentering
+    /// manually does not conflict with the automatic recentering; an entity that has already been
+    /// recentered will not be moved again.
+    ///
+    /// To avoid losing precision when teleporting, prefer computing the cell and transform
+    /// directly with [`Grid::translation_to_grid`].
+    pub fn recenter(&mut self, transform: &mut Transform, grid: &Grid<P>) {
+        if let Some((cell_delta, translation)) = grid.recenter_translation(transform.translation) {
+            *self += cell_delta;
+            transform.translation = translation;
         }
     }
 
     /// If an entity's transform translation becomes larger than the limit specified in its
     /// [`Grid`], it will be relocated to the nearest grid cell to reduce the size of the transform.
+    ///
+    /// Every entity in a grid whose [`GridCell`], [`Transform`], or [`Parent`] changed is also
+    /// recorded, so [`Grid::propagate_high_precision`] can skip the entities that did not move.
+    /// Entities changed after this system runs are propagated on the following frame.
     pub fn recenter_large_transforms(
         mut stats: ResMut<crate::timing::PropagationStats>,
         grids: Query<&Grid<P>>,
-        mut changed_transform: Query<(&mut Self, &mut Transform, &Parent), Changed<Transform>>,
+        mut changed: ResMut<ChangedGridEntities<P>>,
+        mut thread_changed: Local<Parallel<Vec<Entity>>>,
+        mut changed_transform: Query<
+            (Entity, &mut Self, &mut Transform, &Parent),
+            Or<(Changed<Transform>, Changed<Self>, Changed<Parent>)>,
+        >,
     ) {
         let start = Instant::now();
-        changed_transform
-            .par_iter_mut()
-            .for_each(|(mut grid_pos, mut transform, parent)| {
+        changed_transform.par_iter_mut().for_each(
+            |(entity, mut grid_pos, mut transform, parent)| {
                 let Ok(grid) = grids.get(parent.get()) else {
                     return;
                 };
-                if transform
-                    .bypass_change_detection()
-                    .translation
-                    .abs()
-                    .max_element()
-                    > grid.maximum_distance_from_origin()
+                thread_changed.scope(|tl| tl.push(entity));
+                if let Some((grid_cell_delta, translation)) =
+                    grid.recenter_translation(transform.bypass_change_detection().translation)
                 {
-                    let (grid_cell_delta, translation) = grid.imprecise_translation_to_grid(
-                        transform.bypass_change_detection().translation,
-                    );
                     *grid_pos += grid_cell_delta;
                     transform.translation = translation;
                 }
-            });
+            },
+        );
+        thread_changed.drain_into(&mut changed.list);
         stats.grid_recentering += start.elapsed();
     }
 }
 
+/// An iterator over every [`GridCell`] in a box, see [`GridCell::range`].
+#[derive(Debug, Clone)]
+pub struct GridCellRange<P: GridPrecision> {
+    min: GridCell<P>,
+    max: GridCell<P>,
+    next: Option<GridCell<P>>,
+}
+
+impl<P: GridPrecision> Iterator for GridCellRange<P> {
+    type Item = GridCell<P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        let mut next = current;
+        self.next = if current.x < self.max.x {
+            next.x = next.x.wrapping_add(P::ONE);
+            Some(next)
+        } else if current.y < self.max.y {
+            next.x = self.min.x;
+            next.y = next.y.wrapping_add(P::ONE);
+            Some(next)
+        } else if current.z < self.max.z {
+            next.x = self.min.x;
+            next.y = self.min.y;
+            next.z = next.z.wrapping_add(P::ONE);
+            Some(next)
+        } else {
+            None
+        };
+        Some(current)
+    }
+}
+
 impl<P: GridPrecision> std::ops::Add for GridCell<P> {
     type Output = GridCell<P>;
 
@@ -247,6 +336,21 @@ impl<P: GridPrecision> std::ops::Mul<P> for &GridCell<P> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<P: GridPrecision + serde::Serialize> serde::Serialize for GridCell<P> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.x, self.y, self.z].serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, P: GridPrecision + serde::Deserialize<'de>> serde::Deserialize<'de> for GridCell<P> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let [x, y, z] = <[P; 3]>::deserialize(deserializer)?;
+        Ok(GridCell { x, y, z })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use bevy::prelude::*;
@@ -265,4 +369,114 @@ mod tests {
             })
             .run();
     }
+
+    #[test]
+    fn recenter() {
+        use super::{Grid, GridCell};
+
+        let grid = Grid::<i32>::new_uniform(10.0, 1.0);
+        let mut cell = GridCell::new(1, 0, 0);
+        let mut transform = Transform::from_xyz(23.0, -5.5, 0.0);
+        cell.recenter(&mut transform, &grid);
+        // The y axis is past the cell boundary, but within the switching threshold.
+        assert_eq!(cell, GridCell::new(3, 0, 0));
+        assert_eq!(transform.translation, Vec3::new(3.0, -5.5, 0.0));
+
+        // Already recentered, nothing changes.
+        cell.recenter(&mut transform, &grid);
+        assert_eq!(cell, GridCell::new(3, 0, 0));
+    }
+
+    #[test]
+    fn range() {
+        use super::GridCell;
+
+        let cells: Vec<_> =
+            GridCell::<i32>::range(GridCell::new(1, 0, 5), GridCell::new(0, 1, 5)).collect();
+        assert_eq!(
+            cells,
+            vec![
+                GridCell::new(0, 0, 5),
+                GridCell::new(1, 0, 5),
+                GridCell::new(0, 1, 5),
+                GridCell::new(1, 1, 5),
+            ]
+        );
+
+        let max = GridCell::<i8>::new(i8::MAX, i8::MAX, i8::MAX);
+        let near_max = GridCell::<i8>::new(i8::MAX - 1, i8::MAX - 2, i8::MAX);
+        assert_eq!(GridCell::range(near_max, max).count(), 2 * 3);
+        assert_eq!(GridCell::range(max, max).collect::<Vec<_>>(), vec![max]);
+    }
+
+    #[test]
+    fn hysteresis() {
+        use crate::prelude::*;
+
+        #[derive(Resource)]
+        struct Oscillating(Entity);
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 1.0), |root| {
+                    root.spawn_spatial(FloatingOrigin);
+                    let id = root.spawn_spatial(Transform::from_xyz(4.5, 5.5, 0.0)).id();
+                    root.commands().insert_resource(Oscillating(id));
+                });
+            });
+        app.update();
+
+        let entity = app.world().resource::<Oscillating>().0;
+        let mut cells = Vec::new();
+        for frame in 0..10 {
+            let nudge = if frame % 2 == 0 { 2.0 } else { -2.0 };
+            let mut transform = app.world_mut().get_mut::<Transform>(entity).unwrap();
+            transform.translation.x += nudge;
+            transform.translation.y += nudge / 4.0;
+            app.update();
+            cells.push(*app.world().get::<GridCell<i32>>(entity).unwrap());
+        }
+
+        // The entity crosses the x boundary by more than the switching threshold once, then
+        // oscillates around the boundary without changing cell again. It never crosses the y
+        // boundary by more than the switching threshold.
+        assert_eq!(cells[0], GridCell::new(1, 0, 0));
+        assert!(cells.iter().all(|cell| *cell == cells[0]), "{cells:?}");
+    }
+
+    #[test]
+    fn lexicographic_order() {
+        use super::GridCell;
+        use std::collections::BTreeMap;
+
+        let map: BTreeMap<_, _> = [
+            (GridCell::<i32>::new(1, 0, 0), 'd'),
+            (GridCell::new(0, 1, -5), 'b'),
+            (GridCell::new(0, 1, 5), 'c'),
+            (GridCell::new(0, 0, 9), 'a'),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(map.values().collect::<String>(), "abcd");
+
+        let range = map.range(GridCell::new(0, 1, i32::MIN)..GridCell::new(0, 2, i32::MIN));
+        assert_eq!(range.map(|(_, v)| *v).collect::<String>(), "bc");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_compact() {
+        use super::GridCell;
+        use bevy::scene::ron;
+
+        let cell = GridCell::<i64>::new(1, -2, i64::MAX);
+        let text = ron::to_string(&cell).unwrap();
+        assert_eq!(text, format!("(1,-2,{})", i64::MAX));
+        assert_eq!(ron::from_str::<GridCell<i64>>(&text).unwrap(), cell);
+
+        let cell = GridCell::<i8>::new(i8::MIN, 0, 3);
+        let text = ron::to_string(&cell).unwrap();
+        assert_eq!(ron::from_str::<GridCell<i8>>(&text).unwrap(), cell);
+    }
 }
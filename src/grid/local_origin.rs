@@ -12,6 +12,7 @@ use bevy_ecs::{
 };
 use bevy_hierarchy::prelude::*;
 use bevy_math::{prelude::*, DAffine3, DQuat};
+use bevy_reflect::prelude::*;
 use bevy_transform::prelude::*;
 
 pub use inner::LocalFloatingOrigin;
@@ -111,21 +112,13 @@ mod inner {
             self.cell = translation_grid;
             self.translation = translation_float;
             self.rotation = rotation_float;
-            self.grid_transform = DAffine3 {
-                matrix3: DMat3::from_quat(self.rotation),
-                translation: self.translation.as_dvec3(),
-            }
-            .inverse();
+            self.grid_transform = Self::inverse_transform(self.translation, self.rotation);
             self.is_local_origin_unchanged = prev.eq(self);
         }
 
         /// Create a new [`LocalFloatingOrigin`].
         pub fn new(cell: GridCell<P>, translation: Vec3, rotation: DQuat) -> Self {
-            let grid_transform = DAffine3 {
-                matrix3: DMat3::from_quat(rotation),
-                translation: translation.as_dvec3(),
-            }
-            .inverse();
+            let grid_transform = Self::inverse_transform(translation, rotation);
 
             Self {
                 cell,
@@ -136,6 +129,24 @@ mod inner {
             }
         }
 
+        /// Compute the inverse of the transform with the given `translation` and `rotation`.
+        ///
+        /// Most grids share the orientation of the floating origin's grid, in which case the
+        /// inverse is just the negated translation, and the general matrix inverse can be skipped.
+        /// This roughly halves the cost of [`Self::set`], although that is small compared to the
+        /// rest of [`LocalFloatingOrigin::compute_all`], see the `local_origin_rotation` benchmark.
+        #[inline]
+        fn inverse_transform(translation: Vec3, rotation: DQuat) -> DAffine3 {
+            if rotation == DQuat::IDENTITY {
+                return DAffine3::from_translation(-translation.as_dvec3());
+            }
+            DAffine3 {
+                matrix3: DMat3::from_quat(rotation),
+                translation: translation.as_dvec3(),
+            }
+            .inverse()
+        }
+
         /// Returns true iff the local origin has not changed relative to the floating origin.
         #[inline]
         pub fn is_local_origin_unchanged(&self) -> bool {
@@ -244,6 +255,7 @@ fn propagate_origin_to_child<P: GridPrecision>(
 pub struct Grids<'w, 's, P: GridPrecision> {
     parent: Query<'w, 's, Read<Parent>>,
     grid_query: Query<'w, 's, (Entity, Read<Grid<P>>, Option<Read<Parent>>)>,
+    roots: Query<'w, 's, Entity, With<BigSpace>>,
 }
 
 impl<P: GridPrecision> Grids<'_, '_, P> {
@@ -257,6 +269,30 @@ impl<P: GridPrecision> Grids<'_, '_, P> {
             })
     }
 
+    /// Get the position of the floating origin in the grid with the entity `grid`, as the cell,
+    /// translation, and rotation stored in the grid's [`LocalFloatingOrigin`].
+    ///
+    /// ```
+    /// # use big_space::prelude::*;
+    /// # use bevy::prelude::*;
+    /// # #[derive(Component)]
+    /// # struct Planet;
+    /// fn camera_on_planet(grids: Grids<i64>, planets: Query<Entity, With<Planet>>) {
+    ///     for planet in &planets {
+    ///         let (cell, translation, rotation) = grids.floating_origin_in(planet);
+    ///         info!("The camera is at {translation} in cell {cell:?} of planet {planet}");
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// This will panic if the entity does not have a [`Grid`] component.
+    pub fn floating_origin_in(&self, grid: Entity) -> (GridCell<P>, Vec3, DQuat) {
+        let origin = self.get(grid).local_floating_origin();
+        (origin.cell(), origin.translation(), origin.rotation())
+    }
+
     /// Get the [`Grid`] that `this` `Entity` is a child of, if it exists.
     pub fn parent_grid(&self, this: Entity) -> Option<&Grid<P>> {
         self.parent_grid_entity(this)
@@ -275,6 +311,35 @@ impl<P: GridPrecision> Grids<'_, '_, P> {
         }
     }
 
+    /// Iterate over the root entities of all [`BigSpace`]s in the world. The root of a big space is
+    /// the entity with the [`BigSpace`] component, which is also its outermost [`Grid`].
+    pub fn roots(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.roots.iter()
+    }
+
+    /// Get the root entity of the [`BigSpace`] that `entity` is in, by walking up its ancestors
+    /// until an entity with the [`BigSpace`] component is found. If `entity` is itself a root, it
+    /// is returned. Returns `None` if the entity is not in a big space.
+    pub fn root_of(&self, entity: Entity) -> Option<Entity> {
+        std::iter::successors(Some(entity), |&e| self.parent.get(e).ok().map(|p| p.get()))
+            .find(|&e| self.roots.contains(e))
+    }
+
+    /// Get the lowest [`Grid`] that contains both `a` and `b`, or `None` if they are not in the
+    /// same [`BigSpace`].
+    ///
+    /// An entity that is a grid counts as containing itself, so if `a` is a grid that is an
+    /// ancestor of `b`, this returns `a`.
+    pub fn common_ancestor(&self, a: Entity, b: Entity) -> Option<Entity> {
+        let grid_ancestors = |entity: Entity| {
+            let this = self.grid_query.contains(entity).then_some(entity);
+            let first = this.or_else(|| self.parent_grid_entity(entity));
+            std::iter::successors(first, |&grid| self.parent_grid_entity(grid))
+        };
+        let a_ancestors: Vec<Entity> = grid_ancestors(a).collect();
+        grid_ancestors(b).find(|grid| a_ancestors.contains(grid))
+    }
+
     /// Get all grid entities that are children of this grid. Applies a filter to the returned
     /// children.
     fn child_grids_filtered<'a>(
@@ -421,6 +486,37 @@ impl<P: GridPrecision> GridsMut<'_, '_, P> {
     }
 }
 
+/// The maximum number of steps [`LocalFloatingOrigin::compute_all`] will take walking up from the
+/// [`FloatingOrigin`]'s [`Grid`] to the root of its [`BigSpace`], which defensively prevents
+/// infinite looping in case there is a degenerate hierarchy. Set with
+/// [`BigSpacePlugin::with_max_grid_depth`]. The default is `1_000`.
+///
+/// This is the depth of the floating origin's grid below the root, where children of the root grid
+/// have a depth of one. Grids that are deeper than the floating origin's grid do not count towards
+/// this limit, so it is unrelated to
+/// [`PropagationStats::max_grid_depth`](crate::timing::PropagationStats::max_grid_depth).
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub struct MaxGridDepth(pub usize);
+
+impl Default for MaxGridDepth {
+    fn default() -> Self {
+        Self(1_000)
+    }
+}
+
+/// Sent when walking from the [`FloatingOrigin`] up to the root of a [`BigSpace`] exceeds the
+/// [`MaxGridDepth`]. The floating origin is not propagated to the rest of the hierarchy, so the
+/// [`GlobalTransform`]s of entities in this big space will be wrong until this is resolved, either
+/// by raising the limit, or fixing a degenerate hierarchy.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridDepthExceeded {
+    /// The root entity of the [`BigSpace`].
+    pub root: Entity,
+    /// The depth at which propagation was stopped, equal to the [`MaxGridDepth`].
+    pub depth: usize,
+}
+
 impl<P: GridPrecision> LocalFloatingOrigin<P> {
     /// Update the [`LocalFloatingOrigin`] of every [`Grid`] in the world. This does not update any
     /// entity transforms, instead this is a preceding step that updates every reference grid, so it
@@ -430,26 +526,27 @@ impl<P: GridPrecision> LocalFloatingOrigin<P> {
     /// source of truth and never mutated.
     pub fn compute_all(
         mut stats: ResMut<crate::timing::PropagationStats>,
+        max_depth: Res<MaxGridDepth>,
+        mut depth_exceeded: EventWriter<GridDepthExceeded>,
         mut grids: GridsMut<P>,
-        mut grid_stack: Local<Vec<Entity>>,
-        mut scratch_buffer: Local<Vec<Entity>>,
+        mut buffers: Local<(Vec<(Entity, isize)>, Vec<Entity>)>,
         cells: Query<(Entity, Ref<GridCell<P>>)>,
         roots: Query<(Entity, &BigSpace)>,
-        parents: Query<&Parent>,
     ) {
         let start = bevy_utils::Instant::now();
-
-        /// The maximum grid tree depth, defensively prevents infinite looping in case there is a
-        /// degenerate hierarchy. It might take a while, but at least it's not forever?
-        const MAX_REFERENCE_FRAME_DEPTH: usize = 1_000;
+        let (grid_stack, scratch_buffer) = &mut *buffers;
+        let max_depth = max_depth.0;
 
         // TODO: because each tree under a root is disjoint, these updates can be done in parallel
         // without aliasing. This will require unsafe, just like bevy's own transform propagation.
-        'outer: for (origin_entity, origin_cell) in roots
-            .iter() // TODO: If any of these checks fail, log to some diagnostic
-            .filter_map(|(root_entity, root)| root.validate_floating_origin(root_entity, &parents))
-            .filter_map(|origin| cells.get(origin).ok())
-        {
+        'outer: for (root_entity, root) in &roots {
+            // TODO: If any of these checks fail, log to some diagnostic
+            let Some((origin_entity, origin_cell)) = root
+                .validate_floating_origin(root_entity, &grids.parent)
+                .and_then(|origin| cells.get(origin).ok())
+            else {
+                continue;
+            };
             let Some(mut this_grid) = grids.parent_grid_entity(origin_entity) else {
                 tracing::error!("The floating origin is not in a valid grid. The floating origin entity must be a child of an entity with the `Grid` component.");
                 continue;
@@ -465,11 +562,16 @@ impl<P: GridPrecision> LocalFloatingOrigin<P> {
 
             // Seed the grid stack with the floating origin's grid. From this point out, we will
             // only look at siblings and parents, which will allow us to visit the entire tree.
+            //
+            // Each grid on the stack is paired with its level relative to the floating origin's
+            // grid, to measure the depth of the tree.
             grid_stack.clear();
-            grid_stack.push(this_grid);
+            grid_stack.push((this_grid, 0));
+            let mut this_level = 0;
+            let mut deepest_level = 0;
 
             // Recurse up and across the tree, updating siblings and their children.
-            for _ in 0..MAX_REFERENCE_FRAME_DEPTH {
+            for _ in 0..=max_depth {
                 // We start by propagating up to the parent of this grid, then propagating down to
                 // the siblings of this grid (children of the parent that are not this grid).
                 if let Some(parent_grid) = grids.parent_grid_entity(this_grid) {
@@ -480,20 +582,23 @@ impl<P: GridPrecision> LocalFloatingOrigin<P> {
                     for sibling_grid in scratch_buffer.drain(..) {
                         // The siblings of this grid are also the children of the parent grid.
                         propagate_origin_to_child(parent_grid, &mut grids, sibling_grid);
-                        grid_stack.push(sibling_grid); // We'll recurse through children next
+                        // We'll recurse through children next
+                        grid_stack.push((sibling_grid, this_level));
                     }
                 }
 
                 // All of the grids pushed on the stack have been processed. We can now pop those
                 // off the stack and recursively process their children all the way out to the
                 // leaves of the tree.
-                while let Some(this_grid) = grid_stack.pop() {
+                while let Some((this_grid, level)) = grid_stack.pop() {
+                    deepest_level = deepest_level.max(level);
                     scratch_buffer.extend(grids.child_grids(this_grid));
                     // TODO: This loop could be run in parallel, because we are mutating each unique
                     // child, these do no alias.
                     for child_grid in scratch_buffer.drain(..) {
                         propagate_origin_to_child(this_grid, &mut grids, child_grid);
-                        grid_stack.push(child_grid) // Push processed child onto the stack
+                        // Push processed child onto the stack
+                        grid_stack.push((child_grid, level + 1));
                     }
                 }
 
@@ -502,12 +607,24 @@ impl<P: GridPrecision> LocalFloatingOrigin<P> {
                 // to a parent, "this grid" and all descendants have already been processed, so we
                 // only need to process the siblings.
                 match grids.parent_grid_entity(this_grid) {
-                    Some(parent_grid) => this_grid = parent_grid,
-                    None => continue 'outer, // We have reached the root of the tree, and can exit.
+                    Some(parent_grid) => {
+                        this_grid = parent_grid;
+                        this_level -= 1;
+                    }
+                    None => {
+                        // We have reached the root of the tree, and can exit.
+                        let depth = (deepest_level - this_level) as usize;
+                        stats.max_grid_depth = stats.max_grid_depth.max(depth);
+                        continue 'outer;
+                    }
                 }
             }
 
-            tracing::error!("Reached the maximum grid depth ({MAX_REFERENCE_FRAME_DEPTH}), and exited early to prevent an infinite loop. This might be caused by a degenerate hierarchy.")
+            tracing::error!("Reached the maximum grid depth ({max_depth}) in the BigSpace {root_entity:?}, and exited early to prevent an infinite loop. This might be caused by a degenerate hierarchy, or the limit can be raised with `BigSpacePlugin::with_max_grid_depth`.");
+            depth_exceeded.send(GridDepthExceeded {
+                root: root_entity,
+                depth: max_depth,
+            });
         }
 
         stats.local_origin_propagation += start.elapsed();
@@ -516,7 +633,11 @@ impl<P: GridPrecision> LocalFloatingOrigin<P> {
 
 #[cfg(test)]
 mod tests {
-    use bevy::{ecs::system::SystemState, math::DVec3, prelude::*};
+    use bevy::{
+        ecs::system::SystemState,
+        math::{DAffine3, DMat3, DQuat, DVec3},
+        prelude::*,
+    };
 
     use super::*;
 
@@ -570,6 +691,124 @@ mod tests {
         assert_eq!(result, vec![child_2]);
     }
 
+    #[test]
+    fn common_ancestor() {
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default());
+
+        let root = app.world_mut().spawn(BigGridBundle::<i32>::default()).id();
+        let other_root = app.world_mut().spawn(BigGridBundle::<i32>::default()).id();
+        let parent = app.world_mut().spawn(BigGridBundle::<i32>::default()).id();
+        let child = app.world_mut().spawn(BigGridBundle::<i32>::default()).id();
+        let in_root = app
+            .world_mut()
+            .spawn(BigSpatialBundle::<i32>::default())
+            .id();
+        let in_child = app
+            .world_mut()
+            .spawn(BigSpatialBundle::<i32>::default())
+            .id();
+        let in_other = app
+            .world_mut()
+            .spawn(BigSpatialBundle::<i32>::default())
+            .id();
+
+        app.world_mut()
+            .entity_mut(root)
+            .add_children(&[parent, in_root]);
+        app.world_mut().entity_mut(parent).add_child(child);
+        app.world_mut().entity_mut(child).add_child(in_child);
+        app.world_mut().entity_mut(other_root).add_child(in_other);
+
+        let mut state = SystemState::<Grids<i32>>::new(app.world_mut());
+        let grids = state.get(app.world());
+
+        assert_eq!(grids.common_ancestor(in_root, in_child), Some(root));
+        assert_eq!(grids.common_ancestor(in_child, in_root), Some(root));
+        assert_eq!(grids.common_ancestor(in_child, child), Some(child));
+        assert_eq!(grids.common_ancestor(parent, in_child), Some(parent));
+        assert_eq!(grids.common_ancestor(in_child, in_child), Some(child));
+        assert_eq!(grids.common_ancestor(in_child, in_other), None);
+    }
+
+    #[test]
+    fn roots() {
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default());
+
+        let root = app
+            .world_mut()
+            .spawn(BigSpaceRootBundle::<i32>::default())
+            .id();
+        let other_root = app
+            .world_mut()
+            .spawn(BigSpaceRootBundle::<i32>::default())
+            .id();
+        let child = app.world_mut().spawn(BigGridBundle::<i32>::default()).id();
+        let in_child = app
+            .world_mut()
+            .spawn(BigSpatialBundle::<i32>::default())
+            .id();
+        let outside = app
+            .world_mut()
+            .spawn(BigSpatialBundle::<i32>::default())
+            .id();
+        app.world_mut().entity_mut(root).add_child(child);
+        app.world_mut().entity_mut(child).add_child(in_child);
+
+        let mut state = SystemState::<Grids<i32>>::new(app.world_mut());
+        let grids = state.get(app.world());
+
+        let mut roots: Vec<_> = grids.roots().collect();
+        roots.sort();
+        assert_eq!(roots, vec![root, other_root]);
+        assert_eq!(grids.root_of(in_child), Some(root));
+        assert_eq!(grids.root_of(child), Some(root));
+        assert_eq!(grids.root_of(other_root), Some(other_root));
+        assert_eq!(grids.root_of(outside), None);
+    }
+
+    #[test]
+    fn max_grid_depth() {
+        let build = |max_depth: usize| {
+            let mut app = App::new();
+            app.add_plugins(BigSpacePlugin::<i32>::default().with_max_grid_depth(max_depth));
+            let world = app.world_mut();
+            let root = world.spawn(BigSpaceRootBundle::<i32>::default()).id();
+            // A chain of five grids below the root, with the floating origin three grids deep.
+            let mut parent = root;
+            for depth in 1..=5 {
+                let grid = world.spawn(BigGridBundle::<i32>::default()).id();
+                world.entity_mut(parent).add_child(grid);
+                if depth == 3 {
+                    let origin = world
+                        .spawn((BigSpatialBundle::<i32>::default(), FloatingOrigin))
+                        .id();
+                    world.entity_mut(grid).add_child(origin);
+                }
+                parent = grid;
+            }
+            app.update();
+            (app, root)
+        };
+        let exceeded = |app: &App| {
+            let events = app.world().resource::<Events<GridDepthExceeded>>();
+            events
+                .iter_current_update_events()
+                .copied()
+                .collect::<Vec<_>>()
+        };
+
+        // The limit applies to the depth of the floating origin's grid, not the whole tree.
+        let (app, _) = build(3);
+        let stats = app.world().resource::<crate::timing::PropagationStats>();
+        assert_eq!(stats.max_grid_depth(), 5);
+        assert!(exceeded(&app).is_empty());
+
+        let (app, root) = build(2);
+        assert_eq!(exceeded(&app), vec![GridDepthExceeded { root, depth: 2 }]);
+    }
+
     #[test]
     fn child_propagation() {
         let mut app = App::new();
@@ -745,4 +984,18 @@ mod tests {
         assert!((computed_pos - correct_pos).length() < 1e-6);
         assert!((computed_pos - DVec3::new(7.0, -3.0, 0.0)).length() < 1e-6);
     }
+
+    #[test]
+    fn identity_rotation_inverse() {
+        let translation = Vec3::new(1.5, -200.0, 3e6);
+        for rotation in [DQuat::IDENTITY, DQuat::from_rotation_y(0.3)] {
+            let origin = LocalFloatingOrigin::<i32>::new(GridCell::ZERO, translation, rotation);
+            let expected = DAffine3 {
+                matrix3: DMat3::from_quat(rotation),
+                translation: translation.as_dvec3(),
+            }
+            .inverse();
+            assert!(origin.grid_transform().abs_diff_eq(expected, 1e-9));
+        }
+    }
 }
@@ -3,15 +3,20 @@
 
 use crate::prelude::*;
 use bevy_ecs::prelude::*;
-use bevy_math::{prelude::*, Affine3A, DAffine3, DVec3};
+use bevy_math::{prelude::*, Affine3A, DAffine3, DQuat, DVec3};
 use bevy_reflect::prelude::*;
+use bevy_tasks::{ComputeTaskPool, ParallelSlice, TaskPool};
 use bevy_transform::prelude::*;
 
 use local_origin::LocalFloatingOrigin;
 
+pub mod bounds;
 pub mod cell;
 pub mod local_origin;
 pub mod propagation;
+pub mod raycast;
+pub mod to_root;
+pub mod wrap;
 
 /// A component that defines a spatial grid that child entities are located on. Child entities are
 /// located on this grid with the [`GridCell`] component.
@@ -33,21 +38,27 @@ pub mod propagation;
 pub struct Grid<P: GridPrecision> {
     /// The high-precision position of the floating origin's current grid cell local to this grid.
     local_floating_origin: LocalFloatingOrigin<P>,
-    /// Defines the uniform scale of the grid by the length of the edge of a grid cell.
-    cell_edge_length: f32,
-    /// How far an entity can move from the origin before its grid cell is recomputed.
-    maximum_distance_from_origin: f32,
+    /// Defines the scale of the grid by the length of the edges of a grid cell, along each axis.
+    cell_edge_length: Vec3,
+    /// How far an entity can move from the origin, along each axis, before its grid cell is
+    /// recomputed.
+    maximum_distance_from_origin: Vec3,
 }
 
 impl<P: GridPrecision> Default for Grid<P> {
     fn default() -> Self {
-        Self::new(2_000f32, 100f32)
+        Self::new_uniform(2_000f32, 100f32)
     }
 }
 
 impl<P: GridPrecision> Grid<P> {
-    /// Construct a new [`Grid`]. The properties of a grid cannot be changed after construction.
-    pub fn new(cell_edge_length: f32, switching_threshold: f32) -> Self {
+    /// Construct a new [`Grid`] with cells that may have a different edge length along each axis.
+    /// The properties of a grid cannot be changed after construction.
+    ///
+    /// This is useful for anisotropic worlds, for example a thin atmosphere where the vertical
+    /// resolution should differ from the horizontal resolution. Cells are still indexed with
+    /// integers, so neighbor lookups and spatial hashing are unaffected by the cell shape.
+    pub fn new(cell_edge_length: Vec3, switching_threshold: f32) -> Self {
         Self {
             local_floating_origin: LocalFloatingOrigin::default(),
             cell_edge_length,
@@ -55,21 +66,99 @@ impl<P: GridPrecision> Grid<P> {
         }
     }
 
+    /// Construct a new [`Grid`] with cubic cells. The properties of a grid cannot be changed after
+    /// construction.
+    pub fn new_uniform(cell_edge_length: f32, switching_threshold: f32) -> Self {
+        Self::new(Vec3::splat(cell_edge_length), switching_threshold)
+    }
+
+    /// Construct a new [`Grid`], setting the [`Self::maximum_distance_from_origin`] directly,
+    /// instead of deriving it from the cell edge length and a switching threshold. This is useful
+    /// for very large cells, where you want entities to be recentered with a small absolute
+    /// threshold, regardless of the size of the cell.
+    ///
+    /// ## Invariant
+    ///
+    /// `maximum_distance_from_origin` must be at least half of the `cell_edge_length` along each
+    /// axis. If it is smaller, an entity can exceed the maximum distance while still being closest
+    /// to the center of its current cell. Recentering would then compute the same cell every time,
+    /// leaving the entity's [`Transform`] beyond the maximum distance, and recomputing its cell
+    /// every time it moves. This is checked with a debug assertion.
+    pub fn new_with_max_distance(
+        cell_edge_length: Vec3,
+        maximum_distance_from_origin: Vec3,
+    ) -> Self {
+        debug_assert!(
+            maximum_distance_from_origin
+                .cmpge(cell_edge_length / 2.0)
+                .all(),
+            "The maximum distance from origin ({maximum_distance_from_origin}) must be at least half of the cell edge length ({cell_edge_length})."
+        );
+        Self {
+            local_floating_origin: LocalFloatingOrigin::default(),
+            cell_edge_length,
+            maximum_distance_from_origin,
+        }
+    }
+
+    /// A preset for human scale worlds measured in meters, like a large open world game, with
+    /// 1 km cells and a 50 m switching threshold.
+    ///
+    /// Recommended precision: `i32`, for a usable volume with an edge length of 4.3 billion km,
+    /// about 29 times the distance from the Earth to the Sun. Translations are within 550 m of the
+    /// cell center, with a worst case precision of 0.06 mm.
+    ///
+    /// See [`GridPrecision`] for how the usable volume depends on the precision.
+    pub fn meters_human_scale() -> Self {
+        Self::new_uniform(1_000.0, 50.0)
+    }
+
+    /// A preset for worlds the size of a solar system measured in meters, with 10 km cells and a
+    /// 500 m switching threshold.
+    ///
+    /// Recommended precision: `i32`, for a usable volume with an edge length of 0.0045 light years,
+    /// about 4 times the width of the solar system. Translations are within 5.5 km of the cell
+    /// center, with a worst case precision of 0.5 mm.
+    ///
+    /// See [`GridPrecision`] for how the usable volume depends on the precision.
+    pub fn solar_system() -> Self {
+        Self::new_uniform(10_000.0, 500.0)
+    }
+
+    /// A preset for worlds the size of a galaxy measured in meters, with 100 km cells and a 5 km
+    /// switching threshold.
+    ///
+    /// Recommended precision: `i64`, for a usable volume with an edge length of 195 million light
+    /// years, about 1,000 times the width of the Milky Way. Translations are within 55 km of the
+    /// cell center, with a worst case precision of 4 mm.
+    ///
+    /// See [`GridPrecision`] for how the usable volume depends on the precision.
+    pub fn galaxy() -> Self {
+        Self::new_uniform(100_000.0, 5_000.0)
+    }
+
     /// Get the position of the floating origin relative to the current grid.
     #[inline]
     pub fn local_floating_origin(&self) -> &LocalFloatingOrigin<P> {
         &self.local_floating_origin
     }
 
-    /// Get the size of each cell this grid's grid.
+    /// Mutable access to the position of the floating origin relative to the current grid, used to
+    /// seed the origin of grids that are moved into a new tree.
     #[inline]
-    pub fn cell_edge_length(&self) -> f32 {
+    pub(crate) fn local_floating_origin_mut(&mut self) -> &mut LocalFloatingOrigin<P> {
+        &mut self.local_floating_origin
+    }
+
+    /// Get the size of each cell in this grid, along each axis.
+    #[inline]
+    pub fn cell_edge_length(&self) -> Vec3 {
         self.cell_edge_length
     }
 
-    /// Get the grid's [`Self::maximum_distance_from_origin`].
+    /// Get the grid's [`Self::maximum_distance_from_origin`] along each axis.
     #[inline]
-    pub fn maximum_distance_from_origin(&self) -> f32 {
+    pub fn maximum_distance_from_origin(&self) -> Vec3 {
         self.maximum_distance_from_origin
     }
 
@@ -77,22 +166,18 @@ impl<P: GridPrecision> Grid<P> {
     /// [`GridCell`] within this grid.
     #[inline]
     pub fn grid_position_double(&self, pos: &GridCell<P>, transform: &Transform) -> DVec3 {
-        DVec3 {
-            x: pos.x.as_f64() * self.cell_edge_length as f64 + transform.translation.x as f64,
-            y: pos.y.as_f64() * self.cell_edge_length as f64 + transform.translation.y as f64,
-            z: pos.z.as_f64() * self.cell_edge_length as f64 + transform.translation.z as f64,
-        }
+        self.cell_to_float(pos) + transform.translation.as_dvec3()
     }
 
     /// Compute the single precision position of an entity's [`Transform`] with respect to the given
     /// [`GridCell`].
+    ///
+    /// This is computed in double precision, and only the result is cast to single precision, so
+    /// it is equal to [`Self::grid_position_double`] cast to a [`Vec3`]. Positions far from the
+    /// origin of the grid will still lose precision in the result.
     #[inline]
     pub fn grid_position(&self, pos: &GridCell<P>, transform: &Transform) -> Vec3 {
-        Vec3 {
-            x: pos.x.as_f64() as f32 * self.cell_edge_length + transform.translation.x,
-            y: pos.y.as_f64() as f32 * self.cell_edge_length + transform.translation.y,
-            z: pos.z.as_f64() as f32 * self.cell_edge_length + transform.translation.z,
-        }
+        self.grid_position_double(pos, transform).as_vec3()
     }
 
     /// Returns the floating point position of a [`GridCell`].
@@ -101,26 +186,82 @@ impl<P: GridPrecision> Grid<P> {
             x: pos.x.as_f64(),
             y: pos.y.as_f64(),
             z: pos.z.as_f64(),
-        } * self.cell_edge_length as f64
+        } * self.cell_edge_length.as_dvec3()
+    }
+
+    /// Returns the position of the center of a [`GridCell`] in this grid. This is the same as
+    /// [`Self::cell_to_float`].
+    #[inline]
+    pub fn cell_center(&self, cell: &GridCell<P>) -> DVec3 {
+        self.cell_to_float(cell)
+    }
+
+    /// Returns the minimum and maximum corners of a [`GridCell`] in this grid.
+    #[inline]
+    pub fn cell_aabb(&self, cell: &GridCell<P>) -> (DVec3, DVec3) {
+        let center = self.cell_center(cell);
+        let half_extents = self.cell_edge_length.as_dvec3() / 2.0;
+        (center - half_extents, center + half_extents)
+    }
+
+    /// Returns the inclusive minimum and maximum [`GridCell`]s covering the box from `min` to `max`
+    /// in this grid. This is roughly the inverse of [`Self::cell_aabb`], and is useful to find the
+    /// cells to populate when spawning entities within a box.
+    ///
+    /// Cells are centered on their position, so a point is in the cell nearest to it. A box smaller
+    /// than a cell returns the same cell for both corners if it doesn't straddle a cell boundary.
+    /// Cells beyond the range of the [`GridPrecision`] are clamped to its bounds.
+    ///
+    /// ```
+    /// # use big_space::prelude::*;
+    /// # use bevy::math::DVec3;
+    /// let grid = Grid::<i64>::new_uniform(10.0, 0.0);
+    /// let (min, max) = grid.aabb_to_cells(DVec3::new(-4.0, 0.0, 0.0), DVec3::new(25.0, 1.0, 1.0));
+    /// assert_eq!(min, GridCell::new(0, 0, 0));
+    /// assert_eq!(max, GridCell::new(3, 0, 0));
+    /// ```
+    #[inline]
+    pub fn aabb_to_cells(&self, min: DVec3, max: DVec3) -> (GridCell<P>, GridCell<P>) {
+        let to_cell = |position: DVec3| {
+            let DVec3 { x, y, z } = (position / self.cell_edge_length.as_dvec3()).round();
+            GridCell::new(P::from_f64(x), P::from_f64(y), P::from_f64(z))
+        };
+        (to_cell(min.min(max)), to_cell(min.max(max)))
+    }
+
+    /// Returns the position of the center of a [`GridCell`] relative to the floating origin, in the
+    /// same space as the [`GlobalTransform`]s computed by this plugin. This is useful for
+    /// rendering, like placing gizmos or decorations aligned to the grid.
+    #[inline]
+    pub fn cell_center_global(&self, cell: &GridCell<P>) -> Vec3 {
+        self.global_transform(cell, &Transform::IDENTITY)
+            .translation()
     }
 
     /// Convert a large translation into a small translation relative to a grid cell.
+    ///
+    /// This only splits a translation, an entity's rotation and scale are not affected by the cell
+    /// it is in. To place an entity with a rotation and scale, use [`Self::transform_to_grid`].
     #[inline]
     pub fn translation_to_grid(&self, input: impl Into<DVec3>) -> (GridCell<P>, Vec3) {
-        let l = self.cell_edge_length as f64;
+        let l = self.cell_edge_length.as_dvec3();
         let input = input.into();
         let DVec3 { x, y, z } = input;
 
-        if input.abs().max_element() < self.maximum_distance_from_origin as f64 {
+        if input
+            .abs()
+            .cmplt(self.maximum_distance_from_origin.as_dvec3())
+            .all()
+        {
             return (GridCell::default(), input.as_vec3());
         }
 
-        let x_r = (x / l).round();
-        let y_r = (y / l).round();
-        let z_r = (z / l).round();
-        let t_x = x - x_r * l;
-        let t_y = y - y_r * l;
-        let t_z = z - z_r * l;
+        let x_r = (x / l.x).round();
+        let y_r = (y / l.y).round();
+        let z_r = (z / l.z).round();
+        let t_x = x - x_r * l.x;
+        let t_y = y - y_r * l.y;
+        let t_z = z - z_r * l.z;
 
         (
             GridCell {
@@ -132,6 +273,144 @@ impl<P: GridPrecision> Grid<P> {
         )
     }
 
+    /// Convert many large translations into small translations relative to grid cells, in parallel
+    /// on the [`ComputeTaskPool`]. This is equivalent to calling [`Self::translation_to_grid`] on
+    /// each position, and is useful when importing a large number of absolute positions at once.
+    ///
+    /// The contents of `out` are replaced with one result per position, in the same order as
+    /// `positions`.
+    pub fn translation_to_grid_batch(
+        &self,
+        positions: &[DVec3],
+        out: &mut Vec<(GridCell<P>, Vec3)>,
+    ) {
+        let task_pool = ComputeTaskPool::get_or_init(TaskPool::default);
+        let chunks = positions.par_splat_map(task_pool, None, |_, chunk| {
+            chunk
+                .iter()
+                .map(|&position| self.translation_to_grid(position))
+                .collect::<Vec<_>>()
+        });
+        out.clear();
+        out.reserve(positions.len());
+        out.extend(chunks.into_iter().flatten());
+    }
+
+    /// Convert a large position in this grid into a [`GridCell`] and a [`Transform`] with the given
+    /// `rotation` and `scale`. This is the inverse of [`Self::global_transform`] when the floating
+    /// origin is at the origin of this grid, and is useful to place an entity from an absolute
+    /// position.
+    #[inline]
+    pub fn transform_to_grid(
+        &self,
+        position: DVec3,
+        rotation: DQuat,
+        scale: Vec3,
+    ) -> (GridCell<P>, Transform) {
+        let (cell, translation) = self.translation_to_grid(position);
+        let transform = Transform {
+            translation,
+            rotation: rotation.as_quat(),
+            scale,
+        };
+        (cell, transform)
+    }
+
+    /// Compute the position of an entity placed at `local_offset` from a reference entity at `cell`
+    /// and `transform` in this grid, with the offset in the reference entity's local space. For
+    /// example, with `-Z` forward, `Vec3::new(200.0, 0.0, 50.0)` is 200 units to the right of and
+    /// 50 units behind the reference entity, useful for formation flying.
+    ///
+    /// The offset is rotated by the reference entity's rotation but not scaled. The result has the
+    /// same rotation and scale as the reference entity, and is recentered into the nearest cell,
+    /// so it is precise even across cell boundaries. The computation is done relative to `cell`,
+    /// instead of with absolute positions that lose precision far from the origin of the grid.
+    ///
+    /// ```
+    /// # use big_space::prelude::*;
+    /// # use bevy::prelude::*;
+    /// # let grid = Grid::<i64>::new_uniform(100.0, 0.0);
+    /// let leader = (GridCell::new(1_000_000_000, 0, 0), Transform::from_xyz(40.0, 0.0, 0.0));
+    /// let (cell, transform) = grid.offset_from(&leader.0, &leader.1, Vec3::new(200.0, 0.0, 50.0));
+    /// assert_eq!(cell, GridCell::new(1_000_000_002, 0, 1));
+    /// assert_eq!(transform.translation, Vec3::new(40.0, 0.0, -50.0));
+    /// ```
+    pub fn offset_from(
+        &self,
+        cell: &GridCell<P>,
+        transform: &Transform,
+        local_offset: Vec3,
+    ) -> (GridCell<P>, Transform) {
+        let offset = transform.rotation.as_dquat() * local_offset.as_dvec3();
+        let (cell_offset, translation) =
+            self.translation_to_grid(transform.translation.as_dvec3() + offset);
+        (*cell + cell_offset, transform.with_translation(translation))
+    }
+
+    /// Snap the translation of an entity in `cell` to the nearest multiple of `increment` along
+    /// each axis, relative to the center of the cell. If snapping moves the translation past the
+    /// edge of the cell, the entity is moved into the neighboring cell. An `increment` equal to
+    /// [`Self::cell_edge_length`] snaps to the center of the nearest cell.
+    ///
+    /// The `increment` should evenly divide the cell edge length, so the snapped positions line up
+    /// across cells. Axes with an increment of zero or less are not snapped. The rotation and scale
+    /// of the `transform` are unchanged.
+    pub fn snap(
+        &self,
+        cell: GridCell<P>,
+        transform: &Transform,
+        increment: Vec3,
+    ) -> (GridCell<P>, Transform) {
+        let increment = increment.as_dvec3();
+        let edge = self.cell_edge_length.as_dvec3();
+        let translation = transform.translation.as_dvec3();
+        let snapped = DVec3::select(
+            increment.cmpgt(DVec3::ZERO),
+            (translation / increment).round() * increment,
+            translation,
+        );
+        // Only carry into the next cell when past the edge, a translation exactly on the edge of
+        // the cell stays in the cell.
+        let cell_offset = ((snapped.abs() - edge / 2.0) / edge)
+            .ceil()
+            .max(DVec3::ZERO)
+            * snapped.signum();
+        let offset = GridCell::new(
+            P::from_f64(cell_offset.x),
+            P::from_f64(cell_offset.y),
+            P::from_f64(cell_offset.z),
+        );
+        let transform = Transform {
+            translation: (snapped - cell_offset * edge).as_vec3(),
+            ..*transform
+        };
+        (cell + offset, transform)
+    }
+
+    /// Compute the cell offset and new translation of an entity with the given `translation`, if it
+    /// needs to be recentered. Returns `None` if the translation is within
+    /// [`Self::maximum_distance_from_origin`] along every axis.
+    ///
+    /// Only the axes that exceed the maximum distance are moved to a new cell. This adds hysteresis
+    /// to cell changes: once an entity is in a cell, it must move past the cell boundary by the
+    /// switching threshold along an axis before it changes cell along that axis, even if it has
+    /// already changed cell along a different axis. An entity oscillating around a cell boundary
+    /// will not flip between cells every frame.
+    #[inline]
+    pub fn recenter_translation(&self, translation: Vec3) -> Option<(GridCell<P>, Vec3)> {
+        let exceeded = translation.abs().cmpgt(self.maximum_distance_from_origin);
+        if !exceeded.any() {
+            return None;
+        }
+        let (cell, recentered) = self.imprecise_translation_to_grid(translation);
+        let cell = GridCell {
+            x: if exceeded.x { cell.x } else { P::ZERO },
+            y: if exceeded.y { cell.y } else { P::ZERO },
+            z: if exceeded.z { cell.z } else { P::ZERO },
+        };
+        Some((cell, Vec3::select(exceeded, recentered, translation)))
+    }
+
     /// Convert a large translation into a small translation relative to a grid cell.
     #[inline]
     pub fn imprecise_translation_to_grid(&self, input: Vec3) -> (GridCell<P>, Vec3) {
@@ -164,4 +443,315 @@ impl<P: GridPrecision> Grid<P> {
         }
         .into()
     }
+
+    /// Convert a [`GlobalTransform`], relative to the floating origin, into a [`GridCell`] and
+    /// [`Transform`] in this grid. This is the inverse of [`Self::global_transform`], and is useful
+    /// to place a high precision entity at a position only known in render space, like the result
+    /// of a raycast against meshes.
+    ///
+    /// This uses the [`LocalFloatingOrigin`] of this grid, so the result only matches
+    /// [`GlobalTransform`]s computed in the same frame, after
+    /// [`FloatingOriginSystem::LocalFloatingOrigins`]. The precision of the result is limited by
+    /// the single precision [`GlobalTransform`], which is only precise near the floating origin.
+    pub fn from_global_transform(&self, global: &GlobalTransform) -> (GridCell<P>, Transform) {
+        let global = global.affine();
+        let global_64 = DAffine3 {
+            matrix3: global.matrix3.as_dmat3(),
+            translation: global.translation.as_dvec3(),
+        };
+        // The inverse of the transform from the floating origin's grid to this grid, giving a
+        // transform relative to the local floating origin's cell.
+        let local_64 = self.local_floating_origin().grid_transform().inverse() * global_64;
+        let (scale, rotation, translation) = local_64.to_scale_rotation_translation();
+        let (cell_offset, translation) = self.translation_to_grid(translation);
+        let transform = Transform {
+            translation,
+            rotation: rotation.as_quat(),
+            scale: scale.as_vec3(),
+        };
+        (self.local_floating_origin().cell() + cell_offset, transform)
+    }
+
+    /// Format a position in this grid for logging, showing the approximate position relative to
+    /// the origin of the grid, in units scaled to its magnitude, alongside the raw cell. Units are
+    /// assumed to be meters.
+    ///
+    /// ```
+    /// # use big_space::prelude::*;
+    /// # use bevy::prelude::*;
+    /// let grid = Grid::<i64>::new_uniform(10_000.0, 0.0);
+    /// let cell = GridCell::new(15_000_000, 0, -2);
+    /// let position = grid.format_cell(&cell, &Transform::from_xyz(0.0, 1.0, 0.0));
+    /// assert_eq!(
+    ///     position.to_string(),
+    ///     "(1.003 AU, 0.000 AU, -0.000 AU) in cell (15000000, 0, -2)"
+    /// );
+    /// ```
+    pub fn format_cell(&self, cell: &GridCell<P>, transform: &Transform) -> GridPositionDisplay<P> {
+        GridPositionDisplay {
+            position: self.grid_position_double(cell, transform),
+            cell: *cell,
+        }
+    }
+}
+
+/// Displays a position in a [`Grid`] in human-readable units, created with [`Grid::format_cell`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridPositionDisplay<P: GridPrecision> {
+    position: DVec3,
+    cell: GridCell<P>,
+}
+
+impl<P: GridPrecision> std::fmt::Display for GridPositionDisplay<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const UNITS: [(f64, &str); 4] = [
+            (9.460_730_472_580_8e15, "ly"),
+            (1.495_978_707e11, "AU"),
+            (1e3, "km"),
+            (1.0, "m"),
+        ];
+        let magnitude = self.position.abs().max_element();
+        let (scale, unit) = UNITS
+            .into_iter()
+            .find(|(scale, _)| magnitude >= *scale * 0.1)
+            .unwrap_or(UNITS[3]);
+        let DVec3 { x, y, z } = self.position / scale;
+        let cell = self.cell;
+        write!(
+            f,
+            "({x:.3} {unit}, {y:.3} {unit}, {z:.3} {unit}) in cell ({}, {}, {})",
+            cell.x, cell.y, cell.z
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use bevy::{
+        math::{DQuat, DVec3},
+        prelude::*,
+    };
+
+    #[test]
+    fn non_cubic_cells() {
+        let grid = Grid::<i32>::new(Vec3::new(1_000.0, 10.0, 1_000.0), 0.0);
+
+        let (cell, translation) = grid.translation_to_grid(DVec3::new(2_400.0, 24.0, -600.0));
+        assert_eq!(cell, GridCell::new(2, 2, -1));
+        assert!(translation.distance(Vec3::new(400.0, 4.0, 400.0)) < 1e-4);
+
+        let position = grid.grid_position_double(&cell, &Transform::from_translation(translation));
+        assert!(position.distance(DVec3::new(2_400.0, 24.0, -600.0)) < 1e-4);
+    }
+
+    #[test]
+    fn non_cubic_cells_round_trip() {
+        let grid = Grid::<i64>::new(Vec3::new(300.0, 100.0, 300.0), 10.0);
+
+        for input in [
+            DVec3::new(12_345.678, -9_876.5, 0.25),
+            DVec3::new(-1e9, 1e9 + 0.5, 149.9),
+            DVec3::new(150.0, 50.0, -150.0),
+        ] {
+            let (cell, translation) = grid.translation_to_grid(input);
+            assert!(translation
+                .abs()
+                .cmple(grid.maximum_distance_from_origin())
+                .all());
+            let position =
+                grid.grid_position_double(&cell, &Transform::from_translation(translation));
+            assert!(position.distance(input) < 1e-3, "{input} != {position}");
+        }
+    }
+
+    #[test]
+    fn translation_to_grid_batch() {
+        let grid = Grid::<i64>::new_uniform(100.0, 10.0);
+        let positions: Vec<_> = (0..10_000)
+            .map(|i| DVec3::new(i as f64 * 1e6 + 0.5, -(i as f64) * 37.25, 12.0))
+            .collect();
+
+        let mut out = vec![(GridCell::new(1, 2, 3), Vec3::ONE)];
+        grid.translation_to_grid_batch(&positions, &mut out);
+        assert_eq!(out.len(), positions.len());
+        for (position, result) in positions.iter().zip(&out) {
+            assert_eq!(*result, grid.translation_to_grid(*position));
+        }
+
+        grid.translation_to_grid_batch(&[], &mut out);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn offset_from() {
+        let grid = Grid::<i64>::new_uniform(100.0, 0.0);
+        let cell = GridCell::new(-1_000_000_000_000, 7, 0);
+        let leader = Transform::from_xyz(49.0, 0.0, 0.0)
+            .with_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2));
+
+        // Facing -X, so right is -Z and behind is +X.
+        let (follower_cell, follower) =
+            grid.offset_from(&cell, &leader, Vec3::new(200.0, 0.0, 50.0));
+        assert_eq!(follower_cell, GridCell::new(-999_999_999_999, 7, -2));
+        assert!(follower
+            .translation
+            .abs_diff_eq(Vec3::new(-1.0, 0.0, 0.0), 1e-4));
+        assert_eq!(follower.rotation, leader.rotation);
+
+        let leader_position = grid.grid_position_double(&cell, &leader);
+        let follower_position = grid.grid_position_double(&follower_cell, &follower);
+        let distance = (follower_position - leader_position).length();
+        assert!((distance - 50f64.hypot(200.0)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn grid_position_matches_double() {
+        let grid = Grid::<i64>::new_uniform(1_000.0, 0.0);
+        let cell = GridCell::new(1_000_000_000_007, -2_000_017, 7);
+        let transform = Transform::from_xyz(0.25, -499.5, 123.456);
+
+        let single = grid.grid_position(&cell, &transform);
+        let double = grid.grid_position_double(&cell, &transform);
+        assert_eq!(single, double.as_vec3());
+    }
+
+    #[test]
+    fn cell_center_and_aabb() {
+        let grid = Grid::<i32>::new(Vec3::new(10.0, 2.0, 4.0), 0.0);
+        let cell = GridCell::new(3, -1, 0);
+
+        assert_eq!(grid.cell_center(&cell), DVec3::new(30.0, -2.0, 0.0));
+        let (min, max) = grid.cell_aabb(&cell);
+        assert_eq!(min, DVec3::new(25.0, -3.0, -2.0));
+        assert_eq!(max, DVec3::new(35.0, -1.0, 2.0));
+
+        // The local floating origin is at the origin of the grid by default.
+        assert_eq!(grid.cell_center_global(&cell), Vec3::new(30.0, -2.0, 0.0));
+    }
+
+    #[test]
+    fn aabb_to_cells() {
+        let grid = Grid::<i8>::new(Vec3::new(10.0, 2.0, 4.0), 0.0);
+
+        let (min, max) = grid.cell_aabb(&GridCell::new(3, -1, 0));
+        let inset = DVec3::splat(0.01);
+        assert_eq!(
+            grid.aabb_to_cells(min + inset, max - inset),
+            (GridCell::new(3, -1, 0), GridCell::new(3, -1, 0))
+        );
+
+        let (min, max) = grid.aabb_to_cells(DVec3::new(-6.0, 0.5, 1.0), DVec3::new(16.0, 3.5, 9.0));
+        assert_eq!(min, GridCell::new(-1, 0, 0));
+        assert_eq!(max, GridCell::new(2, 2, 2));
+
+        // Swapped corners are handled, and cells out of range are clamped.
+        let (min, max) = grid.aabb_to_cells(DVec3::splat(1e9), DVec3::splat(-1e9));
+        assert_eq!(min, GridCell::new(i8::MIN, i8::MIN, i8::MIN));
+        assert_eq!(max, GridCell::new(i8::MAX, i8::MAX, i8::MAX));
+    }
+
+    #[test]
+    fn from_global_transform_round_trip() {
+        let mut grid = Grid::<i64>::new_uniform(100.0, 0.0);
+        grid.local_floating_origin_mut().set(
+            GridCell::new(1_000_000_000, -3, 7),
+            Vec3::new(12.0, -40.0, 3.5),
+            DQuat::from_rotation_y(0.3),
+        );
+
+        let cell = GridCell::new(1_000_000_002, -3, 6);
+        let transform = Transform::from_xyz(20.0, 1.5, -30.0)
+            .with_rotation(Quat::from_rotation_x(1.0))
+            .with_scale(Vec3::splat(2.0));
+        let global = grid.global_transform(&cell, &transform);
+        let (new_cell, new_transform) = grid.from_global_transform(&global);
+
+        assert_eq!(new_cell, cell);
+        assert!(new_transform.translation.distance(transform.translation) < 1e-3);
+        assert!(new_transform.rotation.angle_between(transform.rotation) < 1e-3);
+        assert!(new_transform.scale.distance(transform.scale) < 1e-4);
+    }
+
+    #[test]
+    fn format_cell() {
+        let grid = Grid::<i32>::new_uniform(100.0, 0.0);
+        let format = |cell, translation| {
+            grid.format_cell(&cell, &Transform::from_translation(translation))
+                .to_string()
+        };
+        assert_eq!(
+            format(GridCell::new(0, 0, 0), Vec3::new(1.5, -2.0, 0.25)),
+            "(1.500 m, -2.000 m, 0.250 m) in cell (0, 0, 0)"
+        );
+        assert_eq!(
+            format(GridCell::new(-12, 3, 0), Vec3::new(1.0, 0.0, 0.0)),
+            "(-1.199 km, 0.300 km, 0.000 km) in cell (-12, 3, 0)"
+        );
+    }
+
+    #[test]
+    fn transform_to_grid_round_trip() {
+        let grid = Grid::<i64>::new_uniform(100.0, 0.0);
+        let position = DVec3::new(1e10 + 12.5, -3.25, 49.0);
+        let rotation = DQuat::from_rotation_y(0.5);
+        let scale = Vec3::new(2.0, 0.5, 3.0);
+
+        let (cell, transform) = grid.transform_to_grid(position, rotation, scale);
+        assert_eq!(transform.rotation, rotation.as_quat());
+        assert_eq!(transform.scale, scale);
+
+        // The floating origin is at the origin of the grid by default.
+        let (global_scale, global_rotation, global_translation) = grid
+            .global_transform(&cell, &transform)
+            .to_scale_rotation_translation();
+        assert!(global_scale.abs_diff_eq(scale, 1e-5));
+        assert!(global_rotation.abs_diff_eq(rotation.as_quat(), 1e-5));
+        assert_eq!(grid.grid_position_double(&cell, &transform), position);
+        assert!(global_translation
+            .as_dvec3()
+            .abs_diff_eq(position, 1e10 * f32::EPSILON as f64));
+    }
+
+    #[test]
+    fn presets() {
+        let ulp = |x: f32| x.next_up() - x;
+        for (grid, precision) in [
+            (Grid::<i32>::meters_human_scale(), 0.000_062),
+            (Grid::<i32>::solar_system(), 0.000_5),
+            (Grid::<i32>::galaxy(), 0.004),
+        ] {
+            let max = grid.maximum_distance_from_origin();
+            assert!(max.cmpgt(grid.cell_edge_length() / 2.0).all());
+            assert!(ulp(max.x) <= precision, "{}", ulp(max.x));
+        }
+    }
+
+    #[test]
+    fn snap() {
+        let grid = Grid::<i32>::new_uniform(10.0, 0.0);
+        let cell = GridCell::new(5, 0, -1);
+
+        let transform = Transform::from_xyz(1.2, -2.6, 4.9).with_scale(Vec3::splat(2.0));
+        let (snapped_cell, snapped) = grid.snap(cell, &transform, Vec3::ONE);
+        assert_eq!(snapped_cell, cell);
+        assert_eq!(snapped.translation, Vec3::new(1.0, -3.0, 5.0));
+        assert_eq!(snapped.scale, transform.scale);
+
+        // Snapping past the edge of the cell moves into the next cell.
+        let transform = Transform::from_xyz(5.3, -4.9, 0.0);
+        let (snapped_cell, snapped) = grid.snap(cell, &transform, Vec3::splat(2.0));
+        assert_eq!(snapped_cell, GridCell::new(6, 0, -1));
+        assert_eq!(snapped.translation, Vec3::new(-4.0, -4.0, 0.0));
+
+        // Snapping by the cell edge length snaps to the nearest cell center.
+        let transform = Transform::from_xyz(5.1, 4.9, -7.0);
+        let (snapped_cell, snapped) = grid.snap(cell, &transform, Vec3::splat(10.0));
+        assert_eq!(snapped_cell, GridCell::new(6, 0, -2));
+        assert_eq!(snapped.translation, Vec3::ZERO);
+
+        // Axes with no increment are not snapped.
+        let (_, snapped) = grid.snap(cell, &transform, Vec3::new(1.0, 0.0, 1.0));
+        assert_eq!(snapped.translation.y, 4.9);
+    }
 }
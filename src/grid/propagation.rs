@@ -1,10 +1,11 @@
 //! Logic for propagating transforms through the hierarchy of grids.
 
 use crate::prelude::*;
-use bevy_ecs::prelude::*;
+use bevy_ecs::{batching::BatchingStrategy, prelude::*};
 use bevy_hierarchy::prelude::*;
-use bevy_reflect::Reflect;
+use bevy_reflect::prelude::*;
 use bevy_transform::prelude::*;
+use std::marker::PhantomData;
 
 /// Marks entities in the big space hierarchy that are themselves roots of a low-precision subtree.
 /// While finding these entities is slow, we only have to do it during hierarchy or archetype
@@ -16,14 +17,146 @@ use bevy_transform::prelude::*;
 #[derive(Component, Default, Reflect)]
 pub struct LowPrecisionRoot;
 
+/// Configures how [`Grid::propagate_high_precision`] splits work between threads.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Default)]
+pub struct BigSpacePropagationConfig {
+    /// The number of entities processed by each parallel task. Defaults to `10_000`.
+    ///
+    /// Smaller batches balance work better across threads when the cost of updating each entity
+    /// varies, while larger batches reduce scheduling overhead. Tune this by measuring your own
+    /// scene on your target hardware.
+    pub batch_size: usize,
+    /// When set, the work of updating entities far from the floating origin is spread across
+    /// frames when the floating origin moves. Disabled by default.
+    pub priority: Option<PropagationPriority>,
+}
+
+/// Spreads the work of [`Grid::propagate_high_precision`] over multiple frames, to reduce the spike
+/// in computation when the floating origin moves to a new cell, and every entity in the grid needs
+/// to be updated.
+///
+/// When an entity needs to be updated only because the floating origin moved, and it is at least
+/// [`Self::min_cell_distance`] cells from the floating origin's cell along any axis, its update is
+/// deferred. At most [`Self::budget`] deferred entities are updated each frame, nearest first, and
+/// the rest are updated on following frames. Entities that move are never deferred.
+///
+/// If the floating origin keeps moving, entities are deferred again every frame, and the nearest
+/// entities would always take the whole budget. To prevent far entities from never being updated,
+/// an entity that has been deferred for more than [`Self::max_deferred_frames`] frames is updated
+/// regardless of the budget. Because deferred entities are far from the floating origin, a
+/// [`GlobalTransform`] that is a few frames late is usually imperceptible. Increase the distance or
+/// the budget, or reduce the maximum number of frames, if you notice far away entities lagging
+/// behind.
+///
+/// Setting [`BigSpacePropagationConfig::priority`] back to `None` updates all deferred entities in
+/// the next frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub struct PropagationPriority {
+    /// The distance from the floating origin's cell, in cells, beyond which updates can be
+    /// deferred.
+    pub min_cell_distance: u64,
+    /// The maximum number of deferred entities to update each frame, in addition to entities that
+    /// have been deferred for too long.
+    pub budget: usize,
+    /// The maximum number of frames an entity's update can be deferred. Entities that have been
+    /// deferred for longer are updated even if the budget is exceeded.
+    pub max_deferred_frames: u32,
+}
+
+impl Default for PropagationPriority {
+    fn default() -> Self {
+        Self {
+            min_cell_distance: 8,
+            budget: 10_000,
+            max_deferred_frames: 8,
+        }
+    }
+}
+
+/// An entity whose update was deferred by [`PropagationPriority`].
+#[derive(Debug, Clone, Copy)]
+pub struct DeferredPropagation {
+    entity: Entity,
+    /// The distance to the floating origin, from the latest frame it was deferred.
+    distance: u128,
+    /// The number of frames since the entity was first deferred.
+    frames: u32,
+}
+
+impl Default for BigSpacePropagationConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 10_000,
+            priority: None,
+        }
+    }
+}
+
+impl BigSpacePropagationConfig {
+    fn batching_strategy(&self) -> BatchingStrategy {
+        BatchingStrategy::fixed(self.batch_size.max(1))
+    }
+}
+
+/// A shortlist of high precision entities whose [`GridCell`], [`Transform`], or [`Parent`] changed
+/// since the last time [`Grid::propagate_high_precision`] ran. This is collected by
+/// [`GridCell::recenter_large_transforms`], which already has to visit every changed entity.
+///
+/// Like `ChangedGridHashes`, this is a manual collection instead of a `Changed` query, because a
+/// query that uses `Changed` still has to iterate over every single entity. Propagation only needs
+/// to visit every entity in a grid if the floating origin moved relative to that grid. In grids
+/// where it did not, only the entities in this list are updated, so static content costs close to
+/// nothing.
+#[derive(Resource)]
+pub struct ChangedGridEntities<P: GridPrecision> {
+    pub(crate) list: Vec<Entity>,
+    spooky: PhantomData<P>,
+}
+
+impl<P: GridPrecision> Default for ChangedGridEntities<P> {
+    fn default() -> Self {
+        Self {
+            list: Vec::new(),
+            spooky: PhantomData,
+        }
+    }
+}
+
+/// Scratch state of [`Grid::propagate_high_precision`], collected on each thread to avoid
+/// contention.
+#[derive(Default)]
+pub struct PropagationThreadLocal {
+    deferred: Vec<(u128, Entity)>,
+    skipped: usize,
+    recomputed: usize,
+}
+
+impl PropagationThreadLocal {
+    fn count(&mut self, recomputed: bool) {
+        match recomputed {
+            true => self.recomputed += 1,
+            false => self.skipped += 1,
+        }
+    }
+}
+
 impl<P: GridPrecision> Grid<P> {
     /// Update the `GlobalTransform` of entities with a [`GridCell`], using the [`Grid`] the entity
     /// belongs to.
     pub fn propagate_high_precision(
         mut stats: ResMut<crate::timing::PropagationStats>,
-        grids: Query<&Grid<P>>,
+        config: Res<BigSpacePropagationConfig>,
+        grids: Query<(&Grid<P>, Option<&Children>)>,
+        parents: Query<&Parent>,
+        mut changed: ResMut<ChangedGridEntities<P>>,
+        mut locals: Local<(
+            Vec<DeferredPropagation>,
+            bevy_utils::Parallel<PropagationThreadLocal>,
+        )>,
         mut entities: ParamSet<(
             Query<(
+                Entity,
                 Ref<GridCell<P>>,
                 Ref<Transform>,
                 Ref<Parent>,
@@ -33,38 +166,234 @@ impl<P: GridPrecision> Grid<P> {
         )>,
     ) {
         let start = bevy_utils::Instant::now();
+        let priority = config.priority;
+        let (deferred, thread_locals) = &mut *locals;
+        let grid_of = |entity: Entity| grids.get(entity).map(|(grid, _)| grid);
+        let grid_children = || {
+            grids
+                .iter()
+                .filter_map(|(grid, children)| Some((grid, children?)))
+        };
 
-        // Performance note: I've also tried to iterate over each grid's children at once, to avoid
-        // the grid and parent lookup, but that made things worse because it prevented dumb
-        // parallelism. The only thing I can see to make this faster is archetype change detection.
-        // Change filters are not archetype filters, so they scale with the total number of entities
-        // that match the query, regardless of change.
-        entities
-            .p0()
-            .par_iter_mut()
-            .for_each(|(cell, transform, parent, mut global_transform)| {
-                if let Ok(grid) = grids.get(parent.get()) {
-                    // Optimization: we don't need to recompute the transforms if the entity hasn't
-                    // moved and the floating origin's local origin in that grid hasn't changed.
-                    //
-                    // This also ensures we don't trigger change detection on GlobalTransforms when
-                    // they haven't changed.
-                    //
-                    // This check can have a big impact on reducing computations for entities in the
-                    // same grid as the floating origin, i.e. the main camera. It also means that as
-                    // the floating origin moves between cells, that could suddenly cause a spike in
-                    // the amount of computation needed that grid. In the future, we might be able
-                    // to spread that work across grids, entities far away can maybe be delayed for
-                    // a grid or two without being noticeable.
-                    if !grid.local_floating_origin().is_local_origin_unchanged()
-                        || transform.is_changed()
-                        || cell.is_changed()
-                        || parent.is_changed()
+        let update = |grid: &Grid<P>,
+                      entity: Entity,
+                      cell: Ref<GridCell<P>>,
+                      transform: Ref<Transform>,
+                      parent: Ref<Parent>,
+                      mut global_transform: Mut<GlobalTransform>,
+                      newly_deferred: &mut Vec<(u128, Entity)>|
+         -> bool {
+            // Optimization: we don't need to recompute the transforms if the entity hasn't moved
+            // and the floating origin's local origin in that grid hasn't changed.
+            //
+            // This also ensures we don't trigger change detection on GlobalTransforms when they
+            // haven't changed.
+            //
+            // This check can have a big impact on reducing computations for entities in the same
+            // grid as the floating origin, i.e. the main camera. It also means that as the floating
+            // origin moves between cells, that could suddenly cause a spike in the amount of
+            // computation needed that grid. See `PropagationPriority` to spread this work across
+            // frames.
+            let moved = transform.is_changed() || cell.is_changed() || parent.is_changed();
+            if moved {
+                *global_transform = grid.global_transform(&cell, &transform);
+            } else if !grid.local_floating_origin().is_local_origin_unchanged() {
+                if let Some(priority) = priority {
+                    let distance =
+                        CellLodDistance::new(&cell, &grid.local_floating_origin().cell()).chebyshev;
+                    if distance >= priority.min_cell_distance as u128 {
+                        newly_deferred.push((distance, entity));
+                        return false;
+                    }
+                }
+                *global_transform = grid.global_transform(&cell, &transform);
+            } else {
+                return false;
+            }
+            true
+        };
+
+        if stats.per_root_enabled() {
+            // Timing each root requires knowing which root each entity belongs to, which means
+            // walking the hierarchy and running serially. Cache the root of each grid. Every entity
+            // is visited, so the shortlist of changed entities is not needed.
+            changed.list.clear();
+            let mut grid_roots = bevy_utils::HashMap::<Entity, Entity>::default();
+            let mut per_root = bevy_utils::HashMap::<Entity, std::time::Duration>::default();
+            let local = &mut *thread_locals.borrow_local_mut();
+            for (entity, cell, transform, parent, global_transform) in entities.p0().iter_mut() {
+                let Ok(grid) = grid_of(parent.get()) else {
+                    continue;
+                };
+                let root = *grid_roots.entry(parent.get()).or_insert_with(|| {
+                    parents
+                        .iter_ancestors(parent.get())
+                        .last()
+                        .unwrap_or(parent.get())
+                });
+                let entity_start = bevy_utils::Instant::now();
+                let updated = update(
+                    grid,
+                    entity,
+                    cell,
+                    transform,
+                    parent,
+                    global_transform,
+                    &mut local.deferred,
+                );
+                local.count(updated);
+                *per_root.entry(root).or_default() += entity_start.elapsed();
+            }
+            stats.high_precision_propagation_per_root = per_root;
+        } else {
+            // Entities only need to be updated if they moved, or if the floating origin moved
+            // relative to their grid. Entities in grids where the floating origin did not move are
+            // only visited if they are in the shortlist of changed entities, so a static scene
+            // costs close to nothing.
+            let is_dirty =
+                |grid: &Grid<P>| !grid.local_floating_origin().is_local_origin_unchanged();
+            let total = entities.p0().iter().len();
+            let dirty_children: usize = grid_children()
+                .filter(|(grid, _)| is_dirty(grid))
+                .map(|(_, children)| children.len())
+                .sum();
+
+            if dirty_children * 2 >= total {
+                // Most entities are in grids that need to be updated, for example when the floating
+                // origin moved to another cell. Visiting every entity in parallel is faster than
+                // visiting the children of each grid.
+                //
+                // Performance note: I've also tried to iterate over each grid's children at once,
+                // to avoid the grid and parent lookup, but that made things worse because it
+                // prevented dumb parallelism.
+                entities
+                    .p0()
+                    .par_iter_mut()
+                    .batching_strategy(config.batching_strategy())
+                    .for_each(|(entity, cell, transform, parent, global_transform)| {
+                        let Ok(grid) = grid_of(parent.get()) else {
+                            return;
+                        };
+                        if !is_dirty(grid) {
+                            return;
+                        }
+                        thread_locals.scope(|local| {
+                            let updated = update(
+                                grid,
+                                entity,
+                                cell,
+                                transform,
+                                parent,
+                                global_transform,
+                                &mut local.deferred,
+                            );
+                            local.count(updated);
+                        });
+                    });
+            } else {
+                let local = &mut *thread_locals.borrow_local_mut();
+                let mut query = entities.p0();
+                for (grid, children) in grid_children().filter(|(grid, _)| is_dirty(grid)) {
+                    let mut children = query.iter_many_mut(children);
+                    while let Some((entity, cell, transform, parent, global_transform)) =
+                        children.fetch_next()
                     {
-                        *global_transform = grid.global_transform(&cell, &transform);
+                        let updated = update(
+                            grid,
+                            entity,
+                            cell,
+                            transform,
+                            parent,
+                            global_transform,
+                            &mut local.deferred,
+                        );
+                        local.count(updated);
                     }
                 }
-            });
+            }
+
+            // Entities that moved in grids that were not visited above.
+            let mut moved = 0;
+            let mut query = entities.p0();
+            for entity in changed.list.drain(..) {
+                let Ok((_, cell, transform, parent, mut global_transform)) = query.get_mut(entity)
+                else {
+                    continue;
+                };
+                let Ok(grid) = grid_of(parent.get()) else {
+                    continue;
+                };
+                if !is_dirty(grid) {
+                    *global_transform = grid.global_transform(&cell, &transform);
+                    moved += 1;
+                }
+            }
+            thread_locals.borrow_local_mut().recomputed += moved;
+
+            // Entities that were not visited were skipped.
+            let visited: usize = thread_locals
+                .iter_mut()
+                .map(|local| local.skipped + local.recomputed)
+                .sum();
+            thread_locals.borrow_local_mut().skipped += total.saturating_sub(visited);
+        }
+
+        // Deferred entities
+        //
+        // Update overdue entities, then the nearest deferred entities up to the budget, leaving the
+        // rest for following frames. An entity can be deferred again before its update if the
+        // origin keeps moving, so only keep a single entry for each entity, with its latest
+        // distance and the age of its oldest entry.
+        let (mut skipped, mut recomputed) = (0, 0);
+        for local in thread_locals.iter_mut() {
+            deferred.extend(local.deferred.drain(..).map(|(distance, entity)| {
+                DeferredPropagation {
+                    entity,
+                    distance,
+                    frames: 0,
+                }
+            }));
+            skipped += std::mem::take(&mut local.skipped);
+            recomputed += std::mem::take(&mut local.recomputed);
+        }
+        let flush = match priority {
+            Some(priority) => {
+                // Stable, so older entries of the same entity stay first.
+                deferred.sort_by_key(|deferred| deferred.entity);
+                deferred.dedup_by(|newer, older| {
+                    let same = newer.entity == older.entity;
+                    if same {
+                        older.distance = newer.distance;
+                    }
+                    same
+                });
+                deferred
+                    .iter_mut()
+                    .for_each(|deferred| deferred.frames += 1);
+                let is_overdue =
+                    |deferred: &DeferredPropagation| deferred.frames > priority.max_deferred_frames;
+                deferred
+                    .sort_unstable_by_key(|deferred| (!is_overdue(deferred), deferred.distance));
+                let overdue = deferred.iter().take_while(|d| is_overdue(d)).count();
+                (overdue + priority.budget).min(deferred.len())
+            }
+            // Deferral was disabled, don't leave any entities behind.
+            None => deferred.len(),
+        };
+        let mut query = entities.p0();
+        for DeferredPropagation { entity, .. } in deferred.drain(..flush) {
+            let Ok((_, cell, transform, parent, mut global_transform)) = query.get_mut(entity)
+            else {
+                continue;
+            };
+            if let Ok(grid) = grid_of(parent.get()) {
+                *global_transform = grid.global_transform(&cell, &transform);
+                skipped = skipped.saturating_sub(1);
+                recomputed += 1;
+            }
+        }
+        stats.high_precision_skipped = skipped;
+        stats.high_precision_recomputed = recomputed;
 
         // Root grids
         //
@@ -90,20 +419,35 @@ impl<P: GridPrecision> Grid<P> {
     }
 
     /// Marks entities with [`LowPrecisionRoot`]. Handles adding and removing the component.
+    ///
+    /// Entities that had their [`GridCell`] removed are also checked, so they can be demoted to
+    /// low precision.
     pub fn tag_low_precision_roots(
         mut stats: ResMut<crate::timing::PropagationStats>,
         mut commands: Commands,
         valid_parent: Query<(), (With<GridCell<P>>, With<GlobalTransform>, With<Children>)>,
-        unmarked: Query<
-            (Entity, &Parent),
-            (
-                With<Transform>,
-                With<GlobalTransform>,
-                Without<GridCellAny>,
-                Without<LowPrecisionRoot>,
-                Or<(Changed<Parent>, Added<Transform>)>,
-            ),
-        >,
+        mut unmarked: ParamSet<(
+            Query<
+                (Entity, &Parent),
+                (
+                    With<Transform>,
+                    With<GlobalTransform>,
+                    Without<GridCellAny>,
+                    Without<LowPrecisionRoot>,
+                    Or<(Changed<Parent>, Added<Transform>)>,
+                ),
+            >,
+            Query<
+                (Entity, &Parent),
+                (
+                    With<Transform>,
+                    With<GlobalTransform>,
+                    Without<GridCellAny>,
+                    Without<LowPrecisionRoot>,
+                ),
+            >,
+        )>,
+        mut demoted: RemovedComponents<GridCellAny>,
         invalidated: Query<
             Entity,
             (
@@ -119,7 +463,17 @@ impl<P: GridPrecision> Grid<P> {
         has_possibly_invalid_parent: Query<(Entity, &Parent), With<LowPrecisionRoot>>,
     ) {
         let start = bevy_utils::Instant::now();
-        for (entity, parent) in unmarked.iter() {
+        for (entity, parent) in unmarked.p0().iter() {
+            if valid_parent.contains(parent.get()) {
+                commands.entity(entity).insert(LowPrecisionRoot);
+            }
+        }
+
+        let unmarked = unmarked.p1();
+        for (entity, parent) in demoted
+            .read()
+            .filter_map(|entity| unmarked.get(entity).ok())
+        {
             if valid_parent.contains(parent.get()) {
                 commands.entity(entity).insert(LowPrecisionRoot);
             }
@@ -150,7 +504,7 @@ impl<P: GridPrecision> Grid<P> {
                 Or<(With<Grid<P>>, With<GridCell<P>>)>,
             ),
         >,
-        roots: Query<(Entity, &Parent), With<LowPrecisionRoot>>,
+        roots: Query<(Entity, &Parent, Ref<LowPrecisionRoot>)>,
         transform_query: Query<
             (Ref<Transform>, &mut GlobalTransform, Option<&Children>),
             (
@@ -171,38 +525,42 @@ impl<P: GridPrecision> Grid<P> {
         >,
     ) {
         let start = bevy_utils::Instant::now();
-        let update_transforms = |low_precision_root, parent_transform: Ref<GlobalTransform>| {
-            // High precision global transforms are change-detected, and are only updated if that
-            // entity has moved relative to the floating origin's grid cell.
-            let changed = parent_transform.is_changed();
-
-            // SAFETY:
-            // - Unlike the bevy version of this, we do not iterate over all children of the root,
-            //   and manually verify each child has a parent component that points back to the same
-            //   entity. Instead, we query the roots directly, so we know they are unique.
-            // - We may operate as if all descendants are consistent, since `propagate_recursive`
-            //   will panic before continuing to propagate if it encounters an entity with
-            //   inconsistent parentage.
-            // - Since each root entity is unique and the hierarchy is consistent and forest-like,
-            //   other root entities' `propagate_recursive` calls will not conflict with this one.
-            // - Since this is the only place where `transform_query` gets used, there will be no
-            //   conflicting fetches elsewhere.
-            unsafe {
-                Self::propagate_recursive(
-                    &parent_transform,
-                    &transform_query,
-                    &parent_query,
-                    low_precision_root,
-                    changed,
-                );
-            }
-        };
+        let update_transforms =
+            |low_precision_root, parent_transform: Ref<GlobalTransform>, added| {
+                // High precision global transforms are change-detected, and are only updated if that
+                // entity has moved relative to the floating origin's grid cell. Newly tagged roots,
+                // like entities that were demoted from high precision, are always updated.
+                let changed = parent_transform.is_changed() || added;
 
-        roots.par_iter().for_each(|(low_precision_root, parent)| {
-            if let Ok(parent_transform) = root_parents.get(parent.get()) {
-                update_transforms(low_precision_root, parent_transform);
-            }
-        });
+                // SAFETY:
+                // - Unlike the bevy version of this, we do not iterate over all children of the root,
+                //   and manually verify each child has a parent component that points back to the same
+                //   entity. Instead, we query the roots directly, so we know they are unique.
+                // - We may operate as if all descendants are consistent, since `propagate_recursive`
+                //   will panic before continuing to propagate if it encounters an entity with
+                //   inconsistent parentage.
+                // - Since each root entity is unique and the hierarchy is consistent and forest-like,
+                //   other root entities' `propagate_recursive` calls will not conflict with this one.
+                // - Since this is the only place where `transform_query` gets used, there will be no
+                //   conflicting fetches elsewhere.
+                unsafe {
+                    Self::propagate_recursive(
+                        &parent_transform,
+                        &transform_query,
+                        &parent_query,
+                        low_precision_root,
+                        changed,
+                    );
+                }
+            };
+
+        roots
+            .par_iter()
+            .for_each(|(low_precision_root, parent, tag)| {
+                if let Ok(parent_transform) = root_parents.get(parent.get()) {
+                    update_transforms(low_precision_root, parent_transform, tag.is_added());
+                }
+            });
 
         stats.low_precision_propagation += start.elapsed();
     }
@@ -305,6 +663,7 @@ impl<P: GridPrecision> Grid<P> {
 
 #[cfg(test)]
 mod tests {
+    use super::{BigSpacePropagationConfig, PropagationPriority};
     use crate::prelude::*;
     use bevy::prelude::*;
 
@@ -343,4 +702,326 @@ mod tests {
             GlobalTransform::from_xyz(2004.0, 2005.0, 2006.0)
         )
     }
+
+    #[test]
+    fn per_root_propagation_stats() {
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                for _ in 0..2 {
+                    commands.spawn_big_space_default::<i32>(|root| {
+                        root.spawn_spatial(FloatingOrigin);
+                        root.spawn_spatial(GridCell::new(1, 1, 1));
+                    });
+                }
+            });
+        app.world_mut()
+            .resource_mut::<crate::timing::PropagationStats>()
+            .set_per_root_enabled(true);
+
+        app.update();
+
+        let roots: Vec<Entity> = app
+            .world_mut()
+            .query_filtered::<Entity, With<BigSpace>>()
+            .iter(app.world())
+            .collect();
+        let stats = app.world().resource::<crate::timing::PropagationStats>();
+        assert!(stats.per_root_enabled());
+        assert_eq!(stats.high_precision_propagation_per_root().len(), 2);
+        for root in roots {
+            assert!(stats
+                .high_precision_propagation_per_root()
+                .contains_key(&root));
+        }
+    }
+
+    #[test]
+    fn skipped_and_recomputed_stats() {
+        #[derive(Component)]
+        struct Moving;
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i32>(|root| {
+                    root.spawn_spatial(FloatingOrigin);
+                    root.spawn_spatial((GridCell::new(1, 0, 0), Moving));
+                    root.spawn_spatial(GridCell::new(2, 0, 0));
+                    root.spawn_spatial(GridCell::new(3, 0, 0));
+                });
+            });
+        let counts = |app: &App| {
+            let stats = app.world().resource::<crate::timing::PropagationStats>();
+            (
+                stats.high_precision_skipped(),
+                stats.high_precision_recomputed(),
+            )
+        };
+
+        app.update();
+        assert_eq!(counts(&app), (0, 4));
+
+        app.update();
+        assert_eq!(counts(&app), (4, 0));
+
+        let mut moving = app
+            .world_mut()
+            .query_filtered::<&mut Transform, With<Moving>>();
+        moving.single_mut(app.world_mut()).translation.x += 1.0;
+        app.update();
+        assert_eq!(counts(&app), (3, 1));
+    }
+
+    #[test]
+    fn only_changed_grids_are_updated() {
+        #[derive(Resource)]
+        struct Spawned {
+            moving: Entity,
+            grid: Entity,
+            in_grid: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 0.0), |root| {
+                    root.spawn_spatial(FloatingOrigin);
+                    root.spawn_spatial(GridCell::new(1, 0, 0));
+                    let moving = root.spawn_spatial(GridCell::new(2, 0, 0)).id();
+                    let mut in_grid = Entity::PLACEHOLDER;
+                    let grid = root.with_grid(Grid::new_uniform(10.0, 0.0), |grid| {
+                        grid.insert(GridCell::new(5, 0, 0));
+                        in_grid = grid.spawn_spatial(GridCell::new(1, 0, 0)).id();
+                    });
+                    root.commands().insert_resource(Spawned {
+                        moving,
+                        grid,
+                        in_grid,
+                    });
+                });
+            });
+        app.update();
+
+        let Spawned {
+            moving,
+            grid,
+            in_grid,
+        } = *app.world().resource::<Spawned>();
+        app.world_mut()
+            .get_mut::<Transform>(moving)
+            .unwrap()
+            .translation
+            .y = 1.0;
+        app.world_mut().get_mut::<Transform>(grid).unwrap().rotation =
+            Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+        app.update();
+
+        // Only the floating origin's grid is unchanged, so the static entity and the floating
+        // origin are skipped without being visited.
+        let stats = app.world().resource::<crate::timing::PropagationStats>();
+        assert_eq!(stats.high_precision_skipped(), 2);
+        assert_eq!(stats.high_precision_recomputed(), 3);
+
+        let global = |entity| {
+            app.world()
+                .get::<GlobalTransform>(entity)
+                .unwrap()
+                .translation()
+        };
+        assert_eq!(global(moving), Vec3::new(20.0, 1.0, 0.0));
+        assert!(global(in_grid).distance(Vec3::new(50.0, 0.0, -10.0)) < 1e-4);
+    }
+
+    #[test]
+    fn moved_after_recentering() {
+        #[derive(Component)]
+        struct Late;
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 0.0), |root| {
+                    root.spawn_spatial(FloatingOrigin);
+                    root.spawn_spatial(Late);
+                });
+            });
+        app.update();
+
+        // Moves the entity after the changed entities were collected for this frame.
+        app.add_systems(
+            PostUpdate,
+            (|mut late: Query<&mut Transform, With<Late>>, mut done: Local<bool>| {
+                if !*done {
+                    late.single_mut().translation.x = 3.0;
+                    *done = true;
+                }
+            })
+            .after(FloatingOriginSystem::RecenterLargeTransforms)
+            .before(FloatingOriginSystem::PropagateHighPrecision),
+        );
+        let mut late = app
+            .world_mut()
+            .query_filtered::<&GlobalTransform, With<Late>>();
+
+        app.update();
+        app.update();
+        assert_eq!(late.single(app.world()).translation().x, 3.0);
+    }
+
+    #[test]
+    fn fixed_batch_size() {
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default())
+            .insert_resource(BigSpacePropagationConfig {
+                batch_size: 2,
+                ..default()
+            })
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 0.0), |root| {
+                    root.spawn_spatial(FloatingOrigin);
+                    for x in 1..10 {
+                        root.spawn_spatial(GridCell::new(x, 0, 0));
+                    }
+                });
+            });
+        app.update();
+
+        let mut query = app
+            .world_mut()
+            .query_filtered::<(&GridCell<i32>, &GlobalTransform), Without<FloatingOrigin>>();
+        let mut count = 0;
+        for (cell, global_transform) in query.iter(app.world()) {
+            assert_eq!(global_transform.translation().x, cell.x as f32 * 10.0);
+            count += 1;
+        }
+        assert_eq!(count, 9);
+
+        let config = app.world().resource::<BigSpacePropagationConfig>();
+        assert_eq!(config.batching_strategy().batch_size_limits, 2..2);
+        let defaults = BigSpacePropagationConfig::default().batching_strategy();
+        assert_eq!(defaults.batch_size_limits, 10_000..10_000);
+        let zero = BigSpacePropagationConfig {
+            batch_size: 0,
+            ..default()
+        };
+        assert_eq!(zero.batching_strategy().batch_size_limits, 1..1);
+    }
+
+    #[test]
+    fn deferred_propagation() {
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default())
+            .insert_resource(BigSpacePropagationConfig {
+                priority: Some(PropagationPriority {
+                    min_cell_distance: 5,
+                    budget: 2,
+                    max_deferred_frames: 8,
+                }),
+                ..default()
+            })
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 0.0), |root| {
+                    root.spawn_spatial(FloatingOrigin);
+                    for x in 1..=10 {
+                        root.spawn_spatial(GridCell::new(x, 0, 0));
+                    }
+                });
+            });
+        app.update();
+
+        let mut origin = app
+            .world_mut()
+            .query_filtered::<&mut GridCell<i32>, With<FloatingOrigin>>();
+        origin.single_mut(app.world_mut()).x = 1;
+
+        let mut query = app
+            .world_mut()
+            .query_filtered::<(&GridCell<i32>, &GlobalTransform), Without<FloatingOrigin>>();
+        let mut updated = |app: &mut App| {
+            let mut updated: Vec<i32> = query
+                .iter(app.world())
+                .filter(|(cell, global)| global.translation().x == (cell.x - 1) as f32 * 10.0)
+                .map(|(cell, _)| cell.x)
+                .collect();
+            updated.sort();
+            updated
+        };
+
+        // Entities 6 to 10 are at least 5 cells from the new origin, and are deferred.
+        app.update();
+        assert_eq!(updated(&mut app), vec![1, 2, 3, 4, 5, 6, 7]);
+        app.update();
+        assert_eq!(updated(&mut app), vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        app.update();
+        assert_eq!(updated(&mut app), (1..=10).collect::<Vec<_>>());
+
+        // Disabling deferral updates all pending entities, instead of leaving them stale.
+        let mut origin = app
+            .world_mut()
+            .query_filtered::<&mut GridCell<i32>, With<FloatingOrigin>>();
+        origin.single_mut(app.world_mut()).x = 0;
+        app.update();
+        let mut all_updated = |app: &mut App| {
+            query
+                .iter(app.world())
+                .all(|(cell, global)| global.translation().x == cell.x as f32 * 10.0)
+        };
+        assert!(!all_updated(&mut app));
+        app.world_mut()
+            .resource_mut::<BigSpacePropagationConfig>()
+            .priority = None;
+        app.update();
+        assert!(all_updated(&mut app));
+    }
+
+    #[test]
+    fn deferred_propagation_does_not_starve() {
+        let max_deferred_frames = 3;
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default())
+            .insert_resource(BigSpacePropagationConfig {
+                priority: Some(PropagationPriority {
+                    min_cell_distance: 5,
+                    budget: 1,
+                    max_deferred_frames,
+                }),
+                ..default()
+            })
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 0.0), |root| {
+                    root.spawn_spatial(FloatingOrigin);
+                    for x in 100..110 {
+                        root.spawn_spatial(GridCell::new(x, 0, 0));
+                    }
+                });
+            });
+        app.update();
+
+        let mut origin = app
+            .world_mut()
+            .query_filtered::<&mut GridCell<i32>, With<FloatingOrigin>>();
+        let mut query = app
+            .world_mut()
+            .query_filtered::<(&GridCell<i32>, &GlobalTransform), Without<FloatingOrigin>>();
+        let mut last_updated = bevy::utils::HashMap::<i32, u32>::default();
+
+        // The origin moves every frame, so every entity is deferred again every frame, and the
+        // nearest entity would take the whole budget.
+        for frame in 1..=20 {
+            origin.single_mut(app.world_mut()).x = frame as i32;
+            app.update();
+            for (cell, global) in query.iter(app.world()) {
+                if global.translation().x == (cell.x - frame as i32) as f32 * 10.0 {
+                    last_updated.insert(cell.x, frame);
+                }
+                let last = last_updated.get(&cell.x).copied().unwrap_or(0);
+                assert!(
+                    frame - last <= max_deferred_frames,
+                    "cell {} was last updated in frame {last}, now {frame}",
+                    cell.x
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,222 @@
+//! Traversal of the [`GridCell`]s intersected by a ray, using a 3D DDA.
+
+use crate::prelude::*;
+use bevy_math::{prelude::*, DVec3};
+
+/// A [`GridCell`] intersected by a ray, visited by [`GridRayCells`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridRayCell<P: GridPrecision> {
+    /// The cell intersected by the ray.
+    pub cell: GridCell<P>,
+    /// The distance along the ray where it enters this cell.
+    pub enter: f64,
+    /// The distance along the ray where it exits this cell.
+    pub exit: f64,
+}
+
+/// A ray with a high precision origin in a [`Grid`], computed from a ray in the floating origin's
+/// frame with [`Grid::ray_to_grid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridRay<P: GridPrecision> {
+    /// The cell containing the origin of the ray.
+    pub cell: GridCell<P>,
+    /// The origin of the ray, relative to the center of [`Self::cell`].
+    pub origin: Vec3,
+    /// The normalized direction of the ray, in the grid.
+    pub direction: Vec3,
+}
+
+/// An iterator over the [`GridCell`]s intersected by a ray, in the order they are visited, using
+/// the voxel traversal algorithm described by Amanatides and Woo.
+///
+/// Constructed with [`Grid::ray_cells`]. Traversal is done relative to the floating origin's cell
+/// in the grid, so precision does not depend on how far the ray is from the origin of the grid.
+#[derive(Debug, Clone)]
+pub struct GridRayCells<P: GridPrecision> {
+    cell: GridCell<P>,
+    step: IVec3,
+    t_max: DVec3,
+    t_delta: DVec3,
+    t: f64,
+    max_distance: f64,
+}
+
+impl<P: GridPrecision> Iterator for GridRayCells<P> {
+    type Item = GridRayCell<P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.t.is_finite() || self.t > self.max_distance {
+            return None;
+        }
+
+        let exit = self.t_max.min_element();
+        let item = GridRayCell {
+            cell: self.cell,
+            enter: self.t,
+            exit,
+        };
+
+        let axis = if self.t_max.x == exit {
+            0
+        } else if self.t_max.y == exit {
+            1
+        } else {
+            2
+        };
+        let mut offset = IVec3::ZERO;
+        offset[axis] = self.step[axis];
+        self.cell += offset;
+        self.t = exit;
+        self.t_max[axis] += self.t_delta[axis];
+
+        Some(item)
+    }
+}
+
+impl<P: GridPrecision> Grid<P> {
+    /// Iterate over the cells in this grid intersected by a `ray` in the floating origin's frame,
+    /// up to `max_distance` along the ray. This is the same space as the [`GlobalTransform`]s
+    /// computed by this plugin, so you can use rays computed from a camera that is rendering this
+    /// [`BigSpace`].
+    ///
+    /// [`GlobalTransform`]: bevy_transform::prelude::GlobalTransform
+    pub fn ray_cells(&self, ray: Ray3d, max_distance: f32) -> GridRayCells<P> {
+        let (origin, direction) = self.ray_to_local(ray);
+        self.ray_cells_local(origin, direction, max_distance as f64)
+    }
+
+    /// Convert a `ray` in the floating origin's frame into a high precision [`GridRay`] in this
+    /// grid. Rays from picking backends, or from [`Camera::viewport_to_world`], are in the
+    /// floating origin's frame, because they are computed from [`GlobalTransform`]s.
+    ///
+    /// The origin of the ray is found relative to the floating origin's cell in this grid, so the
+    /// ray stays precise no matter how far the floating origin is from the origin of the grid.
+    ///
+    /// [`Camera::viewport_to_world`]: https://docs.rs/bevy/0.15/bevy/render/camera/struct.Camera.html#method.viewport_to_world
+    /// [`GlobalTransform`]: bevy_transform::prelude::GlobalTransform
+    pub fn ray_to_grid(&self, ray: Ray3d) -> GridRay<P> {
+        let (origin, direction) = self.ray_to_local(ray);
+        let (cell, origin) = self.translation_to_grid(origin);
+        GridRay {
+            cell: self.local_floating_origin().cell() + cell,
+            origin,
+            direction: direction.normalize().as_vec3(),
+        }
+    }
+
+    /// Iterate over the cells in this grid intersected by a high precision [`GridRay`], up to
+    /// `max_distance` along the ray. See [`Self::ray_cells`].
+    pub fn grid_ray_cells(&self, ray: &GridRay<P>, max_distance: f64) -> GridRayCells<P> {
+        let cell_offset = ray.cell - self.local_floating_origin().cell();
+        let origin = self.cell_to_float(&cell_offset) + ray.origin.as_dvec3();
+        self.ray_cells_local(origin, ray.direction.as_dvec3(), max_distance)
+    }
+
+    /// Convert a `ray` in the floating origin's frame into an origin and direction in this grid,
+    /// relative to the floating origin's cell in this grid.
+    pub(crate) fn ray_to_local(&self, ray: Ray3d) -> (DVec3, DVec3) {
+        let to_local = self.local_floating_origin().grid_transform().inverse();
+        (
+            to_local.transform_point3(ray.origin.as_dvec3()),
+            to_local.transform_vector3(ray.direction.as_dvec3()),
+        )
+    }
+
+    /// Iterate over the cells intersected by a ray, with an `origin` and `direction` relative to
+    /// the floating origin's cell in this grid.
+    pub(crate) fn ray_cells_local(
+        &self,
+        origin: DVec3,
+        direction: DVec3,
+        max_distance: f64,
+    ) -> GridRayCells<P> {
+        let edge = self.cell_edge_length().as_dvec3();
+        // Cells are centered on their position, shift by half a cell so cell boundaries fall on
+        // integer values.
+        let shifted = origin / edge + 0.5;
+        let first = shifted.floor();
+
+        let mut step = IVec3::ZERO;
+        let mut t_max = DVec3::INFINITY;
+        let mut t_delta = DVec3::INFINITY;
+        for axis in 0..3 {
+            let d = direction[axis];
+            if d > 0.0 {
+                step[axis] = 1;
+                t_delta[axis] = edge[axis] / d;
+                t_max[axis] = (first[axis] + 1.0 - shifted[axis]) * t_delta[axis];
+            } else if d < 0.0 {
+                step[axis] = -1;
+                t_delta[axis] = edge[axis] / -d;
+                t_max[axis] = (shifted[axis] - first[axis]) * t_delta[axis];
+            }
+        }
+
+        let cell = self.local_floating_origin().cell()
+            + GridCell::new(
+                P::from_f64(first.x),
+                P::from_f64(first.y),
+                P::from_f64(first.z),
+            );
+
+        GridRayCells {
+            cell,
+            step,
+            t_max,
+            t_delta,
+            t: 0.0,
+            max_distance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn ray_cells_diagonal() {
+        let grid = Grid::<i32>::new_uniform(1.0, 0.0);
+        let ray = Ray3d::new(Vec3::ZERO, Dir3::new(Vec3::new(1.0, 1.0, 0.0)).unwrap());
+        let cells: Vec<_> = grid.ray_cells(ray, 2.0).map(|c| c.cell).collect();
+
+        assert_eq!(cells.first(), Some(&GridCell::ZERO));
+        assert!(cells.contains(&GridCell::new(1, 1, 0)));
+        assert!(cells.iter().all(|cell| cell.z == 0));
+        // Each step moves exactly one cell along a single axis
+        for pair in cells.windows(2) {
+            let delta = pair[1] - pair[0];
+            assert_eq!(delta.x.abs() + delta.y.abs() + delta.z.abs(), 1);
+        }
+    }
+
+    #[test]
+    fn ray_to_grid() {
+        #[derive(Resource)]
+        struct Root(Entity);
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i64>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                let root = commands.spawn_big_space::<i64>(Grid::new_uniform(10.0, 0.0), |root| {
+                    root.spawn_spatial((FloatingOrigin, GridCell::<i64>::new(1 << 50, 0, 0)));
+                });
+                commands.insert_resource(Root(root));
+            });
+        app.update();
+
+        let root = app.world().resource::<Root>().0;
+        let grid = app.world().get::<Grid<i64>>(root).unwrap();
+        let ray = Ray3d::new(Vec3::new(3.0, 24.0, 0.0), Dir3::X);
+        let grid_ray = grid.ray_to_grid(ray);
+        assert_eq!(grid_ray.cell, GridCell::new(1 << 50, 2, 0));
+        assert_eq!(grid_ray.origin, Vec3::new(3.0, 4.0, 0.0));
+        assert_eq!(grid_ray.direction, Vec3::X);
+
+        let from_ray: Vec<_> = grid.ray_cells(ray, 25.0).collect();
+        let from_grid_ray: Vec<_> = grid.grid_ray_cells(&grid_ray, 25.0).collect();
+        assert_eq!(from_ray, from_grid_ray);
+        assert_eq!(from_ray.len(), 4);
+    }
+}
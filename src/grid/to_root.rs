@@ -0,0 +1,184 @@
+//! Cached transforms from each [`Grid`] to the root of its [`BigSpace`].
+
+use std::marker::PhantomData;
+
+use crate::prelude::*;
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::prelude::*;
+use bevy_math::{DAffine3, DVec3};
+use bevy_reflect::prelude::*;
+use bevy_transform::prelude::*;
+
+/// Keeps the [`GridToRoot`] component of all grids up to date. Without this plugin, the component
+/// is never updated, so this has no cost when unused.
+pub struct GridToRootPlugin<P: GridPrecision>(PhantomData<P>);
+
+impl<P: GridPrecision> Default for GridToRootPlugin<P> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<P: GridPrecision> Plugin for GridToRootPlugin<P> {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GridToRoot>().add_systems(
+            PostUpdate,
+            GridToRoot::update::<P>.in_set(FloatingOriginSystem::LocalFloatingOrigins),
+        );
+    }
+}
+
+/// Add this to an entity with a [`Grid`] to cache the double precision transform from the grid to
+/// the root [`Grid`] of its [`BigSpace`]. Updated by the [`GridToRootPlugin`].
+///
+/// Computing the position of an entity relative to the root normally requires walking up the
+/// hierarchy and composing the transform of every grid along the way. With this component on the
+/// entity's grid, this is a single multiplication, see [`Self::root_position`]. This is useful for
+/// systems that need positions that are independent of the [`FloatingOrigin`], like physics or AI.
+///
+/// The transform maps positions relative to the origin of this grid, as computed by
+/// [`Grid::grid_position_double`], to positions relative to the origin of the root grid.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct GridToRoot(pub DAffine3);
+
+impl Default for GridToRoot {
+    fn default() -> Self {
+        Self(DAffine3::IDENTITY)
+    }
+}
+
+impl GridToRoot {
+    /// Compute the position, relative to the origin of the root grid, of an entity in this `grid`.
+    #[inline]
+    pub fn root_position<P: GridPrecision>(
+        &self,
+        grid: &Grid<P>,
+        cell: &GridCell<P>,
+        transform: &Transform,
+    ) -> DVec3 {
+        self.0
+            .transform_point3(grid.grid_position_double(cell, transform))
+    }
+
+    /// The transform from a child grid, at `cell` and `transform` in its `parent` grid, to the
+    /// `parent` grid. A [`GridToRoot`] is the product of these for every grid up to the root.
+    #[inline]
+    pub fn child_to_parent<P: GridPrecision>(
+        parent: &Grid<P>,
+        cell: &GridCell<P>,
+        transform: &Transform,
+    ) -> DAffine3 {
+        DAffine3::from_scale_rotation_translation(
+            transform.scale.as_dvec3(),
+            transform.rotation.as_dquat(),
+            parent.grid_position_double(cell, transform),
+        )
+    }
+
+    /// Compute the [`GridToRoot`] of the `grid` entity by walking up the hierarchy, for one-off
+    /// use without the [`GridToRootPlugin`]. Returns `None` if the grid is not in a [`BigSpace`],
+    /// or if a grid along the way does not have a [`GridCell`] and [`Transform`].
+    pub fn compute<P: GridPrecision>(
+        grid: Entity,
+        grids: &Grids<P>,
+        positions: &Query<(&GridCell<P>, &Transform), With<Grid<P>>>,
+    ) -> Option<Self> {
+        let mut to_root = DAffine3::IDENTITY;
+        let mut current = grid;
+        while let Some(parent) = grids.parent_grid_entity(current) {
+            let (cell, transform) = positions.get(current).ok()?;
+            to_root = Self::child_to_parent(grids.get(parent), cell, transform) * to_root;
+            current = parent;
+        }
+        (grids.root_of(current) == Some(current)).then_some(Self(to_root))
+    }
+
+    /// Update the [`GridToRoot`] of all grids, walking down from the root of each [`BigSpace`].
+    pub fn update<P: GridPrecision>(
+        mut stack: Local<Vec<(Entity, DAffine3)>>,
+        roots: Query<Entity, (With<BigSpace>, With<Grid<P>>)>,
+        grids: Query<(&Grid<P>, Option<&Children>)>,
+        grid_positions: Query<(&GridCell<P>, &Transform), With<Grid<P>>>,
+        mut cache: Query<&mut GridToRoot>,
+    ) {
+        stack.extend(roots.iter().map(|root| (root, DAffine3::IDENTITY)));
+        while let Some((entity, to_root)) = stack.pop() {
+            if let Ok(mut cached) = cache.get_mut(entity) {
+                cached.set_if_neq(GridToRoot(to_root));
+            }
+            let Ok((grid, Some(children))) = grids.get(entity) else {
+                continue;
+            };
+            for &child in children {
+                let Ok((cell, transform)) = grid_positions.get(child) else {
+                    continue;
+                };
+                stack.push((
+                    child,
+                    to_root * Self::child_to_parent(grid, cell, transform),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn root_position() {
+        #[derive(Resource)]
+        struct Spawned {
+            outer: Entity,
+            inner: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i64>::default(),
+            GridToRootPlugin::<i64>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space::<i64>(Grid::new_uniform(10.0, 0.0), |root| {
+                root.spawn_spatial(FloatingOrigin);
+                let mut inner = Entity::PLACEHOLDER;
+                let outer = root.with_grid(Grid::new_uniform(2.0, 0.0), |outer| {
+                    outer.insert((
+                        GridCell::<i64>::new(10, 0, 0),
+                        Transform::from_xyz(1.0, 0.0, 0.0)
+                            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)),
+                        GridToRoot::default(),
+                    ));
+                    inner = outer.with_grid_default(|inner| {
+                        inner.insert((GridCell::<i64>::new(1, 0, 0), GridToRoot::default()));
+                    });
+                });
+                root.commands().insert_resource(Spawned { outer, inner });
+            });
+        });
+        app.update();
+
+        let Spawned { outer, inner } = *app.world().resource::<Spawned>();
+        let world = app.world();
+        let outer_to_root = world.get::<GridToRoot>(outer).unwrap();
+        let outer_grid = world.get::<Grid<i64>>(outer).unwrap();
+        // The inner grid's origin, 2 units along the outer grid's x axis, which is rotated to y.
+        let position =
+            outer_to_root.root_position(outer_grid, &GridCell::new(1, 0, 0), &Transform::IDENTITY);
+        assert!(
+            position.distance(DVec3::new(101.0, 2.0, 0.0)) < 1e-6,
+            "{position}"
+        );
+
+        let inner_to_root = world.get::<GridToRoot>(inner).unwrap();
+        let origin = inner_to_root.0.transform_point3(DVec3::ZERO);
+        assert!(
+            origin.distance(DVec3::new(101.0, 2.0, 0.0)) < 1e-6,
+            "{origin}"
+        );
+    }
+}
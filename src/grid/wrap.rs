@@ -0,0 +1,181 @@
+//! Wraps the cells that entities occupy in a [`Grid`], for toroidal worlds.
+
+use crate::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::prelude::*;
+use bevy_reflect::prelude::*;
+
+/// Add this to an entity with a [`Grid`] to make its children wrap around the box of cells between
+/// [`Self::min`] and [`Self::max`], inclusive, like a toroidal world. An entity that moves past
+/// the `max` cell along an axis reappears in the `min` cell, and vice versa.
+///
+/// Cells are wrapped before [`GridBounds`] are enforced, so a grid with both only checks the
+/// wrapped cell against its bounds.
+///
+/// Only the [`GridCell`] is wrapped, the [`Transform`](bevy_transform::components::Transform)
+/// within the cell is unchanged. This has some limitations:
+///
+/// - Rendering is not seamless. Entities on the other side of the seam are rendered at their
+///   unwrapped position, and the [`GlobalTransform`](bevy_transform::components::GlobalTransform)
+///   of an entity jumps when it wraps.
+/// - Spatial queries like [`GridHashMap::within_cube`] do not wrap around the seam. Use
+///   [`GridHashMap::within_cube_wrapped`] to search across it.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct GridWrap<P: GridPrecision> {
+    /// The minimum cell, inclusive.
+    pub min: GridCell<P>,
+    /// The maximum cell, inclusive.
+    pub max: GridCell<P>,
+}
+
+impl<P: GridPrecision> GridWrap<P> {
+    /// Create a new wrapping range between the `a` and `b` corners, inclusive.
+    pub fn new(a: GridCell<P>, b: GridCell<P>) -> Self {
+        Self {
+            min: GridCell::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: GridCell::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    /// The cell inside the wrapping range that `cell` corresponds to.
+    pub fn wrap(&self, cell: &GridCell<P>) -> GridCell<P> {
+        let wrap_axis = |value: P, min: P, max: P| {
+            // Computed in 128 bits, because the size of the range and the offset into it may not
+            // fit in `P`. The `u128` range overflows to zero when it spans every `i128`.
+            let (value, min) = (value.as_i128(), min.as_i128());
+            let Some(range) = (max.as_i128().wrapping_sub(min) as u128).checked_add(1) else {
+                return P::from_i128(value);
+            };
+            let offset = if value >= min {
+                value.wrapping_sub(min) as u128 % range
+            } else {
+                (range - min.wrapping_sub(value) as u128 % range) % range
+            };
+            P::from_i128(min.wrapping_add(offset as i128))
+        };
+        GridCell::new(
+            wrap_axis(cell.x, self.min.x, self.max.x),
+            wrap_axis(cell.y, self.min.y, self.max.y),
+            wrap_axis(cell.z, self.min.z, self.max.z),
+        )
+    }
+
+    /// Wrap the [`GridCell`] of every child whose cell changed, in grids with a [`GridWrap`].
+    ///
+    /// The [`BigSpacePlugin`] only runs this system while a [`GridWrap`] exists.
+    pub fn enforce(
+        grids: Query<&GridWrap<P>>,
+        mut changed: Query<(&mut GridCell<P>, &Parent), Changed<GridCell<P>>>,
+    ) {
+        for (mut cell, parent) in &mut changed {
+            let Ok(wrap) = grids.get(parent.get()) else {
+                continue;
+            };
+            let wrapped = wrap.wrap(&cell);
+            cell.set_if_neq(wrapped);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn wrap() {
+        let wrap = GridWrap::<i64>::new(GridCell::new(-2, 0, 0), GridCell::new(2, 9, 0));
+        assert_eq!(wrap.wrap(&GridCell::new(3, 10, 1)), GridCell::new(-2, 0, 0));
+        assert_eq!(
+            wrap.wrap(&GridCell::new(-3, -1, -1)),
+            GridCell::new(2, 9, 0)
+        );
+        assert_eq!(wrap.wrap(&GridCell::new(1, 5, 0)), GridCell::new(1, 5, 0));
+        assert_eq!(
+            wrap.wrap(&GridCell::new(13, 25, 0)),
+            GridCell::new(-2, 5, 0)
+        );
+    }
+
+    #[test]
+    fn wrap_wider_than_precision() {
+        // The range is wider than `i8::MAX`, so offsets into its upper half don't fit in an `i8`.
+        let wrap = GridWrap::<i8>::new(GridCell::new(-100, -100, 0), GridCell::new(100, 100, 0));
+        assert_eq!(
+            wrap.wrap(&GridCell::new(100, 50, 0)),
+            GridCell::new(100, 50, 0)
+        );
+        assert_eq!(
+            wrap.wrap(&GridCell::new(-100, 99, 0)),
+            GridCell::new(-100, 99, 0)
+        );
+        assert_eq!(
+            wrap.wrap(&GridCell::new(101, -101, 0)),
+            GridCell::new(-100, 100, 0)
+        );
+        assert_eq!(
+            wrap.wrap(&GridCell::new(127, -128, 0)),
+            GridCell::new(-74, 73, 0)
+        );
+
+        let full = GridWrap::<i8>::new(GridCell::new(i8::MIN, 0, 0), GridCell::new(i8::MAX, 0, 0));
+        for x in i8::MIN..=i8::MAX {
+            assert_eq!(full.wrap(&GridCell::new(x, 0, 0)), GridCell::new(x, 0, 0));
+        }
+
+        let (min, max) = (i32::MIN + 10, i32::MAX - 10);
+        let wrap = GridWrap::<i32>::new(GridCell::new(min, min, min), GridCell::new(max, max, max));
+        assert_eq!(
+            wrap.wrap(&GridCell::new(max, max - 1, 0)),
+            GridCell::new(max, max - 1, 0)
+        );
+        assert_eq!(
+            wrap.wrap(&GridCell::new(i32::MAX, i32::MIN, min)),
+            GridCell::new(min + 9, max - 9, min)
+        );
+
+        let full = GridWrap::<i128>::new(
+            GridCell::new(i128::MIN, i128::MIN, 0),
+            GridCell::new(i128::MAX, i128::MAX - 1, 0),
+        );
+        assert_eq!(
+            full.wrap(&GridCell::new(i128::MAX, i128::MAX, 0)),
+            GridCell::new(i128::MAX, i128::MIN, 0)
+        );
+    }
+
+    #[test]
+    fn entity_wraps_around() {
+        #[derive(Resource)]
+        struct Walker(Entity);
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 0.0), |root| {
+                    root.insert(GridWrap::<i32>::new(
+                        GridCell::new(-2, -2, -2),
+                        GridCell::new(2, 2, 2),
+                    ));
+                    root.spawn_spatial(FloatingOrigin);
+                    let walker = root.spawn_spatial(GridCell::<i32>::new(2, 0, 0)).id();
+                    root.commands().insert_resource(Walker(walker));
+                });
+            });
+        app.update();
+
+        let walker = app.world().resource::<Walker>().0;
+        app.world_mut()
+            .get_mut::<Transform>(walker)
+            .unwrap()
+            .translation
+            .x = 7.0;
+        app.update();
+
+        let cell = *app.world().get::<GridCell<i32>>(walker).unwrap();
+        let transform = app.world().get::<Transform>(walker).unwrap();
+        assert_eq!(cell, GridCell::new(-2, 0, 0));
+        assert_eq!(transform.translation.x, -3.0);
+    }
+}
@@ -6,11 +6,37 @@ use crate::prelude::*;
 use bevy_ecs::prelude::*;
 use bevy_hierarchy::Parent;
 use bevy_math::IVec3;
-use bevy_reflect::Reflect;
+use bevy_reflect::prelude::*;
 use bevy_utils::{AHasher, Instant, Parallel};
 
 use super::{ChangedGridHashes, GridHashMapFilter};
 
+/// Add this marker to an entity with a [`GridCell`] to exclude it from spatial hashing, even if it
+/// matches the [`GridHashMapFilter`] of a [`GridHashPlugin`]. The entity will not have a
+/// [`GridHash`], and will not be in any [`GridHashMap`].
+///
+/// This is useful for entities that are never queried spatially, like decorations, without needing
+/// to write a custom filter. Adding this to an entity that is already hashed removes its
+/// [`GridHash`].
+#[derive(Component, Default, Clone, Copy, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct NoSpatialHash;
+
+/// Add this marker to a hashed entity that never moves, to skip checking it for changes when
+/// updating [`GridHash`]es.
+///
+/// The entity is hashed as usual when it is spawned, and stays in the [`GridHashMap`] at that
+/// cell. Checking a single entity for changes is cheap, but skipping the static majority of a
+/// large scene adds up.
+///
+/// While this marker is present, moving the entity to another cell or grid does *not* update its
+/// [`GridHash`] or its entry in the map, they will continue to refer to its old cell. To move it,
+/// "wake" the entity by removing this marker, which rehashes it in the next update. The marker can
+/// be added back once the entity has been rehashed.
+#[derive(Component, Default, Clone, Copy, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct StaticSpatial;
+
 /// A fast but lossy version of [`GridHash`]. Use this component when you don't care about false
 /// positives (hash collisions). See the docs on [`GridHash::fast_eq`] for more details on fast but
 /// lossy equality checks.
@@ -20,6 +46,7 @@ use super::{ChangedGridHashes, GridHashMapFilter};
 /// Use this in `HashMap`s and `HashSet`s with `PassHash` to avoid re-hashing the stored precomputed
 /// hash. Remember, hash collisions cannot be resolved for this type!
 #[derive(Component, Clone, Copy, Debug, Reflect, PartialEq, Eq)]
+#[reflect(Component, PartialEq)]
 pub struct FastGridHash(u64);
 
 impl Hash for FastGridHash {
@@ -43,7 +70,18 @@ impl<P: GridPrecision> PartialEq<GridHash<P>> for FastGridHash {
 /// Due to grids and multiple big spaces in a single world, this must use both the [`GridCell`] and
 /// the [`Parent`] of the entity to uniquely identify its position. These two values are then hashed
 /// and stored in this spatial hash component.
+/// The hash is recomputed when either changes, so an entity that is moved to another grid
+/// is moved to the matching cell of that grid in the [`GridHashMap`], even if its [`GridCell`] is
+/// unchanged.
+///
+/// ### Serialization
+///
+/// With the `serde` feature enabled, a [`GridHash`] is serialized compactly as the bits of its grid
+/// [`Entity`] followed by its [`GridCell`]. The precomputed hash is not written, it is recomputed
+/// when deserializing. Entity ids are only meaningful within the [`World`] they came from, so the
+/// grid entity must be mapped when sending hashes to another world.
 #[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
 pub struct GridHash<P: GridPrecision> {
     // Needed for equality checks
     cell: GridCell<P>,
@@ -100,8 +138,23 @@ impl<P: GridPrecision> GridHash<P> {
         }
     }
 
+    /// Construct the hash of a `cell` in the `grid` entity, without an entity in that cell. This is
+    /// useful for looking up cells in a [`GridHashMap`], building test fixtures, or building your
+    /// own spatial indices.
+    ///
+    /// The `grid` must be the entity with the [`Grid`] that the cell belongs to, which is the
+    /// [`Parent`] of the entities in that cell. Otherwise, the hash will not match the [`GridHash`]
+    /// components computed by the [`GridHashPlugin`].
+    ///
+    /// Do not insert or mutate a [`GridHash`] component with this, it is managed by the plugin.
+    #[inline]
+    pub fn new_for(grid: Entity, cell: GridCell<P>) -> Self {
+        Self::from_parent(grid, &cell)
+    }
+
     /// Do not use this to manually construct this component. You've been warned.
     #[doc(hidden)]
+    #[deprecated = "use `GridHash::new_for` instead"]
     pub fn __new_manual(parent: Entity, cell: &GridCell<P>) -> Self {
         Self::from_parent(parent, cell)
     }
@@ -162,16 +215,58 @@ impl<P: GridPrecision> GridHash<P> {
                     &mut GridHash<P>,
                     &mut FastGridHash,
                 ),
-                (F, Or<(Changed<Parent>, Changed<GridCell<P>>)>),
+                (
+                    F,
+                    Without<NoSpatialHash>,
+                    Without<StaticSpatial>,
+                    Or<(Changed<Parent>, Changed<GridCell<P>>)>,
+                ),
+            >,
+            Query<
+                (Entity, &Parent, &GridCell<P>),
+                (F, Without<NoSpatialHash>, Without<GridHash<P>>),
+            >,
+            Query<Entity, (Added<NoSpatialHash>, With<GridHash<P>>)>,
+            Query<
+                Entity,
+                (
+                    With<GridHash<P>>,
+                    Or<(Without<GridCell<P>>, Without<Parent>)>,
+                ),
+            >,
+            Query<
+                (
+                    Entity,
+                    &Parent,
+                    &GridCell<P>,
+                    &mut GridHash<P>,
+                    &mut FastGridHash,
+                ),
+                (F, Without<NoSpatialHash>, Without<StaticSpatial>),
             >,
-            Query<(Entity, &Parent, &GridCell<P>), (F, Without<GridHash<P>>)>,
         )>,
+        mut woken: RemovedComponents<StaticSpatial>,
         mut stats: Option<ResMut<crate::timing::GridHashStats>>,
         mut thread_changed_hashes: Local<Parallel<Vec<Entity>>>,
         mut thread_commands: Local<Parallel<Vec<(Entity, GridHash<P>, FastGridHash)>>>,
     ) {
         let start = Instant::now();
 
+        // Remove opted out
+        for entity in &spatial_entities.p2() {
+            commands
+                .entity(entity)
+                .remove::<(GridHash<P>, FastGridHash)>();
+        }
+
+        // Remove demoted to low precision or moved out of all grids, the map is updated when the
+        // `GridHash` is removed.
+        for entity in &spatial_entities.p3() {
+            commands
+                .entity(entity)
+                .remove::<(GridHash<P>, FastGridHash)>();
+        }
+
         // Create new
         spatial_entities
             .p1()
@@ -198,6 +293,21 @@ impl<P: GridPrecision> GridHash<P> {
             },
         );
 
+        // Rehash woken entities, they may have moved while they were static.
+        let mut woken_entities = spatial_entities.p4();
+        for entity in woken.read() {
+            let Ok((entity, parent, cell, mut hash, mut fast_hash)) =
+                woken_entities.get_mut(entity)
+            else {
+                continue;
+            };
+            let new_hash = GridHash::new(parent, cell);
+            fast_hash.0 = new_hash.pre_hash;
+            if hash.replace_if_neq(new_hash).is_some() {
+                changed_hashes.list.push(entity);
+            }
+        }
+
         changed_hashes.list.extend(thread_changed_hashes.drain());
 
         if let Some(ref mut stats) = stats {
@@ -215,3 +325,19 @@ impl<P: GridPrecision> GridHash<P> {
         self.grid
     }
 }
+
+#[cfg(feature = "serde")]
+impl<P: GridPrecision + serde::Serialize> serde::Serialize for GridHash<P> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.grid.to_bits(), self.cell).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, P: GridPrecision + serde::Deserialize<'de>> serde::Deserialize<'de> for GridHash<P> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (bits, cell) = <(u64, GridCell<P>)>::deserialize(deserializer)?;
+        let grid = Entity::try_from_bits(bits).map_err(serde::de::Error::custom)?;
+        Ok(GridHash::from_parent(grid, &cell))
+    }
+}
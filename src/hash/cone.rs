@@ -0,0 +1,172 @@
+//! Field of view queries against entities in a [`GridHashMap`].
+
+use crate::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::DVec3;
+
+use super::GridHashMapFilter;
+
+/// A cone in double precision grid space, used by [`GridHashMap::within_cone`].
+///
+/// Positions are relative to the origin of a [`Grid`], as computed by
+/// [`Grid::grid_position_double`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridCone {
+    /// The tip of the cone.
+    pub apex: DVec3,
+    /// The normalized direction the cone opens towards.
+    pub direction: DVec3,
+    /// The angle between the direction and the surface of the cone, in radians.
+    pub half_angle: f64,
+    /// The distance from the apex to the end of the cone.
+    pub range: f64,
+}
+
+impl GridCone {
+    /// Create a new cone. The `direction` does not need to be normalized.
+    pub fn new(apex: DVec3, direction: DVec3, half_angle: f32, range: f32) -> Self {
+        Self {
+            apex,
+            direction: direction.normalize_or_zero(),
+            half_angle: half_angle as f64,
+            range: range as f64,
+        }
+    }
+
+    /// Returns `true` if the `point` is inside the cone.
+    #[inline]
+    pub fn contains(&self, point: DVec3) -> bool {
+        self.intersects_sphere(point, 0.0)
+    }
+
+    /// Returns `true` if a sphere with `center` and `radius` overlaps the cone. This treats the end
+    /// of the cone as a spherical cap, so it matches a distance and angle test.
+    #[inline]
+    pub fn intersects_sphere(&self, center: DVec3, radius: f64) -> bool {
+        let to_center = center - self.apex;
+        let distance = to_center.length();
+        if distance <= radius {
+            return true;
+        }
+        if distance - radius > self.range {
+            return false;
+        }
+        let angle = (to_center.dot(self.direction) / distance)
+            .clamp(-1.0, 1.0)
+            .acos();
+        let angular_radius = (radius / distance).asin();
+        angle - angular_radius <= self.half_angle
+    }
+}
+
+impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
+    /// Iterate over the entities in occupied cells of the `grid` that overlap the `cone`. Useful
+    /// for perception and targeting, like finding everything in a sensor arc.
+    ///
+    /// This is a coarse test on cells: all cells within `range` of the apex are tested against the
+    /// cone using their bounding sphere, and all entities in cells that overlap are returned. To
+    /// refine the results per entity, test their positions with [`GridCone::contains`] or
+    /// [`GridCone::intersects_sphere`].
+    ///
+    /// ### Warning
+    ///
+    /// Like [`Self::within_cube`], the number of cells tested grows with the cube of the range in
+    /// cells, so a long range compared to the cell size can be expensive.
+    pub fn within_cone<'a>(
+        &'a self,
+        grid_entity: Entity,
+        grid: &Grid<P>,
+        cone: &GridCone,
+    ) -> impl Iterator<Item = Entity> + 'a {
+        let (apex_cell, _) = grid.translation_to_grid(cone.apex);
+        let extent = (cone.range / grid.cell_edge_length().as_dvec3())
+            .ceil()
+            .as_i64vec3()
+            + 1;
+        let cell_radius = grid.cell_edge_length().as_dvec3().length() / 2.0;
+
+        let mut occupied = Vec::new();
+        for x in -extent.x..=extent.x {
+            for y in -extent.y..=extent.y {
+                for z in -extent.z..=extent.z {
+                    let offset = GridCell::new(
+                        P::from_f64(x as f64),
+                        P::from_f64(y as f64),
+                        P::from_f64(z as f64),
+                    );
+                    let cell = apex_cell + offset;
+                    if !cone.intersects_sphere(grid.cell_center(&cell), cell_radius) {
+                        continue;
+                    }
+                    occupied.extend(self.get(&GridHash::from_parent(grid_entity, &cell)));
+                }
+            }
+        }
+        occupied.into_iter().entities()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn within_cone() {
+        #[derive(Resource)]
+        struct Entities {
+            ahead: Entity,
+            off_axis: Entity,
+            behind: Entity,
+            out_of_range: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i64>::default(),
+            GridHashPlugin::<i64>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space::<i64>(Grid::new_uniform(10.0, 0.0), |root| {
+                root.spawn_spatial(FloatingOrigin);
+                let ahead = root.spawn_spatial(GridCell::<i64>::new(5, 2, 0)).id();
+                let off_axis = root.spawn_spatial(GridCell::<i64>::new(5, 3, 0)).id();
+                let behind = root.spawn_spatial(GridCell::<i64>::new(-5, 0, 0)).id();
+                let out_of_range = root.spawn_spatial(GridCell::<i64>::new(20, 0, 0)).id();
+                root.commands().insert_resource(Entities {
+                    ahead,
+                    off_axis,
+                    behind,
+                    out_of_range,
+                });
+            });
+        });
+        app.update();
+
+        let world = app.world();
+        let entities = world.resource::<Entities>();
+        let grid_entity = world.get::<Parent>(entities.ahead).unwrap().get();
+        let grid = world.get::<Grid<i64>>(grid_entity).unwrap();
+        let map = world.resource::<GridHashMap<i64>>();
+
+        let cone = GridCone::new(DVec3::ZERO, DVec3::X, 30f32.to_radians(), 100.0);
+        let found: Vec<_> = map.within_cone(grid_entity, grid, &cone).collect();
+        assert!(found.contains(&entities.ahead));
+        assert!(!found.contains(&entities.behind));
+        assert!(!found.contains(&entities.out_of_range));
+
+        // The off axis entity is in a cell touching the cone, refining removes it.
+        assert!(found.contains(&entities.off_axis));
+        let position = |entity| {
+            let cell = world.get::<GridCell<i64>>(entity).unwrap();
+            let transform = world.get::<Transform>(entity).unwrap();
+            grid.grid_position_double(cell, transform)
+        };
+        let refined: Vec<_> = found
+            .into_iter()
+            .filter(|&entity| cone.contains(position(entity)))
+            .collect();
+        assert!(refined.contains(&entities.ahead));
+        assert!(!refined.contains(&entities.off_axis));
+    }
+}
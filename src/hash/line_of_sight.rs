@@ -0,0 +1,122 @@
+//! Line of sight tests between cells in a [`GridHashMap`].
+
+use crate::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::{prelude::*, DVec3};
+
+use super::GridHashMapFilter;
+
+impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
+    /// Returns `true` if every cell on the line between the centers of cells `a` and `b` in the
+    /// `grid` entity is empty.
+    ///
+    /// The cells `a` and `b` themselves are not tested, so entities can test if they can see each
+    /// other. See [`Self::line_of_sight_by`] to choose which cells block the line of sight.
+    pub fn line_of_sight(&self, grid: Entity, a: GridCell<P>, b: GridCell<P>) -> bool {
+        self.line_of_sight_by(grid, a, b, |hash| self.contains(hash))
+    }
+
+    /// Returns `true` if none of the cells on the line between the centers of cells `a` and `b` in
+    /// the `grid` entity are blocked, according to `is_blocked`. This is useful if only certain
+    /// entities should block the line of sight.
+    ///
+    /// This visits the supercover of the line: every cell the line touches, including both cells
+    /// on either side when the line passes exactly through an edge or corner. The cells `a` and `b`
+    /// themselves are not tested.
+    pub fn line_of_sight_by(
+        &self,
+        grid: Entity,
+        a: GridCell<P>,
+        b: GridCell<P>,
+        mut is_blocked: impl FnMut(&GridHash<P>) -> bool,
+    ) -> bool {
+        let mut is_clear = |cell: GridCell<P>| {
+            cell == a || cell == b || !is_blocked(&GridHash::from_parent(grid, &cell))
+        };
+
+        let delta = b - a;
+        let delta = DVec3::new(delta.x.as_f64(), delta.y.as_f64(), delta.z.as_f64());
+        let mut step = IVec3::ZERO;
+        let mut t_max = DVec3::INFINITY;
+        let mut t_delta = DVec3::INFINITY;
+        for axis in 0..3 {
+            if delta[axis] != 0.0 {
+                step[axis] = delta[axis].signum() as i32;
+                t_delta[axis] = 1.0 / delta[axis].abs();
+                // The line starts at the center of the cell, half a cell from the boundary.
+                t_max[axis] = 0.5 * t_delta[axis];
+            }
+        }
+
+        let mut cell = a;
+        loop {
+            let t = t_max.min_element();
+            if t > 1.0 {
+                return true;
+            }
+            let tied = t_max.cmpeq(DVec3::splat(t)).bitmask();
+            let tied_step = IVec3::select(t_max.cmpeq(DVec3::splat(t)), step, IVec3::ZERO);
+
+            // The line passes exactly through an edge or corner, visit the cells that only touch
+            // it.
+            if tied.count_ones() > 1 {
+                for partial in (1..tied).filter(|partial| partial & tied == *partial) {
+                    let mask = BVec3::new(partial & 1 != 0, partial & 2 != 0, partial & 4 != 0);
+                    let partial_step = IVec3::select(mask, step, IVec3::ZERO);
+                    if !is_clear(cell + partial_step) {
+                        return false;
+                    }
+                }
+            }
+
+            cell += tied_step;
+            t_max = DVec3::select(t_max.cmpeq(DVec3::splat(t)), t_max + t_delta, t_max);
+            if !is_clear(cell) {
+                return false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn line_of_sight() {
+        #[derive(Resource)]
+        struct Root(Entity);
+
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                root.spawn_spatial(FloatingOrigin);
+                root.spawn_spatial(GridCell::new(5, 0, 0));
+                root.spawn_spatial(GridCell::new(1, 1, 0));
+                let id = root.id();
+                root.commands().insert_resource(Root(id));
+            });
+        });
+
+        app.update();
+
+        let grid = app.world().resource::<Root>().0;
+        let map = app.world().resource::<GridHashMap<i32>>();
+
+        // Blocked by the entity in the middle, but not the entities at either end.
+        assert!(!map.line_of_sight(grid, GridCell::new(0, 0, 0), GridCell::new(10, 0, 0)));
+        assert!(map.line_of_sight(grid, GridCell::new(0, 0, 0), GridCell::new(5, 0, 0)));
+        assert!(map.line_of_sight(grid, GridCell::new(0, 2, 0), GridCell::new(10, 2, 0)));
+        assert!(!map.line_of_sight(grid, GridCell::new(0, 2, 0), GridCell::new(2, 0, 0)));
+        // The diagonal only touches the corner of (1, 1, 0), which still counts as blocking.
+        assert!(!map.line_of_sight(grid, GridCell::new(0, 1, 0), GridCell::new(1, 2, 0)));
+        let never_blocked = |_: &GridHash<i32>| false;
+        let (a, b) = (GridCell::new(0, 1, 0), GridCell::new(1, 2, 0));
+        assert!(map.line_of_sight_by(grid, a, b, never_blocked));
+    }
+}
@@ -3,7 +3,13 @@
 use std::{collections::VecDeque, marker::PhantomData, time::Instant};
 
 use crate::prelude::*;
-use bevy_ecs::{entity::EntityHash, prelude::*};
+use bevy_ecs::{
+    entity::EntityHash,
+    prelude::*,
+    query::{QueryData, QueryFilter, ROQueryItem},
+};
+use bevy_math::IVec3;
+use bevy_tasks::{ComputeTaskPool, ParallelSlice, TaskPool};
 use bevy_utils::{
     hashbrown::{HashMap, HashSet},
     PassHash,
@@ -11,6 +17,25 @@ use bevy_utils::{
 
 use super::GridHashMapFilter;
 
+/// Compare two cells by their Morton code, without computing the full interleaved code.
+///
+/// The axis with the most significant differing bit determines the order, with ties broken in x,
+/// y, z order. Coordinates are biased by flipping the sign bit, so negative cells are ordered
+/// before positive cells.
+fn z_order<P: GridPrecision>(a: &GridCell<P>, b: &GridCell<P>) -> std::cmp::Ordering {
+    let biased =
+        |cell: &GridCell<P>| [cell.x, cell.y, cell.z].map(|v| (v.as_i128() as u128) ^ (1 << 127));
+    let (a, b) = (biased(a), biased(b));
+    let less_msb = |x: u128, y: u128| x < y && x < (x ^ y);
+    let mut axis = 0;
+    for i in 1..3 {
+        if less_msb(a[axis] ^ b[axis], a[i] ^ b[i]) {
+            axis = i;
+        }
+    }
+    a[axis].cmp(&b[axis])
+}
+
 /// An entry in a [`GridHashMap`], accessed with a [`GridHash`].
 #[derive(Clone, Debug)]
 pub struct GridHashEntry<P: GridPrecision> {
@@ -32,7 +57,21 @@ impl<P: GridPrecision> GridHashEntry<P> {
             .find_map(|(i, h)| (h == hash).then_some(i))
     }
 
-    /// Iterate over this cell and its non-empty adjacent neighbors.
+    /// Iterate over the entities in this cell in a stable order, sorted by their [`Entity`] bits.
+    ///
+    /// Iterating over [`Self::entities`] directly is faster, but the order is arbitrary, and can
+    /// differ between runs and machines. Use this when the result of a spatial query must be
+    /// deterministic, for example in a lockstep simulation. The order of cells can be made
+    /// deterministic with [`GridHashMap::entries_z_order`]. This allocates and sorts a temporary
+    /// list of the entities in the cell.
+    pub fn entities_sorted(&self) -> impl Iterator<Item = Entity> {
+        let mut entities: Vec<Entity> = self.entities.iter().copied().collect();
+        entities.sort_unstable_by_key(|entity| entity.to_bits());
+        entities.into_iter()
+    }
+
+    /// Iterate over this cell and its non-empty adjacent neighbors. This cell is always the first
+    /// item.
     ///
     /// See [`GridHashMap::nearby`].
     pub fn nearby<'a, F: GridHashMapFilter>(
@@ -74,6 +113,22 @@ impl<'a, P: GridPrecision> SpatialEntryToEntities<'a> for Neighbor<'a, P> {
 }
 
 /// A global spatial hash map for quickly finding entities in a grid cell.
+///
+/// ### Change Sets
+///
+/// The map is updated once per frame in [`GridHashMapSystem::UpdateMap`], in the `PostUpdate`
+/// schedule. The change sets, [`Self::just_inserted`] and [`Self::just_removed`], are replaced
+/// during this update, and are not modified at any other time, so they are stable for the rest of
+/// the frame and the first half of the next one:
+///
+/// - Systems in `PostUpdate` after [`GridHashMapSystem::UpdateMap`] see the changes made during
+///   this frame.
+/// - Systems in `PreUpdate`, `Update`, or `PostUpdate` before [`GridHashMapSystem::UpdateMap`] see
+///   the changes made during the previous frame.
+///
+/// Either way, a system that reads the change sets once per frame will see every change exactly
+/// once, and does not need to be scheduled after the map update. Changes made in the same frame
+/// the system runs, before the update, will be seen the following frame.
 #[derive(Resource, Clone)]
 pub struct GridHashMap<P, F = ()>
 where
@@ -124,6 +179,78 @@ impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
         self.map.inner.get(hash)
     }
 
+    /// Reserve capacity for at least `additional` more entities and cells in this map. Use this
+    /// before spawning many spatial entities at once, to avoid repeatedly growing the map during
+    /// the first update. See [`GridCommands::reserve_spatial_hash`].
+    ///
+    /// This also fills the object pools used for new cells, up to the
+    /// [pool limit](Self::set_pool_limit), so the entity set and neighbor list of each new cell are
+    /// not allocated one at a time during the update.
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+        self.reverse_map.reserve(additional);
+    }
+
+    /// The number of cells this map can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.map.inner.capacity()
+    }
+
+    /// Limit the number of allocations kept for reuse when cells are vacated, or `None` for no
+    /// limit, which is the default. Usually set with [`GridHashPlugin::with_pool_limit`].
+    ///
+    /// When a cell becomes empty, its entity set and neighbor list are kept in an object pool, so
+    /// they can be reused when another cell becomes occupied, instead of freeing and allocating
+    /// memory. This is fastest when the number of occupied cells is stable, but the pools will hold
+    /// onto the memory of the most occupied cells ever seen. A limit trades some speed for memory,
+    /// allocations beyond the limit are freed. Lowering the limit does not free allocations that
+    /// are already pooled, use [`Self::shrink_pools`] for that.
+    pub fn set_pool_limit(&mut self, limit: Option<usize>) {
+        self.map.pool_limit = limit;
+    }
+
+    /// The limit on the number of allocations kept for reuse. See [`Self::set_pool_limit`].
+    pub fn pool_limit(&self) -> Option<usize> {
+        self.map.pool_limit
+    }
+
+    /// Free all allocations kept for reuse when cells are vacated, for example after a level
+    /// transition that vacates many cells. See [`Self::set_pool_limit`].
+    pub fn shrink_pools(&mut self) {
+        self.map.hash_set_pool = Vec::new();
+        self.map.neighbor_pool = Vec::new();
+    }
+
+    /// Iterate over the read-only query items of the entities in the cell at `hash`, skipping
+    /// entities that do not match the `query`. Returns an empty iterator if the cell is empty.
+    ///
+    /// This is a shorthand for calling [`Query::get`] with every entity in the cell:
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use big_space::prelude::*;
+    /// # #[derive(Component)]
+    /// # struct Health(f32);
+    /// fn total_health_per_cell(
+    ///     map: Res<GridHashMap<i64>>,
+    ///     health: Query<&Health>,
+    /// ) {
+    ///     for (hash, _) in map.all_entries() {
+    ///         let total: f32 = map.iter_query(hash, &health).map(|h| h.0).sum();
+    ///     }
+    /// }
+    /// ```
+    pub fn iter_query<'a, D: QueryData, QF: QueryFilter>(
+        &'a self,
+        hash: &GridHash<P>,
+        query: &'a Query<'a, 'a, D, QF>,
+    ) -> impl Iterator<Item = ROQueryItem<'a, D>> + 'a {
+        self.get(hash)
+            .into_iter()
+            .flat_map(|entry| entry.entities.iter())
+            .filter_map(|entity| query.get(*entity).ok())
+    }
+
     /// Returns `true` if this [`GridHash`] is occupied.
     #[inline]
     pub fn contains(&self, hash: &GridHash<P>) -> bool {
@@ -136,8 +263,76 @@ impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
         self.map.inner.iter()
     }
 
+    /// Iterate over the cells that are occupied in both this map and the `other` map, with the
+    /// entry from each map, in arbitrary order. The entry from this map is always the first of the
+    /// two.
+    ///
+    /// This is the core of a broadphase between two sets of entities tracked with different
+    /// [`GridHashMapFilter`]s, for example projectiles and enemies. Every pair of entities in the
+    /// same cell is a potential collision. To also find pairs in adjacent cells, look up the
+    /// [`GridHashEntry::occupied_neighbors`] of each entry in the other map. The smaller of the two
+    /// maps is iterated, and each of its cells is looked up in the larger map.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use big_space::prelude::*;
+    /// # #[derive(Component)]
+    /// # struct Projectile;
+    /// # #[derive(Component)]
+    /// # struct Enemy;
+    /// fn broadphase(
+    ///     projectiles: Res<GridHashMap<i64, With<Projectile>>>,
+    ///     enemies: Res<GridHashMap<i64, With<Enemy>>>,
+    /// ) {
+    ///     for (_hash, projectiles, enemies) in projectiles.spatial_join(&enemies) {
+    ///         for projectile in projectiles.entities.iter() {
+    ///             for enemy in enemies.entities.iter() {
+    ///                 // Test for collision between `projectile` and `enemy`
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn spatial_join<'a, FB: GridHashMapFilter>(
+        &'a self,
+        other: &'a GridHashMap<P, FB>,
+    ) -> impl Iterator<Item = (GridHash<P>, &'a GridHashEntry<P>, &'a GridHashEntry<P>)> {
+        let swapped = self.map.inner.len() > other.map.inner.len();
+        let (small, large) = match swapped {
+            true => (&other.map.inner, &self.map.inner),
+            false => (&self.map.inner, &other.map.inner),
+        };
+        small.iter().filter_map(move |(hash, small_entry)| {
+            let large_entry = large.get(hash)?;
+            match swapped {
+                true => Some((*hash, large_entry, small_entry)),
+                false => Some((*hash, small_entry, large_entry)),
+            }
+        })
+    }
+
+    /// An iterator visiting all spatial hash cells and their contents in Z-order (Morton order).
+    ///
+    /// This is the order of the Morton codes of the occupied cells, made by interleaving the bits
+    /// of each axis of the [`GridCell`]. Cells that are close together are usually close together
+    /// in this order, which improves memory locality for spatial algorithms, and the order is
+    /// deterministic, which is useful for serialization. Cells with the same [`GridCell`] in
+    /// different grids are ordered by their grid entity.
+    ///
+    /// Unlike [`Self::all_entries`], this allocates and sorts a temporary list of all entries.
+    pub fn entries_z_order(&self) -> impl Iterator<Item = (&GridHash<P>, &GridHashEntry<P>)> {
+        let mut entries: Vec<_> = self.map.inner.iter().collect();
+        entries.sort_unstable_by(|(a, _), (b, _)| {
+            z_order(&a.cell(), &b.cell()).then_with(|| a.grid().cmp(&b.grid()))
+        });
+        entries.into_iter()
+    }
+
     /// Iterate over this cell and its non-empty adjacent neighbors.
     ///
+    /// The `entry` itself is always the first item. To only visit the neighbors, iterate over
+    /// [`GridHashEntry::occupied_neighbors`], or skip the first item.
+    ///
     /// `GridHashEntry`s cache information about their neighbors as the spatial map is updated,
     /// making it faster to look up neighboring entries when compared to computing all neighbor
     /// hashes and checking if they exist.
@@ -163,6 +358,9 @@ impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
 
     /// Iterate over all [`GridHashEntry`]s within a cube with `center` and `radius`.
     ///
+    /// This includes the `center` cell, if it is occupied, as the first item. Use
+    /// [`Self::within_cube_exclusive`] to only visit the cells around it.
+    ///
     /// ### Warning
     ///
     /// This can become expensive very quickly! The number of cells that need to be checked is
@@ -183,6 +381,39 @@ impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
             .filter_map(|hash| self.get(&hash))
     }
 
+    /// Iterate over all [`GridHashEntry`]s within a cube with `center` and `radius`, wrapping
+    /// around the edges of the [`GridWrap`] of the center's grid, so cells on the other side of the
+    /// seam are included. The center cell is included, and each cell is visited at most once, even
+    /// if the cube is larger than the wrapping range.
+    ///
+    /// See [`Self::within_cube`] for performance considerations.
+    pub fn within_cube_wrapped<'a>(
+        &'a self,
+        center: &'a GridHash<P>,
+        radius: u8,
+        wrap: &'a GridWrap<P>,
+    ) -> impl Iterator<Item = &'a GridHashEntry<P>> + 'a {
+        let mut visited = HashSet::<GridHash<P>, PassHash>::default();
+        Iterator::chain(std::iter::once(*center), center.adjacent(radius))
+            .map(|hash| GridHash::new_for(hash.grid(), wrap.wrap(&hash.cell())))
+            .filter(move |hash| visited.insert(*hash))
+            .filter_map(|hash| self.get(&hash))
+    }
+
+    /// Iterate over all [`GridHashEntry`]s within a cube with `center` and `radius`, excluding the
+    /// `center` cell itself.
+    ///
+    /// This is useful for neighbor-only queries, like a broadphase that handles pairs within the
+    /// center cell separately. See [`Self::within_cube`] for performance considerations.
+    #[inline]
+    pub fn within_cube_exclusive<'a>(
+        &'a self,
+        center: &'a GridHash<P>,
+        radius: u8,
+    ) -> impl Iterator<Item = &'a GridHashEntry<P>> + 'a {
+        center.adjacent(radius).filter_map(|hash| self.get(&hash))
+    }
+
     /// Iterate over all connected neighboring cells with a breadth-first "flood fill" traversal
     /// starting at `seed`. Limits the extents of the breadth-first flood fill traversal with a
     /// `max_depth`.
@@ -206,19 +437,149 @@ impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
         max_depth: Option<P>,
     ) -> impl Iterator<Item = Neighbor<'a, P>> {
         let starting_cell_cell = seed.cell();
+        self.flood_where(seed, |_, _| true)
+            .take_while(move |Neighbor(hash, _)| {
+                let Some(max_depth) = max_depth else {
+                    return true;
+                };
+                let dist = hash.cell() - starting_cell_cell;
+                dist.x <= max_depth && dist.y <= max_depth && dist.z <= max_depth
+            })
+    }
+
+    /// A parallel version of [`Self::flood`] without a depth limit, which visits the same cells.
+    ///
+    /// The traversal is level-synchronous: the neighbors of every cell at the current depth are
+    /// found in parallel on the [`ComputeTaskPool`], then deduplicated to form the next depth. The
+    /// cells are returned in breadth-first order, but the order of cells at the same depth is
+    /// unspecified. Unlike [`Self::flood`], all cells are visited before this returns, so it is not
+    /// lazy.
+    ///
+    /// This is faster than [`Self::flood`] for very large connected regions. For small regions,
+    /// the overhead of spawning tasks for every depth outweighs the benefit.
+    #[doc(alias = "bfs")]
+    pub fn flood_par<'a>(&'a self, seed: &GridHash<P>) -> impl Iterator<Item = Neighbor<'a, P>> {
+        let task_pool = ComputeTaskPool::get_or_init(TaskPool::default);
+        let mut visited = HashSet::<GridHash<P>, PassHash>::default();
+        let mut visited_in_order = Vec::new();
+        let mut frontier: Vec<_> = self
+            .get(seed)
+            .map(|entry| Neighbor(*seed, entry))
+            .into_iter()
+            .collect();
+        visited.extend(frontier.iter().map(|Neighbor(hash, _)| *hash));
+
+        while !frontier.is_empty() {
+            let candidates = frontier.par_splat_map(task_pool, None, |_, chunk| {
+                chunk
+                    .iter()
+                    .flat_map(|Neighbor(_, entry)| entry.occupied_neighbors.iter())
+                    .filter(|hash| !visited.contains(*hash))
+                    .copied()
+                    .collect::<Vec<_>>()
+            });
+            visited_in_order.append(&mut frontier);
+            for hash in candidates.into_iter().flatten() {
+                if visited.insert(hash) {
+                    let entry = self
+                        .get(&hash)
+                        .expect("Neighbor hashes in GridHashEntry are guaranteed to exist.");
+                    frontier.push(Neighbor(hash, entry));
+                }
+            }
+        }
+        visited_in_order.into_iter()
+    }
+
+    /// Iterate over all connected neighboring cells with a breadth-first "flood fill" traversal
+    /// starting at `seed`, only expanding into neighboring cells where `predicate` returns `true`.
+    ///
+    /// This is useful for growing regions with arbitrary constraints, like only flooding into
+    /// cells that contain entities of the same faction. The `seed` is always visited if it is
+    /// occupied, and `predicate` is called at most once for every other cell.
+    #[doc(alias = "bfs")]
+    pub fn flood_where<'a, W>(
+        &'a self,
+        seed: &GridHash<P>,
+        predicate: W,
+    ) -> ContiguousNeighborsIter<'a, P, F, W>
+    where
+        W: FnMut(&GridHash<P>, &GridHashEntry<P>) -> bool,
+    {
         ContiguousNeighborsIter {
             initial_hash: Some(*seed),
             spatial_map: self,
             stack: Default::default(),
             visited_cells: Default::default(),
+            predicate,
         }
-        .take_while(move |Neighbor(hash, _)| {
-            let Some(max_depth) = max_depth else {
-                return true;
+    }
+
+    /// Find the entity nearest to the `seed` cell that passes the `predicate`, searching outward
+    /// in rings of cells in the same [`Grid`]. Returns `None` if no entity in the grid passes.
+    ///
+    /// This is useful for occasional queries on a subset of the entities in this map, like the
+    /// nearest enemy, without needing a separate [`GridHashPlugin`] with a [`GridHashMapFilter`].
+    ///
+    /// Distance is measured between cell centers using the cell size of the `grid`, so entities in
+    /// the same cell are equally near, and ties are broken arbitrarily. Once a match is found, the
+    /// search continues until no cell in the remaining rings could be nearer, so the result is the
+    /// nearest by cell even if the `grid` has non-cubic cells. If the rings grow larger than the
+    /// number of occupied cells in the map, the remaining cells are scanned directly instead.
+    ///
+    /// Unlike [`Self::flood`], this is not limited to cells connected to the `seed`, and the
+    /// `seed` itself does not need to be occupied.
+    pub fn nearest_filtered(
+        &self,
+        seed: &GridHash<P>,
+        predicate: impl Fn(Entity) -> bool,
+        grid: &Grid<P>,
+    ) -> Option<(Entity, GridHash<P>)> {
+        let distance =
+            |hash: &GridHash<P>| grid.cell_to_float(&(hash.cell() - seed.cell())).length();
+        let min_edge = grid.cell_edge_length().min_element() as f64;
+        type Nearest<P> = Option<(Entity, GridHash<P>, f64)>;
+        let mut nearest: Nearest<P> = None;
+        let consider = |nearest: &mut Nearest<P>, hash: GridHash<P>, entry: &GridHashEntry<P>| {
+            let Some(entity) = entry.entities.iter().copied().find(|e| predicate(*e)) else {
+                return;
             };
-            let dist = hash.cell() - starting_cell_cell;
-            dist.x <= max_depth && dist.y <= max_depth && dist.z <= max_depth
-        })
+            let distance = distance(&hash);
+            if nearest.is_none_or(|(.., nearest)| distance < nearest) {
+                *nearest = Some((entity, hash, distance));
+            }
+        };
+
+        let mut visited = 0;
+        for radius in 0i32.. {
+            // No cell in this ring, or any ring after it, can be nearer than the nearest match.
+            if nearest.is_some_and(|(.., nearest)| radius as f64 * min_edge > nearest) {
+                break;
+            }
+            if visited > self.map.inner.len() {
+                // The rings are now larger than the map, it is faster to check every cell.
+                for (hash, entry) in self.all_entries() {
+                    let offset = hash.cell() - seed.cell();
+                    let ring = [offset.x, offset.y, offset.z]
+                        .into_iter()
+                        .map(|v| v.as_i128().unsigned_abs())
+                        .max()
+                        .unwrap_or_default();
+                    if hash.grid() == seed.grid() && ring >= radius as u128 {
+                        consider(&mut nearest, *hash, entry);
+                    }
+                }
+                break;
+            }
+            for offset in ring(radius) {
+                visited += 1;
+                let hash = GridHash::new_for(seed.grid(), seed.cell() + offset);
+                if let Some(entry) = self.get(&hash) {
+                    consider(&mut nearest, hash, entry);
+                }
+            }
+        }
+        nearest.map(|(entity, hash, _)| (entity, hash))
     }
 
     /// The set of cells that were inserted in the last update to the spatial hash map.
@@ -226,7 +587,8 @@ impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
     /// These are cells that were previously empty, but now contain at least one entity.
     ///
     /// Useful for incrementally updating data structures that extend the functionality of
-    /// [`GridHashMap`]. Updated in [`GridHashMapSystem::UpdateMap`].
+    /// [`GridHashMap`]. Updated in [`GridHashMapSystem::UpdateMap`], see the
+    /// [change sets](Self#change-sets) section for which data is visible in each schedule.
     pub fn just_inserted(&self) -> &HashSet<GridHash<P>, PassHash> {
         &self.map.just_inserted
     }
@@ -236,10 +598,135 @@ impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
     /// These are cells that were previously occupied, but now contain no entities.
     ///
     /// Useful for incrementally updating data structures that extend the functionality of
-    /// [`GridHashMap`]. Updated in [`GridHashMapSystem::UpdateMap`].
+    /// [`GridHashMap`]. Updated in [`GridHashMapSystem::UpdateMap`], see the
+    /// [change sets](Self#change-sets) section for which data is visible in each schedule.
     pub fn just_removed(&self) -> &HashSet<GridHash<P>, PassHash> {
         &self.map.just_removed
     }
+
+    /// An estimate of the heap memory allocated by this map, in bytes. This counts the capacity of
+    /// the map and of each cell's entity set, ignoring allocator overhead.
+    pub fn approximate_heap_size(&self) -> usize {
+        use std::mem::size_of;
+        let entries = self.map.inner.capacity() * size_of::<(GridHash<P>, GridHashEntry<P>)>()
+            + self
+                .map
+                .inner
+                .values()
+                .map(|entry| {
+                    entry.entities.capacity() * size_of::<Entity>()
+                        + entry.occupied_neighbors.capacity() * size_of::<GridHash<P>>()
+                })
+                .sum::<usize>();
+        let pools = self
+            .map
+            .hash_set_pool
+            .iter()
+            .map(|set| set.capacity() * size_of::<Entity>())
+            .sum::<usize>()
+            + self
+                .map
+                .neighbor_pool
+                .iter()
+                .map(|vec| vec.capacity() * size_of::<GridHash<P>>())
+                .sum::<usize>();
+        let changes = (self.map.just_inserted.capacity() + self.map.just_removed.capacity())
+            * size_of::<GridHash<P>>();
+        let reverse_map = self.reverse_map.capacity() * size_of::<(Entity, GridHash<P>)>();
+        entries + pools + changes + reverse_map
+    }
+
+    /// The cells in [`Self::just_removed`] that are in the `grid` entity, and are inside the cell
+    /// bounding box from `min` to `max`, inclusive.
+    ///
+    /// Useful for streaming systems that only manage a region of a grid, and need to react to cells
+    /// in that region becoming empty, without scanning every removal.
+    pub fn removed_in_region(
+        &self,
+        grid: Entity,
+        min: GridCell<P>,
+        max: GridCell<P>,
+    ) -> impl Iterator<Item = &GridHash<P>> {
+        self.map.just_removed.iter().filter(move |hash| {
+            let cell = hash.cell();
+            hash.grid() == grid
+                && (min.x..=max.x).contains(&cell.x)
+                && (min.y..=max.y).contains(&cell.y)
+                && (min.z..=max.z).contains(&cell.z)
+        })
+    }
+}
+
+/// A callback registered in [`GridHashMapHooks`].
+pub type GridHashHook<P> = Box<dyn FnMut(&GridHash<P>) + Send + Sync>;
+
+/// Callbacks that are run by the [`GridHashMap`] with the same filter `F`, when a cell becomes
+/// occupied or empty. This resource is added by the [`GridHashPlugin`].
+///
+/// The hooks are run during [`GridHashMapSystem::UpdateMap`], immediately after the map is updated,
+/// once for every cell in [`GridHashMap::just_inserted`] or [`GridHashMap::just_removed`]. This is
+/// useful for keeping an external acceleration structure, like a BVH, in sync with the occupied
+/// cells, without diffing the change sets yourself. Any number of hooks can be registered, and
+/// they are run in the order they were added.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use big_space::{prelude::*, hash::map::GridHashMapHooks};
+/// let mut app = App::new();
+/// app.add_plugins(GridHashPlugin::<i64>::default());
+/// app.world_mut()
+///     .resource_mut::<GridHashMapHooks<i64>>()
+///     .on_occupied(|hash| info!("Cell {:?} is now occupied", hash.cell()))
+///     .on_vacated(|hash| info!("Cell {:?} is now empty", hash.cell()));
+/// ```
+#[derive(Resource)]
+pub struct GridHashMapHooks<P, F = ()>
+where
+    P: GridPrecision,
+    F: GridHashMapFilter,
+{
+    occupied: Vec<GridHashHook<P>>,
+    vacated: Vec<GridHashHook<P>>,
+    spooky: PhantomData<F>,
+}
+
+impl<P: GridPrecision, F: GridHashMapFilter> Default for GridHashMapHooks<P, F> {
+    fn default() -> Self {
+        Self {
+            occupied: Vec::new(),
+            vacated: Vec::new(),
+            spooky: PhantomData,
+        }
+    }
+}
+
+impl<P: GridPrecision, F: GridHashMapFilter> GridHashMapHooks<P, F> {
+    /// Register a `hook` that is run for every cell that was empty, and is now occupied.
+    pub fn on_occupied(
+        &mut self,
+        hook: impl FnMut(&GridHash<P>) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.occupied.push(Box::new(hook));
+        self
+    }
+
+    /// Register a `hook` that is run for every cell that was occupied, and is now empty.
+    pub fn on_vacated(
+        &mut self,
+        hook: impl FnMut(&GridHash<P>) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.vacated.push(Box::new(hook));
+        self
+    }
+
+    fn run(&mut self, map: &GridHashMap<P, F>) {
+        for hook in &mut self.occupied {
+            map.just_inserted().iter().for_each(&mut *hook);
+        }
+        for hook in &mut self.vacated {
+            map.just_removed().iter().for_each(&mut *hook);
+        }
+    }
 }
 
 /// Private Systems
@@ -252,6 +739,8 @@ impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
         all_hashes: Query<(Entity, &GridHash<P>), F>,
         mut removed: RemovedComponents<GridHash<P>>,
         mut stats: Option<ResMut<crate::timing::GridHashStats>>,
+        mut occupancy: Option<ResMut<crate::timing::GridHashOccupancy<P, F>>>,
+        mut hooks: Option<ResMut<GridHashMapHooks<P, F>>>,
     ) {
         let start = Instant::now();
 
@@ -275,6 +764,16 @@ impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
             spatial_map.insert(entity, *spatial_hash);
         }
 
+        if let Some(ref mut hooks) = hooks {
+            hooks.run(&spatial_map);
+        }
+
+        if let Some(ref mut occupancy) = occupancy {
+            occupancy.occupied_cells = spatial_map.map.inner.len();
+            occupancy.entities = spatial_map.reverse_map.len();
+            occupancy.max_entities_per_cell = spatial_map.map.max_entities_per_cell();
+        }
+
         if let Some(ref mut stats) = stats {
             stats.map_update_duration += start.elapsed();
         }
@@ -347,18 +846,63 @@ struct InnerGridHashMap<P: GridPrecision> {
     /// <https://en.wikipedia.org/wiki/Object_pool_pattern>.
     hash_set_pool: Vec<HashSet<Entity, EntityHash>>,
     neighbor_pool: Vec<Vec<GridHash<P>>>,
+    /// The maximum length of each object pool, beyond which allocations are freed.
+    pool_limit: Option<usize>,
     /// Cells that were added because they were empty but now contain entities.
     just_inserted: HashSet<GridHash<P>, PassHash>,
     /// Cells that were removed because all entities vacated the cell.
     just_removed: HashSet<GridHash<P>, PassHash>,
+    /// The largest number of entities in a single cell, if `max_entities_dirty` is `false`.
+    max_entities: usize,
+    /// Set when an entity is removed from the most populated cell, which means the maximum must be
+    /// recomputed.
+    max_entities_dirty: bool,
 }
 
 impl<P: GridPrecision> InnerGridHashMap<P> {
+    fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional);
+        self.just_inserted.reserve(additional);
+
+        let pooled = additional.min(self.pool_limit.unwrap_or(usize::MAX));
+        let missing_sets = pooled.saturating_sub(self.hash_set_pool.len());
+        self.hash_set_pool.extend(
+            std::iter::repeat_with(|| HashSet::with_capacity_and_hasher(1, EntityHash))
+                .take(missing_sets),
+        );
+        let missing_neighbors = pooled.saturating_sub(self.neighbor_pool.len());
+        self.neighbor_pool.extend(
+            std::iter::repeat_with(|| Vec::with_capacity(Self::POOLED_NEIGHBORS))
+                .take(missing_neighbors),
+        );
+    }
+
+    /// The capacity of the neighbor lists allocated by [`Self::reserve`], enough for a cell with a
+    /// few occupied neighbors without using too much memory for sparse cells.
+    const POOLED_NEIGHBORS: usize = 4;
+
+    /// The largest number of entities in a single cell. Only scans the map if an entity was removed
+    /// from the most populated cell since the last call.
+    fn max_entities_per_cell(&mut self) -> usize {
+        if self.max_entities_dirty {
+            self.max_entities = self
+                .inner
+                .values()
+                .map(|entry| entry.entities.len())
+                .max()
+                .unwrap_or_default();
+            self.max_entities_dirty = false;
+        }
+        self.max_entities
+    }
+
     #[inline]
     fn insert(&mut self, entity: Entity, hash: GridHash<P>) {
         if let Some(entry) = self.inner.get_mut(&hash) {
             entry.entities.insert(entity);
+            self.max_entities = self.max_entities.max(entry.entities.len());
         } else {
+            self.max_entities = self.max_entities.max(1);
             let mut entities = self.hash_set_pool.pop().unwrap_or_default();
             entities.insert(entity);
 
@@ -392,6 +936,9 @@ impl<P: GridPrecision> InnerGridHashMap<P> {
     #[inline]
     fn remove(&mut self, entity: Entity, old_hash: GridHash<P>) {
         if let Some(entry) = self.inner.get_mut(&old_hash) {
+            if entry.entities.len() == self.max_entities {
+                self.max_entities_dirty = true;
+            }
             entry.entities.remove(&entity);
             if !entry.entities.is_empty() {
                 return; // Early exit if the cell still has other entities in it
@@ -414,8 +961,18 @@ impl<P: GridPrecision> InnerGridHashMap<P> {
                 });
 
             // Add the allocated structs to their object pools, to reuse the allocations.
-            self.hash_set_pool.push(removed_entry.entities);
-            self.neighbor_pool.push(removed_entry.occupied_neighbors);
+            if self
+                .pool_limit
+                .is_none_or(|limit| self.hash_set_pool.len() < limit)
+            {
+                self.hash_set_pool.push(removed_entry.entities);
+            }
+            if self
+                .pool_limit
+                .is_none_or(|limit| self.neighbor_pool.len() < limit)
+            {
+                self.neighbor_pool.push(removed_entry.occupied_neighbors);
+            }
 
             if !self.just_inserted.remove(&old_hash) {
                 // If a cell is added then removed within the same update, it can't be considered
@@ -426,25 +983,48 @@ impl<P: GridPrecision> InnerGridHashMap<P> {
     }
 }
 
-/// An iterator over the neighbors of a cell, breadth-first.
-pub struct ContiguousNeighborsIter<'a, P, F>
+/// An iterator over the neighbors of a cell, breadth-first. See [`GridHashMap::flood_where`].
+pub struct ContiguousNeighborsIter<'a, P, F, W>
 where
     P: GridPrecision,
     F: GridHashMapFilter,
+    W: FnMut(&GridHash<P>, &GridHashEntry<P>) -> bool,
 {
     initial_hash: Option<GridHash<P>>,
     spatial_map: &'a GridHashMap<P, F>,
     stack: VecDeque<Neighbor<'a, P>>,
     visited_cells: HashSet<GridHash<P>>,
+    predicate: W,
+}
+
+/// The offsets of the cells at a chebyshev distance of exactly `radius` from a cell.
+fn ring(radius: i32) -> impl Iterator<Item = IVec3> {
+    let range = -radius..=radius;
+    range.clone().flat_map(move |x| {
+        let range = range.clone();
+        range.clone().flat_map(move |y| {
+            // Only the faces of the cube are in the ring, skip its interior.
+            let step = if x.abs() == radius || y.abs() == radius {
+                1
+            } else {
+                (2 * radius).max(1)
+            };
+            range
+                .clone()
+                .step_by(step as usize)
+                .map(move |z| IVec3::new(x, y, z))
+        })
+    })
 }
 
 /// Newtype used for adding useful extensions like `.entities()`.
 pub struct Neighbor<'a, P: GridPrecision>(pub GridHash<P>, pub &'a GridHashEntry<P>);
 
-impl<'a, P, F> Iterator for ContiguousNeighborsIter<'a, P, F>
+impl<'a, P, F, W> Iterator for ContiguousNeighborsIter<'a, P, F, W>
 where
     P: GridPrecision,
     F: GridHashMapFilter,
+    W: FnMut(&GridHash<P>, &GridHashEntry<P>) -> bool,
 {
     type Item = Neighbor<'a, P>;
 
@@ -466,6 +1046,9 @@ where
                     .expect("Neighbor hashes in GridHashEntry are guaranteed to exist.");
                 (neighbor_hash, entry)
             })
+            .filter(|(neighbor_hash, neighbor_entry)| {
+                (self.predicate)(neighbor_hash, neighbor_entry)
+            })
         {
             self.stack
                 .push_front(Neighbor(*neighbor_hash, neighbor_entry));
@@ -473,3 +1056,38 @@ where
         Some(Neighbor(hash, entry))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserve_fills_pools() {
+        let mut map = GridHashMap::<i32>::default();
+        map.reserve(100);
+        assert!(map.capacity() >= 100);
+        assert!(map.reverse_map.capacity() >= 100);
+        assert_eq!(map.map.hash_set_pool.len(), 100);
+        assert_eq!(map.map.neighbor_pool.len(), 100);
+
+        // Reserving again only tops up the pools.
+        map.reserve(50);
+        assert_eq!(map.map.hash_set_pool.len(), 100);
+
+        // New cells take their allocations from the pools.
+        let grid = Entity::from_raw(0);
+        for x in 0..60 {
+            let hash = GridHash::new_for(grid, GridCell::new(x * 2, 0, 0));
+            map.map.insert(Entity::from_raw(x as u32 + 1), hash);
+        }
+        assert_eq!(map.map.hash_set_pool.len(), 40);
+        assert_eq!(map.map.neighbor_pool.len(), 40);
+
+        let mut limited = GridHashMap::<i32>::default();
+        limited.set_pool_limit(Some(10));
+        limited.reserve(100);
+        assert!(limited.capacity() >= 100);
+        assert_eq!(limited.map.hash_set_pool.len(), 10);
+        assert_eq!(limited.map.neighbor_pool.len(), 10);
+    }
+}
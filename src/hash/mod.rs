@@ -7,8 +7,13 @@ use bevy_app::prelude::*;
 use bevy_ecs::{prelude::*, query::QueryFilter};
 
 pub mod component;
+pub mod cone;
+pub mod line_of_sight;
 pub mod map;
+pub mod nearby;
 pub mod partition;
+pub mod raycast;
+pub mod shell;
 
 /// Add spatial hashing acceleration to `big_space`, accessible through the [`GridHashMap`] resource,
 /// and [`GridHash`] components.
@@ -20,10 +25,26 @@ pub mod partition;
 /// If you are adding multiple copies of this plugin with different filters, there are optimizations
 /// in place to avoid duplicating work. However, you should still take care to avoid excessively
 /// overlapping filters.
-pub struct GridHashPlugin<P, F = ()>(PhantomData<(P, F)>)
+pub struct GridHashPlugin<P, F = ()>
 where
     P: GridPrecision,
-    F: GridHashMapFilter;
+    F: GridHashMapFilter,
+{
+    pool_limit: Option<usize>,
+    spooky: PhantomData<(P, F)>,
+}
+
+impl<P: GridPrecision, F: GridHashMapFilter> GridHashPlugin<P, F> {
+    /// Limit the number of allocations the [`GridHashMap`] keeps for reuse when cells are vacated,
+    /// which is unlimited by default. Use this if the number of occupied cells spikes then drops,
+    /// to free the memory of the excess allocations instead of keeping it forever, at the cost of
+    /// allocating again if the number of occupied cells grows. See
+    /// [`GridHashMap::set_pool_limit`].
+    pub fn with_pool_limit(mut self, limit: usize) -> Self {
+        self.pool_limit = Some(limit);
+        self
+    }
+}
 
 impl<P, F> Plugin for GridHashPlugin<P, F>
 where
@@ -31,18 +52,34 @@ where
     F: GridHashMapFilter,
 {
     fn build(&self, app: &mut App) {
-        app.init_resource::<GridHashMap<P, F>>()
+        let mut map = GridHashMap::<P, F>::default();
+        map.set_pool_limit(self.pool_limit);
+        app.insert_resource(map)
+            .init_resource::<crate::timing::GridHashOccupancy<P, F>>()
             .init_resource::<ChangedGridHashes<P, F>>()
+            .init_resource::<map::GridHashMapHooks<P, F>>()
+            // The maps are not registered, they are derived from the `GridHash`es every update, and
+            // would not be useful to inspect or serialize.
             .register_type::<GridHash<P>>()
+            .register_type::<FastGridHash>()
+            .register_type::<component::NoSpatialHash>()
+            .register_type::<component::StaticSpatial>()
+            .configure_sets(
+                PostUpdate,
+                (
+                    GridHashMapSystem::UpdateHash,
+                    GridHashMapSystem::UpdateMap,
+                    GridHashMapSystem::UpdatePartition,
+                )
+                    .chain()
+                    .after(FloatingOriginSystem::RecenterLargeTransforms)
+                    .before(FloatingOriginSystem::LocalFloatingOrigins),
+            )
             .add_systems(
                 PostUpdate,
                 (
-                    GridHash::<P>::update::<F>
-                        .in_set(GridHashMapSystem::UpdateHash)
-                        .after(FloatingOriginSystem::RecenterLargeTransforms),
-                    GridHashMap::<P, F>::update
-                        .in_set(GridHashMapSystem::UpdateMap)
-                        .after(GridHashMapSystem::UpdateHash),
+                    GridHash::<P>::update::<F>.in_set(GridHashMapSystem::UpdateHash),
+                    GridHashMap::<P, F>::update.in_set(GridHashMapSystem::UpdateMap),
                 ),
             );
     }
@@ -50,11 +87,17 @@ where
 
 impl<P: GridPrecision, F: GridHashMapFilter> Default for GridHashPlugin<P, F> {
     fn default() -> Self {
-        Self(PhantomData)
+        Self {
+            pool_limit: None,
+            spooky: PhantomData,
+        }
     }
 }
 
-/// System sets for [`GridHashPlugin`].
+/// System sets for [`GridHashPlugin`]. These run in order, after
+/// [`FloatingOriginSystem::RecenterLargeTransforms`], and before
+/// [`FloatingOriginSystem::LocalFloatingOrigins`]. See [`FloatingOriginSystem`] for the full
+/// ordering.
 #[derive(SystemSet, Hash, Debug, PartialEq, Eq, Clone)]
 pub enum GridHashMapSystem {
     /// [`GridHash`] updated.
@@ -105,16 +148,14 @@ impl<P: GridPrecision, F: GridHashMapFilter> Default for ChangedGridHashes<P, F>
     }
 }
 
-// TODO:
-//
-// - When an entity is re-parented, is is removed/updated in the spatial map?
-// - Entities are hashed with their parent - what happens if an entity is moved to the root? Is the
-//   hash ever recomputed? Is it removed? Is the spatial map updated?
 #[cfg(test)]
 mod tests {
     use std::sync::OnceLock;
 
-    use crate::{hash::map::SpatialEntryToEntities, prelude::*};
+    use crate::{
+        hash::map::{GridHashEntry, SpatialEntryToEntities},
+        prelude::*,
+    };
     use bevy_utils::hashbrown::HashSet;
 
     #[test]
@@ -147,6 +188,13 @@ mod tests {
             .get(&hash)
             .is_some());
 
+        let parent = app
+            .world()
+            .get::<Parent>(*ENTITY.get().unwrap())
+            .unwrap()
+            .get();
+        assert_eq!(GridHash::new_for(parent, GridCell::<i32>::ZERO), hash);
+
         app.world_mut().despawn(*ENTITY.get().unwrap());
 
         app.update();
@@ -158,6 +206,218 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    fn demote_to_low_precision() {
+        use bevy::prelude::*;
+
+        static ENTITIES: OnceLock<(Entity, Entity)> = OnceLock::new();
+
+        let setup = |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                root.spawn_spatial(FloatingOrigin);
+                let mut grid = root.spawn_grid(
+                    Grid::new_uniform(10.0, 0.0),
+                    (
+                        GridCell::<i32>::new(1, 0, 0),
+                        Transform::from_xyz(1.0, 0.0, 0.0),
+                    ),
+                );
+                let entity = grid
+                    .spawn_spatial((
+                        GridCell::<i32>::new(0, 1, 0),
+                        Transform::from_xyz(0.0, 2.0, 0.0),
+                    ))
+                    .id();
+                ENTITIES.set((grid.id(), entity)).ok();
+            });
+        };
+
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .update();
+
+        let (grid, entity) = *ENTITIES.get().unwrap();
+        let hash = *app.world().get::<GridHash<i32>>(entity).unwrap();
+        assert!(app
+            .world()
+            .resource::<GridHashMap<i32>>()
+            .get(&hash)
+            .is_some());
+
+        app.world_mut().entity_mut(entity).remove::<GridCell<i32>>();
+        app.update();
+
+        let world = app.world();
+        assert!(world.resource::<GridHashMap<i32>>().get(&hash).is_none());
+        assert!(world.get::<GridHash<i32>>(entity).is_none());
+        assert!(world
+            .get::<crate::grid::propagation::LowPrecisionRoot>(entity)
+            .is_some());
+        let grid_cell_size = Grid::<i32>::default().cell_edge_length().x;
+        assert_eq!(
+            world.get::<GlobalTransform>(entity).unwrap().translation(),
+            Vec3::new(grid_cell_size + 1.0, 2.0, 0.0)
+        );
+
+        // The demoted entity still follows its parent through low precision propagation.
+        app.world_mut()
+            .get_mut::<Transform>(grid)
+            .unwrap()
+            .translation
+            .y = 3.0;
+        app.update();
+        assert_eq!(
+            app.world()
+                .get::<GlobalTransform>(entity)
+                .unwrap()
+                .translation(),
+            Vec3::new(grid_cell_size + 1.0, 5.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn leaving_grid_removes_hash() {
+        use bevy::prelude::*;
+
+        static ENTITIES: OnceLock<(Entity, Entity)> = OnceLock::new();
+
+        let setup = |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                let orphan = root.spawn_spatial(GridCell::<i32>::new(1, 0, 0)).id();
+                let stripped = root.spawn_spatial(GridCell::<i32>::new(2, 0, 0)).id();
+                ENTITIES.set((orphan, stripped)).ok();
+            });
+        };
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Startup, setup)
+            .update();
+
+        let (orphan, stripped) = *ENTITIES.get().unwrap();
+        let hashes = [orphan, stripped].map(|e| *app.world().get::<GridHash<i32>>(e).unwrap());
+
+        // Moved to the root of the world, out of all grids, but still has a `GridCell`.
+        app.world_mut().entity_mut(orphan).remove_parent();
+        app.world_mut()
+            .entity_mut(stripped)
+            .remove::<GridCell<i32>>();
+        app.update();
+
+        let map = app.world().resource::<GridHashMap<i32>>();
+        for (entity, hash) in [orphan, stripped].into_iter().zip(hashes) {
+            assert!(!map.contains(&hash));
+            assert!(app.world().get::<GridHash<i32>>(entity).is_none());
+            assert!(app.world().get::<FastGridHash>(entity).is_none());
+        }
+    }
+
+    #[test]
+    fn reparent_between_grids() {
+        use bevy::prelude::*;
+
+        static ENTITIES: OnceLock<(Entity, Entity, Entity, Entity)> = OnceLock::new();
+
+        let setup = |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                let (mut entity, mut other) = (Entity::PLACEHOLDER, Entity::PLACEHOLDER);
+                let a = root.with_grid_default(|grid| {
+                    entity = grid.spawn_spatial(GridCell::<i32>::new(1, 2, 3)).id();
+                    other = grid.spawn_spatial(GridCell::<i32>::new(1, 2, 3)).id();
+                });
+                let b = root.with_grid_default(|_| {});
+                ENTITIES.set((a, b, entity, other)).ok();
+            });
+        };
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Startup, setup)
+            .update();
+
+        let (a, b, entity, other) = *ENTITIES.get().unwrap();
+        let old_hash = GridHash::new_for(a, GridCell::<i32>::new(1, 2, 3));
+        let new_hash = GridHash::new_for(b, GridCell::<i32>::new(1, 2, 3));
+        assert!(app
+            .world()
+            .resource::<GridHashMap<i32>>()
+            .contains(&old_hash));
+
+        // Only the parent changes, the cell is the same.
+        app.world_mut().entity_mut(entity).set_parent(b);
+        app.update();
+
+        let map = app.world().resource::<GridHashMap<i32>>();
+        assert!(map.get(&new_hash).unwrap().entities.contains(&entity));
+        assert_eq!(app.world().get::<GridHash<i32>>(entity), Some(&new_hash));
+        assert!(map.just_inserted().contains(&new_hash));
+        // The old cell is still occupied, but no longer lists the moved entity.
+        let old_entities = &map.get(&old_hash).unwrap().entities;
+        assert!(!old_entities.contains(&entity));
+        assert!(old_entities.contains(&other));
+        assert!(!map.just_removed().contains(&old_hash));
+
+        app.world_mut().entity_mut(other).set_parent(b);
+        app.update();
+
+        let map = app.world().resource::<GridHashMap<i32>>();
+        assert!(!map.contains(&old_hash));
+        assert!(map.just_removed().contains(&old_hash));
+        assert_eq!(map.get(&new_hash).unwrap().entities.len(), 2);
+    }
+
+    #[test]
+    fn nearest_filtered() {
+        use bevy::prelude::*;
+
+        #[derive(Component)]
+        struct Enemy;
+
+        static ROOT: OnceLock<Entity> = OnceLock::new();
+
+        let setup = |mut commands: Commands| {
+            let root = commands.spawn_big_space_default::<i32>(|root| {
+                root.spawn_spatial(GridCell::<i32>::new(1, 0, 0));
+                // In a nearer ring, but farther than the cell below.
+                root.spawn_spatial((GridCell::<i32>::new(2, 2, 2), Enemy));
+                root.spawn_spatial((GridCell::<i32>::new(3, 0, 0), Enemy));
+                // Nearer, but in another grid.
+                root.spawn_grid(Grid::default(), GridCell::<i32>::new(9, 9, 9))
+                    .spawn_spatial((GridCell::<i32>::new(0, 0, 1), Enemy));
+            });
+            ROOT.set(root).ok();
+        };
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Update, setup)
+            .update();
+
+        let world = app.world();
+        let root = *ROOT.get().unwrap();
+        let map = world.resource::<GridHashMap<i32>>();
+        let grid = world.get::<Grid<i32>>(root).unwrap();
+        let seed = GridHash::new_for(root, GridCell::ZERO);
+        let is_enemy = |entity| world.get::<Enemy>(entity).is_some();
+
+        let (_, hash) = map.nearest_filtered(&seed, is_enemy, grid).unwrap();
+        assert_eq!(hash.cell(), GridCell::new(3, 0, 0));
+
+        let (_, hash) = map.nearest_filtered(&seed, |_| true, grid).unwrap();
+        assert_eq!(hash.cell(), GridCell::new(1, 0, 0));
+
+        assert!(map.nearest_filtered(&seed, |_| false, grid).is_none());
+
+        // Entities far away are found after falling back to scanning every cell.
+        let far = GridHash::new_for(root, GridCell::new(1_000, 0, 0));
+        let (_, hash) = map.nearest_filtered(&far, is_enemy, grid).unwrap();
+        assert_eq!(hash.cell(), GridCell::new(3, 0, 0));
+    }
+
     #[test]
     fn get_hash() {
         use bevy::prelude::*;
@@ -301,6 +561,179 @@ mod tests {
         assert!(flooded.contains(&entities.c));
     }
 
+    #[test]
+    fn within_cube_exclusive() {
+        use bevy::prelude::*;
+
+        #[derive(Resource, Clone)]
+        struct Entities {
+            a: Entity,
+            b: Entity,
+            c: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i32>(|root| {
+                    let a = root.spawn_spatial(GridCell::new(0, 0, 0)).id();
+                    let b = root.spawn_spatial(GridCell::new(1, 1, 1)).id();
+                    let c = root.spawn_spatial(GridCell::new(2, 2, 2)).id();
+                    root.commands().insert_resource(Entities { a, b, c });
+                });
+            });
+        app.update();
+
+        let entities = app.world().resource::<Entities>().clone();
+        let grid = app.world().get::<Parent>(entities.a).unwrap().get();
+        let map = app.world().resource::<GridHashMap<i32>>();
+
+        let center = GridHash::new_for(grid, GridCell::new(1, 1, 1));
+        let inclusive: HashSet<Entity> = map.within_cube(&center, 1).entities().collect();
+        assert_eq!(inclusive.len(), 3);
+        let exclusive: HashSet<Entity> = map.within_cube_exclusive(&center, 1).entities().collect();
+        assert!(exclusive.contains(&entities.a));
+        assert!(!exclusive.contains(&entities.b));
+        assert!(exclusive.contains(&entities.c));
+    }
+
+    #[test]
+    fn within_cube_wrapped() {
+        use bevy::prelude::*;
+
+        #[derive(Resource, Clone)]
+        struct Entities {
+            a: Entity,
+            b: Entity,
+            c: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i32>(|root| {
+                    let a = root.spawn_spatial(GridCell::new(0, 0, 0)).id();
+                    let b = root.spawn_spatial(GridCell::new(1, 1, 1)).id();
+                    let c = root.spawn_spatial(GridCell::new(2, 2, 2)).id();
+                    root.commands().insert_resource(Entities { a, b, c });
+                });
+            });
+        app.update();
+
+        let entities = app.world().resource::<Entities>().clone();
+        let grid = app.world().get::<Parent>(entities.a).unwrap().get();
+        let map = app.world().resource::<GridHashMap<i32>>();
+
+        let wrap = GridWrap::new(GridCell::new(0, 0, 0), GridCell::new(2, 2, 2));
+        let corner = GridHash::new_for(grid, GridCell::new(2, 2, 2));
+        let unwrapped: HashSet<Entity> = map.within_cube(&corner, 1).entities().collect();
+        assert!(!unwrapped.contains(&entities.a));
+
+        // Wrapping around the seam, the far corner is adjacent to the origin.
+        let wrapped: HashSet<Entity> = map
+            .within_cube_wrapped(&corner, 1, &wrap)
+            .entities()
+            .collect();
+        assert!(wrapped.contains(&entities.a));
+        assert!(wrapped.contains(&entities.b));
+        assert!(wrapped.contains(&entities.c));
+        // Cells are visited at most once, even when the cube is larger than the wrapping range.
+        assert_eq!(map.within_cube_wrapped(&corner, 3, &wrap).count(), 3);
+    }
+
+    #[test]
+    fn spatial_join() {
+        use bevy::prelude::*;
+
+        #[derive(Component)]
+        struct Projectile;
+
+        #[derive(Component)]
+        struct Enemy;
+
+        static ROOT: OnceLock<Entity> = OnceLock::new();
+
+        let setup = |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                root.spawn_spatial((GridCell::<i32>::new(0, 0, 0), Projectile));
+                root.spawn_spatial((GridCell::<i32>::new(0, 0, 0), Enemy));
+                root.spawn_spatial((GridCell::<i32>::new(0, 0, 0), Enemy));
+                root.spawn_spatial((GridCell::<i32>::new(1, 0, 0), Projectile));
+                root.spawn_spatial((GridCell::<i32>::new(2, 0, 0), Enemy));
+                root.spawn_spatial((GridCell::<i32>::new(3, 0, 0), Enemy));
+                ROOT.set(root.id()).ok();
+            });
+        };
+
+        let mut app = App::new();
+        app.add_plugins((
+            GridHashPlugin::<i32, With<Projectile>>::default(),
+            GridHashPlugin::<i32, With<Enemy>>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .update();
+
+        let zero_hash = GridHash::from_parent(*ROOT.get().unwrap(), &GridCell::ZERO);
+        let projectiles = app.world().resource::<GridHashMap<i32, With<Projectile>>>();
+        let enemies = app.world().resource::<GridHashMap<i32, With<Enemy>>>();
+
+        // The projectile map is smaller, but the entries are returned in the order of the maps.
+        let joined: Vec<_> = projectiles.spatial_join(enemies).collect();
+        assert_eq!(joined.len(), 1);
+        let (hash, projectile, enemy) = joined[0];
+        assert_eq!(hash, zero_hash);
+        assert_eq!(projectile.entities.len(), 1);
+        assert_eq!(enemy.entities.len(), 2);
+
+        let (hash, enemy, projectile) = enemies.spatial_join(projectiles).next().unwrap();
+        assert_eq!(hash, zero_hash);
+        assert_eq!(projectile.entities.len(), 1);
+        assert_eq!(enemy.entities.len(), 2);
+    }
+
+    #[test]
+    fn pool_limit() {
+        use bevy::prelude::*;
+
+        #[derive(Component)]
+        struct Limited;
+
+        let mut app = App::new();
+        app.add_plugins((
+            GridHashPlugin::<i32>::default(),
+            GridHashPlugin::<i32, With<Limited>>::default().with_pool_limit(2),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                for x in 0..100 {
+                    root.spawn_spatial((GridCell::<i32>::new(x * 2, 0, 0), Limited));
+                }
+            });
+        })
+        .update();
+
+        let spatial: Vec<Entity> = app
+            .world_mut()
+            .query_filtered::<Entity, With<Limited>>()
+            .iter(app.world())
+            .collect();
+        for entity in spatial {
+            app.world_mut().despawn(entity);
+        }
+        app.update();
+
+        let unlimited = app.world().resource::<GridHashMap<i32>>();
+        let limited = app.world().resource::<GridHashMap<i32, With<Limited>>>();
+        assert_eq!(limited.pool_limit(), Some(2));
+        assert_eq!(unlimited.pool_limit(), None);
+        let pooled_size = unlimited.approximate_heap_size();
+        assert!(limited.approximate_heap_size() < pooled_size);
+
+        let mut unlimited = app.world_mut().resource_mut::<GridHashMap<i32>>();
+        unlimited.shrink_pools();
+        assert!(unlimited.approximate_heap_size() < pooled_size);
+    }
+
     #[test]
     fn query_filters() {
         use bevy::prelude::*;
@@ -429,4 +862,501 @@ mod tests {
         assert!(map.just_inserted().contains(&b_hash_t1)); // Moved cell via transform
         assert!(!map.just_inserted().contains(&c_hash_t1)); // Did not move
     }
+
+    #[test]
+    fn occupancy() {
+        use crate::timing::GridHashOccupancy;
+        use bevy::prelude::*;
+
+        #[derive(Resource)]
+        struct Crowded(Vec<Entity>);
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i32>(|root| {
+                    let crowded = (0..3)
+                        .map(|_| root.spawn_spatial(GridCell::new(1, 1, 1)).id())
+                        .collect();
+                    root.spawn_spatial(GridCell::new(2, 0, 0));
+                    root.spawn_spatial(GridCell::new(2, 0, 0));
+                    root.commands().insert_resource(Crowded(crowded));
+                });
+            });
+        app.update();
+
+        let occupancy = app.world().resource::<GridHashOccupancy<i32>>();
+        assert_eq!(occupancy.occupied_cells, 2);
+        assert_eq!(occupancy.entities, 5);
+        assert_eq!(occupancy.max_entities_per_cell, 3);
+        assert_eq!(occupancy.average_entities_per_cell(), 2.5);
+
+        let crowded = app.world().resource::<Crowded>().0.clone();
+        app.world_mut().despawn(crowded[0]);
+        app.world_mut().despawn(crowded[1]);
+        app.update();
+
+        let occupancy = app.world().resource::<GridHashOccupancy<i32>>();
+        assert_eq!(occupancy.occupied_cells, 2);
+        assert_eq!(occupancy.entities, 3);
+        assert_eq!(occupancy.max_entities_per_cell, 2);
+    }
+
+    #[test]
+    fn entries_z_order() {
+        use bevy::prelude::*;
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i8>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i8>(|root| {
+                    let (min, max) = (GridCell::new(-2, -2, -2), GridCell::new(1, 1, 1));
+                    for cell in GridCell::<i8>::range(max, min) {
+                        root.spawn_spatial(cell);
+                    }
+                });
+            });
+        app.update();
+
+        // Interleave the bits of the biased coordinates to compute the Morton code directly.
+        let morton = |cell: GridCell<i8>| {
+            let [x, y, z] = [cell.x, cell.y, cell.z].map(|v| (v as u8 ^ 0x80) as u32);
+            (0..8).fold(0u32, |code, bit| {
+                code | ((x >> bit) & 1) << (3 * bit + 2)
+                    | ((y >> bit) & 1) << (3 * bit + 1)
+                    | ((z >> bit) & 1) << (3 * bit)
+            })
+        };
+
+        let map = app.world().resource::<GridHashMap<i8>>();
+        let cells: Vec<_> = map.entries_z_order().map(|(hash, _)| hash.cell()).collect();
+        let mut expected = cells.clone();
+        expected.sort_by_key(|cell| morton(*cell));
+        assert_eq!(cells.len(), 64);
+        assert_eq!(cells, expected);
+        assert_eq!(cells[0], GridCell::new(-2, -2, -2));
+    }
+
+    #[test]
+    fn no_spatial_hash() {
+        use bevy::prelude::*;
+
+        #[derive(Resource)]
+        struct Entities {
+            opted_out: Entity,
+            later: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i32>(|root| {
+                    let opted_out = root
+                        .spawn_spatial((GridCell::<i32>::new(1, 2, 3), NoSpatialHash))
+                        .id();
+                    let later = root.spawn_spatial(GridCell::<i32>::new(1, 2, 3)).id();
+                    root.commands()
+                        .insert_resource(Entities { opted_out, later });
+                });
+            });
+        app.update();
+
+        let Entities { opted_out, later } = *app.world().resource::<Entities>();
+        let in_map = |app: &App, entity| {
+            app.world()
+                .resource::<GridHashMap<i32>>()
+                .all_entries()
+                .any(|(_, entry)| entry.entities.contains(&entity))
+        };
+        assert!(app.world().get::<GridHash<i32>>(opted_out).is_none());
+        assert!(!in_map(&app, opted_out));
+        assert!(in_map(&app, later));
+
+        // Opting out an entity that was already hashed removes it from the map.
+        app.world_mut().entity_mut(later).insert(NoSpatialHash);
+        app.update();
+        assert!(!in_map(&app, later));
+        app.world_mut()
+            .get_mut::<GridCell<i32>>(opted_out)
+            .unwrap()
+            .x += 1;
+        app.update();
+        assert!(!in_map(&app, opted_out));
+    }
+
+    #[test]
+    fn static_spatial() {
+        use bevy::prelude::*;
+
+        #[derive(Resource)]
+        struct Static(Entity);
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i32>(|root| {
+                    let entity = root
+                        .spawn_spatial((GridCell::<i32>::new(1, 2, 3), StaticSpatial))
+                        .id();
+                    root.commands().insert_resource(Static(entity));
+                });
+            });
+        app.update();
+
+        let entity = app.world().resource::<Static>().0;
+        let grid = app.world().get::<Parent>(entity).unwrap().get();
+        let old = GridHash::<i32>::new_for(grid, GridCell::new(1, 2, 3));
+        let new = GridHash::<i32>::new_for(grid, GridCell::new(2, 2, 3));
+        let in_map = |app: &App, hash: &GridHash<i32>| {
+            app.world()
+                .resource::<GridHashMap<i32>>()
+                .get(hash)
+                .is_some_and(|entry| entry.entities.contains(&entity))
+        };
+        // Static entities are still hashed when spawned.
+        assert!(in_map(&app, &old));
+
+        // Moving a static entity is not reflected in the map.
+        app.world_mut().get_mut::<GridCell<i32>>(entity).unwrap().x += 1;
+        app.update();
+        assert!(in_map(&app, &old));
+        assert!(!in_map(&app, &new));
+
+        // Waking the entity rehashes it.
+        app.world_mut().entity_mut(entity).remove::<StaticSpatial>();
+        app.update();
+        assert!(!in_map(&app, &old));
+        assert!(in_map(&app, &new));
+        assert_eq!(app.world().get::<GridHash<i32>>(entity), Some(&new));
+    }
+
+    #[test]
+    fn reserve_spatial_hash() {
+        use bevy::prelude::*;
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default());
+        app.world_mut()
+            .commands()
+            .spawn_big_space_default::<i32>(|root| {
+                root.reserve_spatial_hash(1_000);
+            });
+        app.world_mut().flush();
+
+        let map = app.world().resource::<GridHashMap<i32>>();
+        assert!(map.capacity() >= 1_000);
+        let capacity = map.capacity();
+
+        app.world_mut()
+            .commands()
+            .spawn_big_space_default::<i32>(|root| {
+                for x in 0..1_000 {
+                    root.spawn_spatial(GridCell::<i32>::new(x, 0, 0));
+                }
+            });
+        app.update();
+
+        // The map did not need to grow while the entities were inserted.
+        let map = app.world().resource::<GridHashMap<i32>>();
+        assert_eq!(map.all_entries().count(), 1_000);
+        assert_eq!(map.capacity(), capacity);
+    }
+
+    #[test]
+    fn flood_where() {
+        use bevy::prelude::*;
+
+        #[derive(Component)]
+        struct Wall;
+
+        #[derive(Resource)]
+        struct Seed(Entity);
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i32>(|root| {
+                    let seed = root.spawn_spatial(GridCell::<i32>::new(0, 0, 0)).id();
+                    root.spawn_spatial(GridCell::<i32>::new(1, 0, 0));
+                    root.spawn_spatial((GridCell::<i32>::new(2, 0, 0), Wall));
+                    root.spawn_spatial(GridCell::<i32>::new(3, 0, 0));
+                    root.commands().insert_resource(Seed(seed));
+                });
+            });
+        app.update();
+
+        let seed = app.world().resource::<Seed>().0;
+        let walls: HashSet<Entity> = app
+            .world_mut()
+            .query_filtered::<Entity, With<Wall>>()
+            .iter(app.world())
+            .collect();
+        let world = app.world();
+        let seed_hash = world.get::<GridHash<i32>>(seed).unwrap();
+        let map = world.resource::<GridHashMap<i32>>();
+
+        assert_eq!(map.flood(seed_hash, None).count(), 4);
+        let not_walls = |_: &GridHash<i32>, entry: &GridHashEntry<i32>| {
+            entry.entities.iter().all(|entity| !walls.contains(entity))
+        };
+        let flooded: Vec<_> = map
+            .flood_where(seed_hash, not_walls)
+            .map(|neighbor| neighbor.0.cell())
+            .collect();
+        assert_eq!(
+            flooded,
+            vec![GridCell::new(0, 0, 0), GridCell::new(1, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn entities_sorted() {
+        use bevy::prelude::*;
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i32>(|root| {
+                    for _ in 0..100 {
+                        root.spawn_spatial(GridCell::<i32>::new(1, 2, 3));
+                    }
+                });
+            });
+        app.update();
+
+        let map = app.world().resource::<GridHashMap<i32>>();
+        let (_, entry) = map
+            .all_entries()
+            .find(|(hash, _)| hash.cell() == GridCell::new(1, 2, 3))
+            .unwrap();
+        let sorted: Vec<_> = entry.entities_sorted().collect();
+        assert_eq!(sorted.len(), 100);
+        assert!(sorted.windows(2).all(|w| w[0].to_bits() < w[1].to_bits()));
+    }
+
+    #[test]
+    fn hooks() {
+        use crate::hash::map::GridHashMapHooks;
+        use bevy::prelude::*;
+        use std::sync::{Arc, Mutex};
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i32>(|root| {
+                    root.spawn_spatial(GridCell::<i32>::new(0, 0, 0));
+                    root.spawn_spatial(GridCell::<i32>::new(0, 0, 0));
+                    root.spawn_spatial(GridCell::<i32>::new(5, 0, 0));
+                });
+            });
+
+        let occupied = Arc::new(Mutex::new(Vec::new()));
+        let vacated = Arc::new(Mutex::new(Vec::new()));
+        let second_hook_calls = Arc::new(Mutex::new(0));
+        let (o, v, s) = (occupied.clone(), vacated.clone(), second_hook_calls.clone());
+        app.world_mut()
+            .resource_mut::<GridHashMapHooks<i32>>()
+            .on_occupied(move |hash| o.lock().unwrap().push(hash.cell()))
+            .on_vacated(move |hash| v.lock().unwrap().push(hash.cell()))
+            .on_occupied(move |_| *s.lock().unwrap() += 1);
+
+        app.update();
+        let mut cells = occupied.lock().unwrap().clone();
+        cells.sort();
+        assert_eq!(cells, vec![GridCell::new(0, 0, 0), GridCell::new(5, 0, 0)]);
+        assert_eq!(*second_hook_calls.lock().unwrap(), 2);
+
+        let mut query = app.world_mut().query::<&mut GridCell<i32>>();
+        for mut cell in query.iter_mut(app.world_mut()) {
+            if cell.x == 5 {
+                cell.x = 6;
+            }
+        }
+        app.update();
+        assert_eq!(occupied.lock().unwrap().len(), 3);
+        assert_eq!(occupied.lock().unwrap()[2], GridCell::new(6, 0, 0));
+        assert_eq!(*vacated.lock().unwrap(), vec![GridCell::new(5, 0, 0)]);
+    }
+
+    #[test]
+    fn flood_par() {
+        use bevy::prelude::*;
+
+        #[derive(Resource)]
+        struct Root(Entity);
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                let root = commands.spawn_big_space_default::<i32>(|root| {
+                    for x in 0..10 {
+                        for y in 0..10 {
+                            for z in 0..10 {
+                                root.spawn_spatial(GridCell::<i32>::new(x, y, z));
+                            }
+                        }
+                    }
+                    // Disconnected
+                    root.spawn_spatial(GridCell::<i32>::new(20, 0, 0));
+                });
+                commands.insert_resource(Root(root));
+            });
+        app.update();
+
+        let root = app.world().resource::<Root>().0;
+        let map = app.world().resource::<GridHashMap<i32>>();
+        let seed = GridHash::new_for(root, GridCell::new(3, 4, 5));
+
+        let serial: HashSet<_> = map.flood(&seed, None).map(|n| n.0).collect();
+        let parallel: Vec<_> = map.flood_par(&seed).map(|n| n.0).collect();
+        assert_eq!(serial.len(), 1_000);
+        assert_eq!(parallel.len(), 1_000);
+        assert_eq!(parallel.iter().copied().collect::<HashSet<_>>(), serial);
+        assert_eq!(parallel[0], seed);
+
+        // Breadth-first order: the chebyshev distance from the seed never decreases.
+        let depths: Vec<_> = parallel
+            .iter()
+            .map(|hash| CellLodDistance::new(&hash.cell(), &seed.cell()).chebyshev)
+            .collect();
+        assert!(depths.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let empty = GridHash::new_for(root, GridCell::new(-5, 0, 0));
+        assert_eq!(map.flood_par(&empty).count(), 0);
+    }
+
+    #[test]
+    fn removed_in_region() {
+        use bevy::prelude::*;
+
+        #[derive(Resource)]
+        struct Root(Entity);
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                let root = commands.spawn_big_space_default::<i32>(|root| {
+                    for x in 0..10 {
+                        root.spawn_spatial(GridCell::<i32>::new(x, 0, 0));
+                    }
+                });
+                commands.insert_resource(Root(root));
+            });
+        app.update();
+
+        let root = app.world().resource::<Root>().0;
+        let entities: Vec<Entity> = app
+            .world_mut()
+            .query_filtered::<Entity, With<GridCell<i32>>>()
+            .iter(app.world())
+            .collect();
+        for entity in entities {
+            app.world_mut().despawn(entity);
+        }
+        app.update();
+
+        let map = app.world().resource::<GridHashMap<i32>>();
+        assert_eq!(map.just_removed().len(), 10);
+        let mut removed: Vec<_> = map
+            .removed_in_region(root, GridCell::new(2, -1, -1), GridCell::new(4, 1, 1))
+            .map(|hash| hash.cell().x)
+            .collect();
+        removed.sort();
+        assert_eq!(removed, vec![2, 3, 4]);
+
+        let other_grid = Entity::from_raw(12345);
+        let mut region =
+            map.removed_in_region(other_grid, GridCell::new(2, -1, -1), GridCell::new(4, 1, 1));
+        assert!(region.next().is_none());
+    }
+
+    #[test]
+    fn iter_query() {
+        use bevy::prelude::*;
+
+        #[derive(Component)]
+        struct Value(u32);
+
+        #[derive(Resource, Default)]
+        struct Sum(u32);
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .init_resource::<Sum>()
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i32>(|root| {
+                    root.spawn_spatial((GridCell::<i32>::new(1, 0, 0), Value(1)));
+                    root.spawn_spatial((GridCell::<i32>::new(1, 0, 0), Value(2)));
+                    // Does not match the query
+                    root.spawn_spatial(GridCell::<i32>::new(1, 0, 0));
+                    // Different cell
+                    root.spawn_spatial((GridCell::<i32>::new(2, 0, 0), Value(4)));
+                });
+            })
+            .add_systems(
+                Update,
+                |map: Res<GridHashMap<i32>>,
+                 hashes: Query<&GridHash<i32>>,
+                 values: Query<&Value>,
+                 mut sum: ResMut<Sum>| {
+                    let Some(hash) = hashes
+                        .iter()
+                        .find(|hash| hash.cell() == GridCell::new(1, 0, 0))
+                    else {
+                        return;
+                    };
+                    sum.0 = map.iter_query(hash, &values).map(|value| value.0).sum();
+                },
+            );
+        app.update();
+        app.update();
+
+        assert_eq!(app.world().resource::<Sum>().0, 3);
+    }
+
+    /// A system in `Update` sees each change set exactly once, one frame after the change.
+    #[test]
+    fn change_sets_visible_in_update() {
+        use bevy::prelude::*;
+
+        #[derive(Resource, Default)]
+        struct Seen(Vec<usize>);
+
+        let mut app = App::new();
+        app.add_plugins(GridHashPlugin::<i32>::default())
+            .init_resource::<Seen>()
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i32>(|root| {
+                    root.spawn_spatial(GridCell::<i32>::new(1, 0, 0));
+                    root.spawn_spatial(GridCell::<i32>::new(2, 0, 0));
+                });
+            })
+            .add_systems(
+                Update,
+                |map: Res<GridHashMap<i32>>, mut seen: ResMut<Seen>| {
+                    seen.0.push(map.just_inserted().len());
+                },
+            );
+
+        app.update();
+        app.update();
+        app.update();
+
+        assert_eq!(app.world().resource::<Seen>().0, vec![0, 2, 0]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_compact() {
+        use bevy::{prelude::*, scene::ron};
+
+        let grid = Entity::from_raw(42);
+        let hash = GridHash::<i64>::new_for(grid, GridCell::new(1, 2, 3));
+        let text = ron::to_string(&hash).unwrap();
+        assert_eq!(text, format!("({},(1,2,3))", grid.to_bits()));
+
+        let deserialized = ron::from_str::<GridHash<i64>>(&text).unwrap();
+        assert_eq!(deserialized, hash);
+        assert!(deserialized.fast_eq(&hash));
+    }
 }
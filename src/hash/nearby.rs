@@ -0,0 +1,149 @@
+//! A [`SystemParam`] for finding entities near other entities with the [`GridHashMap`].
+
+use crate::prelude::*;
+use bevy_ecs::{prelude::*, system::SystemParam};
+use bevy_hierarchy::prelude::*;
+
+use super::GridHashMapFilter;
+
+/// A [`SystemParam`] for finding the entities near an entity, using the [`GridHashMap`] with the
+/// same [`GridHashMapFilter`].
+///
+/// ```
+/// # use big_space::prelude::*;
+/// # use bevy::prelude::*;
+/// # #[derive(Component)]
+/// # struct Player;
+/// fn near_players(nearby: Nearby<i64>, players: Query<Entity, With<Player>>) {
+///     for player in &players {
+///         for entity in nearby.iter_near(player, 1) {
+///             // ...
+///         }
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct Nearby<'w, 's, P: GridPrecision, F: GridHashMapFilter = ()> {
+    map: Res<'w, GridHashMap<P, F>>,
+    cells: Query<'w, 's, (&'static Parent, &'static GridCell<P>)>,
+    grids: Query<'w, 's, (), With<Grid<P>>>,
+}
+
+impl<P: GridPrecision, F: GridHashMapFilter> Nearby<'_, '_, P, F> {
+    /// Iterate over all entities in the cell of `entity`, and in cells within `radius` cells of
+    /// it, including `entity` itself. See [`GridHashMap::within_cube`].
+    ///
+    /// The cell of `entity` is found with [`Self::hash_of`], so this works even if `entity` has
+    /// not been hashed yet. Returns an empty iterator if `entity` is not in a grid.
+    pub fn iter_near(&self, entity: Entity, radius: u8) -> impl Iterator<Item = Entity> {
+        let Some(hash) = self.hash_of(entity) else {
+            return Vec::new().into_iter();
+        };
+        // The hash is computed here, so the entities can't borrow from it.
+        let entities: Vec<_> = self.map.within_cube(&hash, radius).entities().collect();
+        entities.into_iter()
+    }
+
+    /// Compute the [`GridHash`] of `entity` from its [`Parent`] and [`GridCell`], matching the
+    /// [`GridHash`] component the [`GridHashPlugin`] would compute for it.
+    ///
+    /// Unlike reading the [`GridHash`] component, this does not depend on whether the plugin has
+    /// hashed `entity` yet, such as right after it was spawned or moved, or whether it passes the
+    /// [`GridHashMapFilter`]. Returns `None` if `entity` does not have a [`GridCell`], or its
+    /// parent is not a [`Grid`].
+    pub fn hash_of(&self, entity: Entity) -> Option<GridHash<P>> {
+        let (parent, cell) = self.cells.get(entity).ok()?;
+        self.grids
+            .contains(parent.get())
+            .then(|| GridHash::new(parent, cell))
+    }
+
+    /// Get the underlying [`GridHashMap`].
+    pub fn map(&self) -> &GridHashMap<P, F> {
+        &self.map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn iter_near() {
+        #[derive(Resource)]
+        struct Entities {
+            center: Entity,
+            near: Entity,
+            far: Entity,
+            unhashed: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            let unhashed = commands.spawn_empty().id();
+            commands.spawn_big_space_default::<i32>(|root| {
+                root.spawn_spatial(FloatingOrigin);
+                let center = root.spawn_spatial(GridCell::new(10, 0, 0)).id();
+                let near = root.spawn_spatial(GridCell::new(11, 1, 0)).id();
+                let far = root.spawn_spatial(GridCell::new(13, 0, 0)).id();
+                root.commands().insert_resource(Entities {
+                    center,
+                    near,
+                    far,
+                    unhashed,
+                });
+            });
+        });
+
+        app.update();
+
+        let mut state = bevy::ecs::system::SystemState::<Nearby<i32>>::new(app.world_mut());
+        let nearby = state.get(app.world());
+        let entities = app.world().resource::<Entities>();
+
+        let found: Vec<_> = nearby.iter_near(entities.center, 1).collect();
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&entities.center));
+        assert!(found.contains(&entities.near));
+        assert!(nearby
+            .iter_near(entities.center, 3)
+            .any(|e| e == entities.far));
+        assert_eq!(nearby.iter_near(entities.unhashed, 1).count(), 0);
+        assert_eq!(nearby.hash_of(entities.unhashed), None);
+    }
+
+    #[test]
+    fn hash_of_unhashed_entity() {
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+        ));
+        let root = app
+            .world_mut()
+            .spawn(BigSpaceRootBundle::<i32>::default())
+            .id();
+        let center = app.world_mut().spawn(GridCell::<i32>::new(5, 0, 0)).id();
+        app.world_mut().entity_mut(root).add_child(center);
+        app.update();
+
+        // Spawned after the plugin ran, so it does not have a `GridHash` component yet.
+        let spawned = app.world_mut().spawn(GridCell::<i32>::new(6, 0, 0)).id();
+        app.world_mut().entity_mut(root).add_child(spawned);
+        assert!(app.world().get::<GridHash<i32>>(spawned).is_none());
+
+        let mut state = bevy::ecs::system::SystemState::<Nearby<i32>>::new(app.world_mut());
+        let nearby = state.get(app.world());
+        assert_eq!(
+            nearby.hash_of(spawned),
+            Some(GridHash::new_for(root, GridCell::new(6, 0, 0)))
+        );
+        assert_eq!(nearby.hash_of(root), None);
+        assert!(nearby.iter_near(spawned, 1).any(|e| e == center));
+    }
+}
@@ -1,10 +1,18 @@
 //! Detect and update groups of nearby occupied cells.
 
-use std::{hash::Hash, marker::PhantomData, ops::Deref, time::Instant};
+use std::{
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+    ops::Deref,
+    time::Instant,
+};
 
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
+use bevy_math::{BVec3, DAffine3, DVec3};
+use bevy_reflect::prelude::*;
 use bevy_tasks::{ComputeTaskPool, ParallelSliceMut};
+use bevy_transform::prelude::*;
 use bevy_utils::{
     hashbrown::{HashMap, HashSet},
     PassHash,
@@ -13,10 +21,24 @@ use bevy_utils::{
 use super::{GridHash, GridHashMap, GridHashMapFilter, GridHashMapSystem, GridPrecision};
 
 /// Adds support for spatial partitioning. Requires [`GridHashPlugin`](super::GridHashPlugin).
-pub struct GridPartitionPlugin<P, F = ()>(PhantomData<(P, F)>)
+pub struct GridPartitionPlugin<P, F = ()>
 where
     P: GridPrecision,
-    F: GridHashMapFilter;
+    F: GridHashMapFilter,
+{
+    split_budget: Option<usize>,
+    spooky: PhantomData<(P, F)>,
+}
+
+impl<P: GridPrecision, F: GridHashMapFilter> GridPartitionPlugin<P, F> {
+    /// Only check partitions with at most `max_cells` cells for splits during the update, to avoid
+    /// hitches when cells are removed from very large partitions. See
+    /// [`GridPartitionMap::set_split_budget`].
+    pub fn with_split_budget(mut self, max_cells: usize) -> Self {
+        self.split_budget = Some(max_cells);
+        self
+    }
+}
 
 impl<P, F> Default for GridPartitionPlugin<P, F>
 where
@@ -24,7 +46,10 @@ where
     F: GridHashMapFilter,
 {
     fn default() -> Self {
-        Self(PhantomData)
+        Self {
+            split_budget: None,
+            spooky: PhantomData,
+        }
     }
 }
 
@@ -34,17 +59,24 @@ where
     F: GridHashMapFilter,
 {
     fn build(&self, app: &mut App) {
-        app.init_resource::<GridPartitionMap<P, F>>().add_systems(
-            PostUpdate,
-            GridPartitionMap::<P, F>::update
-                .in_set(GridHashMapSystem::UpdatePartition)
-                .after(GridHashMapSystem::UpdateMap),
-        );
+        let mut map = GridPartitionMap::<P, F>::default();
+        map.set_split_budget(self.split_budget);
+        app.insert_resource(map)
+            .register_type::<GridPartitionId>()
+            .add_systems(
+                PostUpdate,
+                GridPartitionMap::<P, F>::update.in_set(GridHashMapSystem::UpdatePartition),
+            );
     }
 }
 
 /// Uniquely identifies a [`GridPartition`] in the [`GridPartitionMap`] resource.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Ids are ephemeral: they are reused after a partition is removed, and partitions are given new
+/// ids when they are merged or split. An id may refer to a different partition in a later frame.
+/// To correlate partitions across frames, compare their [`GridPartition::fingerprint`] instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(PartialEq)]
 pub struct GridPartitionId(u64);
 
 impl GridPartitionId {
@@ -76,6 +108,9 @@ where
     partitions: HashMap<GridPartitionId, GridPartition<P>>,
     reverse_map: HashMap<GridHash<P>, GridPartitionId, PassHash>,
     next_partition: u64,
+    split_budget: Option<usize>,
+    /// Cells next to removed cells in partitions that were over the split budget.
+    deferred_splits: HashSet<GridHash<P>, PassHash>,
     spooky: PhantomData<F>,
 }
 
@@ -89,6 +124,8 @@ where
             partitions: HashMap::default(),
             reverse_map: HashMap::default(),
             next_partition: 0,
+            split_budget: None,
+            deferred_splits: HashSet::default(),
             spooky: PhantomData,
         }
     }
@@ -117,6 +154,49 @@ where
         self.partitions.get(id)
     }
 
+    /// Only check partitions with at most `max_cells` cells for splits during the update, or all
+    /// partitions if `None`, which is the default.
+    ///
+    /// Checking whether removing a cell split a partition in two requires flood filling the
+    /// partition, which can cause a hitch for partitions with hundreds of thousands of cells.
+    /// With a budget, the split checks of larger partitions are deferred until
+    /// [`Self::check_deferred_splits`] is called, for example periodically or during a loading
+    /// screen.
+    ///
+    /// Until then, a deferred partition may contain cells that are no longer connected to each
+    /// other. Partitions are still independent: cells in different partitions are never connected,
+    /// but entities in the same partition might be far apart.
+    pub fn set_split_budget(&mut self, max_cells: Option<usize>) {
+        self.split_budget = max_cells;
+    }
+
+    /// The maximum size of partitions that are checked for splits during the update. See
+    /// [`Self::set_split_budget`].
+    pub fn split_budget(&self) -> Option<usize> {
+        self.split_budget
+    }
+
+    /// Returns `true` if there are partitions over the [split budget](Self::set_split_budget)
+    /// that may need to be split.
+    pub fn has_deferred_splits(&self) -> bool {
+        !self.deferred_splits.is_empty()
+    }
+
+    /// Split all partitions over the [split budget](Self::set_split_budget) that were disconnected
+    /// by removed cells since the last check. The `map` must be the [`GridHashMap`] this partition
+    /// map is built from. This is as expensive as a flood fill of each of those partitions.
+    pub fn check_deferred_splits(&mut self, map: &GridHashMap<P, F>) {
+        let mut candidates: HashMap<GridPartitionId, HashSet<GridHash<P>, PassHash>> =
+            HashMap::default();
+        for hash in std::mem::take(&mut self.deferred_splits) {
+            // Partitions may have been merged or removed since the split was deferred.
+            if let Some(id) = map.contains(&hash).then(|| self.get(&hash)).flatten() {
+                candidates.entry(*id).or_default().insert(hash);
+            }
+        }
+        self.split(map, &mut candidates.drain().collect(), &mut Vec::new());
+    }
+
     /// Searches for the [`GridPartition`] that contains this `hash`, returning the partition's
     /// [`GridPartitionId`] if the hash is found in any partition.
     #[inline]
@@ -130,6 +210,107 @@ where
         self.partitions.iter()
     }
 
+    /// Iterates over all entities in the partition `id`, using the `map` this partition map was
+    /// built from. Returns an empty iterator if the partition does not exist.
+    pub fn entities<'a>(
+        &'a self,
+        id: &GridPartitionId,
+        map: &'a GridHashMap<P, F>,
+    ) -> impl Iterator<Item = Entity> + 'a {
+        self.resolve(id)
+            .into_iter()
+            .flat_map(|partition| partition.iter())
+            .filter_map(|hash| map.get(hash))
+            .flat_map(|entry| entry.entities.iter().copied())
+    }
+
+    /// The largest `gap` searched by [`Self::adjacent_partitions`].
+    pub const MAX_ADJACENT_GAP: u8 = 8;
+
+    /// Iterates over the [`GridPartitionId`]s of all partitions that are separated from the
+    /// partition `id` by at most `gap` empty cells. Partitions are disconnected by definition, so
+    /// this is the proximity graph of partitions, useful for clustering nearby regions.
+    ///
+    /// Only the cells on the boundary of the partition are searched, but finding them requires
+    /// checking the neighbors of every cell in the partition, so this is proportional to the size
+    /// of the partition.
+    ///
+    /// ### Warning
+    ///
+    /// This can become expensive very quickly! Every boundary cell checks a cube of cells with a
+    /// radius of `gap + 1`, which is `(2 * gap + 3)^3` lookups: 125 lookups per boundary cell with
+    /// a gap of 1, and 6,859 with a gap of 8. To keep this bounded, `gap` is clamped to
+    /// [`Self::MAX_ADJACENT_GAP`].
+    pub fn adjacent_partitions(
+        &self,
+        id: &GridPartitionId,
+        gap: u8,
+    ) -> impl Iterator<Item = GridPartitionId> {
+        let mut adjacent = HashSet::<GridPartitionId, PassHash>::default();
+        let search_radius = gap.min(Self::MAX_ADJACENT_GAP) + 1;
+        let partition = self.resolve(id).into_iter();
+        let boundary = partition
+            .flat_map(|partition| partition.iter())
+            .filter(|hash| {
+                hash.adjacent(1)
+                    .any(|neighbor| self.get(&neighbor) != Some(id))
+            });
+        for hash in boundary {
+            for neighbor in hash.adjacent(search_radius) {
+                match self.get(&neighbor) {
+                    Some(other) if other != id => adjacent.insert(*other),
+                    _ => continue,
+                };
+            }
+        }
+        adjacent.into_iter()
+    }
+
+    /// The axis-aligned bounding box of every occupied cell in every grid of the
+    /// [`BigSpace`](crate::BigSpace) with the `root` entity, relative to the origin of the root
+    /// grid. Returns `None` if no cells in the big space are occupied.
+    ///
+    /// This is computed from the [`GridPartition::cell_bounds`] of each partition, without visiting
+    /// any entities, which makes it cheap enough to use every frame for framing a camera or drawing
+    /// a map. The bounds of a partition in a rotated grid are conservatively enclosed by the box
+    /// around its transformed corners. Grids are transformed into the root grid with
+    /// [`GridToRoot::compute`](crate::grid::to_root::GridToRoot::compute), using the `positions`
+    /// of the grids.
+    pub fn big_space_bounds(
+        &self,
+        root: Entity,
+        grids: &crate::grid::local_origin::Grids<P>,
+        positions: &Query<(&crate::GridCell<P>, &Transform), With<crate::Grid<P>>>,
+    ) -> Option<(DVec3, DVec3)> {
+        let mut to_root = HashMap::<Entity, Option<DAffine3>>::default();
+        let mut grid_to_root = |grid: Entity| {
+            *to_root.entry(grid).or_insert_with(|| {
+                if grids.root_of(grid) != Some(root) {
+                    return None;
+                }
+                crate::grid::to_root::GridToRoot::compute(grid, grids, positions)
+                    .map(|to_root| to_root.0)
+            })
+        };
+
+        self.iter()
+            .filter_map(|(_, partition)| {
+                let affine = grid_to_root(partition.grid())?;
+                let grid = grids.get(partition.grid());
+                let (min_cell, max_cell) = partition.cell_bounds();
+                let (min, max) = (grid.cell_aabb(&min_cell).0, grid.cell_aabb(&max_cell).1);
+                (0..8)
+                    .map(|i| {
+                        let corner =
+                            DVec3::select(BVec3::new(i & 1 != 0, i & 2 != 0, i & 4 != 0), max, min);
+                        affine.transform_point3(corner)
+                    })
+                    .map(|corner| (corner, corner))
+                    .reduce(|(min, max), (corner, _)| (min.min(corner), max.max(corner)))
+            })
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+    }
+
     #[inline]
     fn insert(&mut self, partition: GridPartitionId, set: HashSet<GridHash<P>, PassHash>) {
         let Some(hash) = set.iter().next() else {
@@ -283,9 +464,37 @@ where
                 });
         }
 
-        // Finally, we need to test for partitions being split apart by a removal (removing a bridge
-        // in graph theory).
+        // Defer the split checks of partitions over the budget.
+        if let Some(budget) = partition_map.split_budget {
+            let map = &mut *partition_map;
+            if !hash_grid.just_removed().is_empty() {
+                map.deferred_splits.retain(|hash| hash_grid.contains(hash));
+            }
+            adjacent_to_removals.retain(|id, hashes| {
+                let within_budget = map
+                    .partitions
+                    .get(id)
+                    .is_some_and(|partition| partition.num_cells() <= budget);
+                if !within_budget {
+                    map.deferred_splits.extend(hashes.drain());
+                }
+                within_budget
+            });
+        }
+
         *split_candidates = adjacent_to_removals.drain().collect::<Vec<_>>();
+        partition_map.split(&hash_grid, &mut split_candidates, &mut split_results);
+        timing.update_partition += start.elapsed();
+    }
+
+    /// Test for partitions being split apart by a removal (removing a bridge in graph theory), and
+    /// split them.
+    fn split(
+        &mut self,
+        hash_grid: &GridHashMap<P, F>,
+        split_candidates: &mut Vec<(GridPartitionId, HashSet<GridHash<P>, PassHash>)>,
+        split_results: &mut Vec<Vec<SplitResult<P>>>,
+    ) {
         *split_results = split_candidates.par_splat_map_mut(
             ComputeTaskPool::get(),
             None,
@@ -336,7 +545,7 @@ where
             // sets are the ones that are assigned a new partition ID.
             new_partitions.sort_unstable_by_key(|v| v.len());
             if let Some(partition) = new_partitions.pop() {
-                if let Some(tables) = partition_map
+                if let Some(tables) = self
                     .partitions
                     .get_mut(original_partition)
                     .map(|p| &mut p.tables)
@@ -354,11 +563,10 @@ where
             // At this point the reverse map will be out of date. However, `partitions.insert()`
             // will update all hashes that now have a new partition, with their new ID.
             for partition_set in new_partitions.drain(..) {
-                let new_id = partition_map.take_next_id();
-                partition_map.insert(new_id, partition_set);
+                let new_id = self.take_next_id();
+                self.insert(new_id, partition_set);
             }
         }
-        timing.update_partition += start.elapsed();
     }
 }
 
@@ -441,8 +649,311 @@ impl<P: GridPrecision> GridPartition<P> {
         }
     }
 
+    /// Returns the minimum and maximum corners of the box of cells that contains this partition.
+    ///
+    /// This visits every cell in the partition. Partitions in a [`GridPartitionMap`] are never
+    /// empty, the bounds of an empty partition are [`GridCell::ZERO`](crate::GridCell::ZERO).
+    pub fn cell_bounds(&self) -> (crate::GridCell<P>, crate::GridCell<P>) {
+        self.iter()
+            .map(|hash| (hash.cell(), hash.cell()))
+            .reduce(|(min, max), (cell, _)| {
+                (
+                    crate::GridCell::new(min.x.min(cell.x), min.y.min(cell.y), min.z.min(cell.z)),
+                    crate::GridCell::new(max.x.max(cell.x), max.y.max(cell.y), max.z.max(cell.z)),
+                )
+            })
+            .unwrap_or_default()
+    }
+
     /// The grid this partition resides in.
     pub fn grid(&self) -> Entity {
         self.grid
     }
+
+    /// An identity of this partition derived from the set of cells it contains, independent of the
+    /// order they are stored in.
+    ///
+    /// Unlike the [`GridPartitionId`], which can be reassigned every frame, the fingerprint only
+    /// changes when a cell is added to or removed from the partition. Partitions with the same
+    /// fingerprint in different frames contain the same cells with high probability. It is a sum of
+    /// 64-bit hashes, so different sets of cells can collide, although this is rare. Because it is
+    /// computed from the cells' [`GridHash`]es, which include the grid entity, fingerprints are
+    /// only comparable within the same [`World`].
+    ///
+    /// This visits every cell in the partition.
+    pub fn fingerprint(&self) -> u64 {
+        self.iter()
+            .map(|hash| PassHash.hash_one(hash))
+            .fold(0, u64::wrapping_add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn adjacent_partitions() {
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+            GridPartitionPlugin::<i32>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                root.spawn_spatial(GridCell::<i32>::new(0, 0, 0));
+                root.spawn_spatial(GridCell::<i32>::new(-1, 0, 0));
+                root.spawn_spatial(GridCell::<i32>::new(3, 0, 0));
+                root.spawn_spatial(GridCell::<i32>::new(10, 1, 0));
+            });
+        });
+        app.update();
+
+        let world = app.world();
+        let hashes = world.resource::<GridHashMap<i32>>();
+        let partitions = world.resource::<GridPartitionMap<i32>>();
+        let id_of = |x: i32| {
+            let (hash, _) = hashes
+                .all_entries()
+                .find(|(hash, _)| hash.cell().x == x)
+                .unwrap();
+            *partitions.get(hash).unwrap()
+        };
+        let (a, b, c) = (id_of(0), id_of(3), id_of(10));
+        let adjacent = |id, gap| {
+            let mut ids: Vec<_> = partitions.adjacent_partitions(&id, gap).collect();
+            ids.sort_by_key(GridPartitionId::id);
+            ids
+        };
+        let sorted = |mut ids: Vec<GridPartitionId>| {
+            ids.sort_by_key(GridPartitionId::id);
+            ids
+        };
+
+        assert_eq!(adjacent(a, 1), vec![]);
+        assert_eq!(adjacent(a, 2), vec![b]);
+        assert_eq!(adjacent(a, 6), vec![b]);
+        assert_eq!(adjacent(b, 6), sorted(vec![a, c]));
+        assert_eq!(adjacent(c, 5), vec![]);
+        // `c` is 9 empty cells from `a`, further than the largest gap that is searched.
+        assert_eq!(
+            adjacent(a, GridPartitionMap::<i32>::MAX_ADJACENT_GAP + 1),
+            vec![b]
+        );
+        assert_eq!(adjacent(a, u8::MAX), vec![b]);
+    }
+
+    #[test]
+    fn entities() {
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+            GridPartitionPlugin::<i32>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                root.spawn_spatial(GridCell::<i32>::new(0, 0, 0));
+                root.spawn_spatial(GridCell::<i32>::new(0, 0, 0));
+                root.spawn_spatial(GridCell::<i32>::new(1, 0, 0));
+                root.spawn_spatial(GridCell::<i32>::new(10, 0, 0));
+            });
+        });
+        app.update();
+
+        let world = app.world();
+        let hashes = world.resource::<GridHashMap<i32>>();
+        let partitions = world.resource::<GridPartitionMap<i32>>();
+        let id_of = |x: i32| {
+            let (hash, _) = hashes
+                .all_entries()
+                .find(|(hash, _)| hash.cell().x == x)
+                .unwrap();
+            *partitions.get(hash).unwrap()
+        };
+        let (a, b) = (id_of(0), id_of(10));
+
+        // Entities are found in every cell of the partition, including cells with several entities.
+        assert_eq!(id_of(1), a);
+        assert_eq!(partitions.entities(&a, hashes).count(), 3);
+        assert_eq!(partitions.entities(&b, hashes).count(), 1);
+    }
+
+    #[test]
+    fn cell_bounds() {
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+            GridPartitionPlugin::<i32>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                root.spawn_spatial(GridCell::<i32>::new(0, 0, 0));
+                root.spawn_spatial(GridCell::<i32>::new(-1, 0, 0));
+                root.spawn_spatial(GridCell::<i32>::new(-1, 1, 1));
+                root.spawn_spatial(GridCell::<i32>::new(10, 0, 0));
+            });
+        });
+        app.update();
+
+        let partitions = app.world().resource::<GridPartitionMap<i32>>();
+        let mut bounds: Vec<_> = partitions
+            .iter()
+            .map(|(_, partition)| partition.cell_bounds())
+            .collect();
+        bounds.sort_by_key(|(min, _)| min.x);
+        assert_eq!(
+            bounds,
+            vec![
+                (GridCell::new(-1, 0, 0), GridCell::new(0, 1, 1)),
+                (GridCell::new(10, 0, 0), GridCell::new(10, 0, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_budget() {
+        #[derive(Resource)]
+        struct Bridges([Entity; 2]);
+
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+            GridPartitionPlugin::<i32>::default().with_split_budget(3),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                let mut bridges = [Entity::PLACEHOLDER; 2];
+                for (i, (len, y)) in [(5, 0), (3, 10)].into_iter().enumerate() {
+                    for x in 0..len {
+                        let entity = root.spawn_spatial(GridCell::<i32>::new(x, y, 0)).id();
+                        if x == len / 2 {
+                            bridges[i] = entity;
+                        }
+                    }
+                }
+                root.commands().insert_resource(Bridges(bridges));
+            });
+        });
+        app.update();
+        assert_eq!(app.world().resource::<GridPartitionMap<i32>>().len(), 2);
+
+        let bridges = app.world().resource::<Bridges>().0;
+        for bridge in bridges {
+            app.world_mut().entity_mut(bridge).despawn_recursive();
+        }
+        app.update();
+
+        // The small partition was split, the large one is deferred.
+        let partitions = app.world().resource::<GridPartitionMap<i32>>();
+        assert_eq!(partitions.len(), 3);
+        assert!(partitions.has_deferred_splits());
+
+        app.world_mut()
+            .resource_scope(|world, mut partitions: Mut<GridPartitionMap<i32>>| {
+                partitions.check_deferred_splits(world.resource::<GridHashMap<i32>>());
+            });
+        let partitions = app.world().resource::<GridPartitionMap<i32>>();
+        assert_eq!(partitions.len(), 4);
+        assert!(!partitions.has_deferred_splits());
+    }
+
+    #[test]
+    fn fingerprint() {
+        #[derive(Resource)]
+        struct Moving(Entity);
+
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+            GridPartitionPlugin::<i32>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                root.spawn_spatial(GridCell::<i32>::new(0, 0, 0));
+                root.spawn_spatial(GridCell::<i32>::new(1, 0, 0));
+                let moving = root.spawn_spatial(GridCell::<i32>::new(10, 0, 0)).id();
+                root.commands().insert_resource(Moving(moving));
+            });
+        });
+        app.update();
+
+        let fingerprint_at = |app: &App, x: i32| {
+            let world = app.world();
+            let (hash, _) = world
+                .resource::<GridHashMap<i32>>()
+                .all_entries()
+                .find(|(hash, _)| hash.cell().x == x)
+                .unwrap();
+            let partitions = world.resource::<GridPartitionMap<i32>>();
+            partitions
+                .resolve(partitions.get(hash).unwrap())
+                .unwrap()
+                .fingerprint()
+        };
+        let before = fingerprint_at(&app, 0);
+        assert_ne!(before, fingerprint_at(&app, 10));
+
+        // Changes to other partitions do not change the fingerprint.
+        let moving = app.world().resource::<Moving>().0;
+        app.world_mut().get_mut::<GridCell<i32>>(moving).unwrap().x = 20;
+        app.update();
+        assert_eq!(fingerprint_at(&app, 0), before);
+
+        // Merging the moving cell into the partition does.
+        app.world_mut().get_mut::<GridCell<i32>>(moving).unwrap().x = 2;
+        app.update();
+        assert_ne!(fingerprint_at(&app, 0), before);
+        assert_eq!(fingerprint_at(&app, 0), fingerprint_at(&app, 2));
+    }
+
+    #[test]
+    fn big_space_bounds() {
+        use bevy::math::DVec3;
+
+        #[derive(Resource)]
+        struct Root(Entity);
+
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+            GridPartitionPlugin::<i32>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            let root = commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 0.0), |root| {
+                root.spawn_spatial(GridCell::<i32>::new(0, 0, 0));
+                root.spawn_spatial(GridCell::<i32>::new(2, 0, 0));
+                let mut child =
+                    root.spawn_grid(Grid::new_uniform(1.0, 0.0), GridCell::<i32>::new(10, 0, 0));
+                child.spawn_spatial(GridCell::<i32>::new(3, 0, 0));
+                child.spawn_spatial(GridCell::<i32>::new(0, -8, 0));
+            });
+            commands.insert_resource(Root(root));
+            // Cells in other big spaces are ignored
+            commands.spawn_big_space_default::<i32>(|other| {
+                other.spawn_spatial(GridCell::<i32>::new(1000, 0, 0));
+            });
+        });
+        app.update();
+
+        let root = app.world().resource::<Root>().0;
+        let mut system_state = bevy::ecs::system::SystemState::<(
+            Res<GridPartitionMap<i32>>,
+            Grids<i32>,
+            Query<(&GridCell<i32>, &Transform), With<Grid<i32>>>,
+        )>::new(app.world_mut());
+        let (partitions, grids, positions) = system_state.get(app.world());
+        let (min, max) = partitions
+            .big_space_bounds(root, &grids, &positions)
+            .unwrap();
+        assert_eq!(min, DVec3::new(-5.0, -8.5, -5.0));
+        // The child grid itself occupies the cell from x = 95 to 105, containing its entity at 103
+        assert_eq!(max, DVec3::new(105.0, 5.0, 5.0));
+    }
 }
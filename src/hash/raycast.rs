@@ -0,0 +1,263 @@
+//! Ray casting against entities in a [`GridHashMap`].
+
+use crate::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::{prelude::*, DAffine3, DVec3};
+use bevy_utils::{hashbrown::HashSet, PassHash};
+
+use super::{map::GridHashEntry, GridHashMapFilter};
+
+/// The result of a successful [`GridHashMap::raycast`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// The first entity hit by the ray.
+    pub entity: Entity,
+    /// The distance along the ray to the hit.
+    pub distance: f64,
+}
+
+impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
+    /// Find the first entity in the `grid` hit by a `ray` in the floating origin's frame, within
+    /// `max_distance` along the ray.
+    ///
+    /// This marches through the cells intersected by the ray with [`Grid::ray_cells`], and only
+    /// tests entities in and around those cells, stopping as soon as no untested entity could be
+    /// closer than the nearest hit. Narrow phase tests are done in double precision, relative to
+    /// the floating origin's cell.
+    ///
+    /// `entity_bounds` is used to get the position of each candidate entity, and the half extents
+    /// of its bounding box in the entity's local space. The box is oriented and scaled with the
+    /// entity's [`Transform`](bevy_transform::prelude::Transform). Return `None` to skip an entity.
+    /// This is usually a lookup into a query of your own bounding volume component, or a constant
+    /// size.
+    ///
+    /// Entities are only found if their bounds do not extend more than a cell outside of their own
+    /// cell.
+    pub fn raycast(
+        &self,
+        grid_entity: Entity,
+        grid: &Grid<P>,
+        ray: Ray3d,
+        max_distance: f32,
+        mut entity_bounds: impl FnMut(Entity) -> Option<(GridTransformOwned<P>, Vec3)>,
+    ) -> Option<RayHit> {
+        let (origin, direction) = grid.ray_to_local(ray);
+        let max_distance = max_distance as f64;
+        let mut tested = HashSet::<GridHash<P>, PassHash>::default();
+        let mut nearest: Option<RayHit> = None;
+
+        for ray_cell in grid.ray_cells_local(origin, direction, max_distance) {
+            if nearest.is_some_and(|hit| hit.distance <= ray_cell.enter) {
+                break;
+            }
+            let center = GridHash::from_parent(grid_entity, &ray_cell.cell);
+            for hash in std::iter::once(center).chain(center.adjacent(1)) {
+                if !tested.insert(hash) {
+                    continue;
+                }
+                let Some(entry) = self.get(&hash) else {
+                    continue;
+                };
+                for &entity in entry.entities.iter() {
+                    let Some((position, half_extents)) = entity_bounds(entity) else {
+                        continue;
+                    };
+                    let Some(distance) =
+                        ray_box_distance(grid, &position, half_extents, origin, direction)
+                    else {
+                        continue;
+                    };
+                    if distance <= max_distance && nearest.is_none_or(|hit| distance < hit.distance)
+                    {
+                        nearest = Some(RayHit { entity, distance });
+                    }
+                }
+            }
+        }
+
+        nearest
+    }
+
+    /// Iterate over the occupied cells in the `grid` touched by a capsule, the segment from `a` to
+    /// `b` expanded by `radius`. Each entry is only returned once.
+    ///
+    /// `a` and `b` are double precision positions in the grid, as computed by
+    /// [`Grid::grid_position_double`]. This is useful for continuous collision detection of fast
+    /// moving entities, like projectiles that cross many cells in a single frame.
+    ///
+    /// The radius is rounded up to a whole number of cells along each axis, so the cells returned
+    /// are a conservative superset of the cells the capsule overlaps.
+    pub fn capsule_cells<'a>(
+        &'a self,
+        grid_entity: Entity,
+        grid: &Grid<P>,
+        a: DVec3,
+        b: DVec3,
+        radius: f32,
+    ) -> impl Iterator<Item = &'a GridHashEntry<P>> + 'a {
+        let origin = a - grid.cell_to_float(&grid.local_floating_origin().cell());
+        let length = a.distance(b);
+        let direction = (b - a).normalize_or_zero();
+        let expand = (radius.max(0.0) / grid.cell_edge_length())
+            .ceil()
+            .as_ivec3();
+
+        let mut visited = HashSet::<GridHash<P>, PassHash>::default();
+        let mut occupied = Vec::new();
+        for ray_cell in grid.ray_cells_local(origin, direction, length) {
+            for x in -expand.x..=expand.x {
+                for y in -expand.y..=expand.y {
+                    for z in -expand.z..=expand.z {
+                        let offset = GridCell::new(
+                            P::from_f64(x as f64),
+                            P::from_f64(y as f64),
+                            P::from_f64(z as f64),
+                        );
+                        let hash = GridHash::from_parent(grid_entity, &(ray_cell.cell + offset));
+                        if visited.insert(hash) {
+                            occupied.extend(self.get(&hash));
+                        }
+                    }
+                }
+            }
+        }
+        occupied.into_iter()
+    }
+}
+
+/// Compute the distance along a ray to an entity's bounding box. The ray `origin` and `direction`
+/// are relative to the floating origin's cell in the `grid`.
+pub(crate) fn ray_box_distance<P: GridPrecision>(
+    grid: &Grid<P>,
+    position: &GridTransformOwned<P>,
+    half_extents: Vec3,
+    origin: DVec3,
+    direction: DVec3,
+) -> Option<f64> {
+    let cell_offset = grid.cell_to_float(&(position.cell - grid.local_floating_origin().cell()));
+    let to_entity = DAffine3::from_scale_rotation_translation(
+        position.transform.scale.as_dvec3(),
+        position.transform.rotation.as_dquat(),
+        position.transform.translation.as_dvec3() + cell_offset,
+    )
+    .inverse();
+
+    // Transforming the ray into the entity's local space preserves the ray parameter, so the
+    // distance does not need to be converted back.
+    let origin = to_entity.transform_point3(origin);
+    let direction = to_entity.transform_vector3(direction);
+    let half_extents = half_extents.as_dvec3();
+
+    let t_1 = (-half_extents - origin) / direction;
+    let t_2 = (half_extents - origin) / direction;
+    let near = t_1.min(t_2).max_element();
+    let far = t_1.max(t_2).min_element();
+
+    (near <= far && far >= 0.0).then_some(near.max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use bevy::{math::DVec3, prelude::*, utils::HashMap};
+
+    #[test]
+    fn raycast_nearest_entity() {
+        #[derive(Resource)]
+        struct Entities {
+            near: Entity,
+            far: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 0.0), |root| {
+                root.spawn_spatial((FloatingOrigin, GridCell::new(0, 0, -3)));
+                let far = root.spawn_spatial(GridCell::new(5, 0, 0)).id();
+                let near = root
+                    .spawn_spatial((GridCell::new(2, 0, 0), Transform::from_xyz(3.0, 0.0, 0.0)))
+                    .id();
+                root.spawn_spatial(GridCell::new(2, 3, 0));
+                root.commands().insert_resource(Entities { near, far });
+            });
+        });
+
+        app.update();
+
+        let positions: HashMap<Entity, GridTransformOwned<i32>> = app
+            .world_mut()
+            .query::<(Entity, GridTransformReadOnly<i32>)>()
+            .iter(app.world())
+            .map(|(entity, position)| (entity, position.to_owned()))
+            .collect();
+        let world = app.world();
+        let entities = world.resource::<Entities>();
+        let grid_entity = world.get::<Parent>(entities.far).unwrap().get();
+        let grid = world.get::<Grid<i32>>(grid_entity).unwrap();
+        let map = world.resource::<GridHashMap<i32>>();
+        let bounds = |entity| Some((*positions.get(&entity)?, Vec3::splat(1.0)));
+
+        let hit = map
+            .raycast(
+                grid_entity,
+                grid,
+                Ray3d::new(Vec3::Z * 30.0, Dir3::X),
+                1e3,
+                bounds,
+            )
+            .unwrap();
+        assert_eq!(hit.entity, entities.near);
+        assert!((hit.distance - 22.0).abs() < 1e-4);
+
+        let miss = map.raycast(
+            grid_entity,
+            grid,
+            Ray3d::new(Vec3::Z * 30.0, Dir3::Z),
+            1e3,
+            bounds,
+        );
+        assert!(miss.is_none());
+    }
+
+    #[test]
+    fn capsule_cells() {
+        #[derive(Resource)]
+        struct Root(Entity);
+
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            GridHashPlugin::<i32>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 0.0), |root| {
+                root.spawn_spatial((FloatingOrigin, GridCell::new(0, 0, -3)));
+                root.spawn_spatial(GridCell::new(2, 0, 0));
+                root.spawn_spatial(GridCell::new(2, 0, 0));
+                root.spawn_spatial(GridCell::new(5, 0, 0));
+                root.spawn_spatial(GridCell::new(2, 3, 0));
+                let id = root.id();
+                root.commands().insert_resource(Root(id));
+            });
+        });
+
+        app.update();
+
+        let world = app.world();
+        let grid_entity = world.resource::<Root>().0;
+        let grid = world.get::<Grid<i32>>(grid_entity).unwrap();
+        let map = world.resource::<GridHashMap<i32>>();
+        let (a, b) = (DVec3::ZERO, DVec3::X * 60.0);
+
+        let segment: Vec<_> = map.capsule_cells(grid_entity, grid, a, b, 0.0).collect();
+        assert_eq!(segment.len(), 2);
+        assert_eq!(segment.iter().map(|e| e.entities.len()).sum::<usize>(), 3);
+
+        assert_eq!(map.capsule_cells(grid_entity, grid, a, b, 25.0).count(), 4);
+        assert_eq!(map.capsule_cells(grid_entity, grid, a, a, 0.0).count(), 0);
+    }
+}
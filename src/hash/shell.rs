@@ -0,0 +1,144 @@
+//! Queries for entities in a spherical shell around a point in a [`GridHashMap`].
+
+use crate::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::DVec3;
+use bevy_transform::prelude::*;
+
+use super::GridHashMapFilter;
+
+impl<P: GridPrecision, F: GridHashMapFilter> GridHashMap<P, F> {
+    /// Find the entities in the `grid` whose distance from `center` is greater than
+    /// `inner_radius`, and no greater than `outer_radius`, along with that distance. Useful for
+    /// effects with a falloff, like the damage rings of an explosion. The `center` is relative to
+    /// the origin of the grid, as computed by [`Grid::grid_position_double`].
+    ///
+    /// Cells within the bounding cube of the outer sphere are skipped if they are entirely inside
+    /// the inner sphere or entirely outside the outer sphere. The entities in the remaining cells
+    /// are tested individually with their `positions`, and entities without a position are
+    /// skipped. Distances are computed relative to the cell of `center`, so they are precise even
+    /// far from the origin of the grid. The results are in arbitrary order.
+    ///
+    /// ### Warning
+    ///
+    /// Like [`Self::within_cube`], the number of cells tested grows with the cube of the outer
+    /// radius in cells, so a large radius compared to the cell size can be expensive.
+    pub fn within_shell(
+        &self,
+        grid_entity: Entity,
+        grid: &Grid<P>,
+        center: DVec3,
+        inner_radius: f64,
+        outer_radius: f64,
+        positions: &Query<(&GridCell<P>, &Transform)>,
+    ) -> impl Iterator<Item = (Entity, f64)> {
+        let (center_cell, center_translation) = grid.translation_to_grid(center);
+        let center_translation = center_translation.as_dvec3();
+        let extent = (outer_radius / grid.cell_edge_length().as_dvec3())
+            .ceil()
+            .as_i64vec3()
+            + 1;
+        // Entities can be up to the switching threshold past the edge of their cell.
+        let cell_radius = grid.maximum_distance_from_origin().as_dvec3().length();
+
+        let mut found = Vec::new();
+        for x in -extent.x..=extent.x {
+            for y in -extent.y..=extent.y {
+                for z in -extent.z..=extent.z {
+                    let offset = GridCell::new(
+                        P::from_f64(x as f64),
+                        P::from_f64(y as f64),
+                        P::from_f64(z as f64),
+                    );
+                    let distance = (grid.cell_to_float(&offset) - center_translation).length();
+                    if distance + cell_radius <= inner_radius
+                        || distance - cell_radius > outer_radius
+                    {
+                        continue;
+                    }
+                    let hash = GridHash::from_parent(grid_entity, &(center_cell + offset));
+                    let Some(entry) = self.get(&hash) else {
+                        continue;
+                    };
+                    found.extend(entry.entities.iter().filter_map(|&entity| {
+                        let (cell, transform) = positions.get(entity).ok()?;
+                        let position = grid.cell_to_float(&(*cell - center_cell))
+                            + transform.translation.as_dvec3();
+                        let distance = (position - center_translation).length();
+                        (distance > inner_radius && distance <= outer_radius)
+                            .then_some((entity, distance))
+                    }));
+                }
+            }
+        }
+        found.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{ecs::system::SystemState, prelude::*};
+
+    #[test]
+    fn within_shell() {
+        #[derive(Resource)]
+        struct Entities {
+            inside: Entity,
+            in_band: Entity,
+            near_edge: Entity,
+            outside: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i64>::default(),
+            GridHashPlugin::<i64>::default(),
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space::<i64>(Grid::new_uniform(10.0, 0.0), |root| {
+                root.spawn_spatial(FloatingOrigin);
+                let far = 1_000_000_000_000;
+                let inside = root
+                    .spawn_spatial((
+                        GridCell::<i64>::new(far, 0, 0),
+                        Transform::from_xyz(3.0, 0.0, 0.0),
+                    ))
+                    .id();
+                let in_band = root.spawn_spatial(GridCell::<i64>::new(far + 2, 0, 0)).id();
+                let near_edge = root
+                    .spawn_spatial((
+                        GridCell::<i64>::new(far, 3, 0),
+                        Transform::from_xyz(0.0, -0.5, 0.0),
+                    ))
+                    .id();
+                let outside = root.spawn_spatial(GridCell::<i64>::new(far, 0, 5)).id();
+                root.commands().insert_resource(Entities {
+                    inside,
+                    in_band,
+                    near_edge,
+                    outside,
+                });
+            });
+        });
+        app.update();
+
+        let mut state = SystemState::<Query<(&GridCell<i64>, &Transform)>>::new(app.world_mut());
+        let positions = state.get(app.world());
+        let world = app.world();
+        let entities = world.resource::<Entities>();
+        let grid_entity = world.get::<Parent>(entities.inside).unwrap().get();
+        let grid = world.get::<Grid<i64>>(grid_entity).unwrap();
+        let map = world.resource::<GridHashMap<i64>>();
+
+        let center = DVec3::new(1e13, 0.0, 0.0);
+        let found: Vec<_> = map
+            .within_shell(grid_entity, grid, center, 5.0, 30.0, &positions)
+            .collect();
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&(entities.in_band, 20.0)));
+        assert!(found.contains(&(entities.near_edge, 29.5)));
+        assert!(!found.iter().any(|(e, _)| *e == entities.inside));
+        assert!(!found.iter().any(|(e, _)| *e == entities.outside));
+    }
+}
@@ -17,6 +17,8 @@
 //! - [`GridCell`] : Cell index of an entity within its parent's grid.
 //! - [`GridPrecision`] : Integer precision of a grid.
 //!
+//! `ReferenceFrame` has been renamed [`Grid`], see the [`reference_frame`] module for help upgrading.
+//!
 //! #### Spatial Hashing
 //!
 //! - [`GridHash`] : The spatial hash of an entity's grid cell.
@@ -208,39 +210,69 @@ pub mod commands;
 pub mod floating_origins;
 pub mod grid;
 pub mod hash;
+pub mod lod;
+pub mod mirror;
+pub mod physics;
 pub mod plugin;
 pub mod precision;
+#[deprecated = "the `reference_frame` module has been renamed `grid`"]
+pub mod reference_frame;
+pub mod replication;
+pub mod scene;
 pub mod timing;
+pub mod track;
 pub mod validation;
 pub mod world_query;
 
+#[cfg(feature = "bevy_compat")]
+pub mod bevy_compat;
 #[cfg(feature = "camera")]
 pub mod camera;
 #[cfg(feature = "debug")]
 pub mod debug;
+#[cfg(feature = "geo")]
+pub mod geo;
 #[cfg(test)]
 mod tests;
+#[cfg(feature = "velocity")]
+pub mod velocity;
 
 /// Common big_space imports.
 pub mod prelude {
     use crate::*;
+    #[cfg(feature = "bevy_compat")]
+    pub use bevy_compat::SceneCommands;
     pub use bundles::{BigGridBundle, BigSpaceRootBundle, BigSpatialBundle};
-    pub use commands::{BigSpaceCommands, GridCommands, SpatialEntityCommands};
+    pub use commands::{BigSpaceCommands, BigSpaceTransfer, GridCommands, SpatialEntityCommands};
     #[cfg(feature = "debug")]
-    pub use debug::FloatingOriginDebugPlugin;
-    pub use floating_origins::{BigSpace, FloatingOrigin};
+    pub use debug::{
+        BigSpaceGizmos, FloatingOriginDebugPlugin, FloatingOriginTrail, FloatingOriginTrailPlugin,
+        GridPartitionDebugPlugin,
+    };
+    pub use floating_origins::{BigSpace, BigSpaces, FloatingOrigin, FloatingOriginCandidate};
     pub use grid::{
+        bounds::{GridBounds, GridBoundsMode, OutOfGridBounds},
         cell::{GridCell, GridCellAny},
-        local_origin::{Grids, GridsMut, LocalFloatingOrigin},
+        local_origin::{GridDepthExceeded, Grids, GridsMut, LocalFloatingOrigin, MaxGridDepth},
+        to_root::{GridToRoot, GridToRootPlugin},
+        wrap::GridWrap,
         Grid,
     };
     pub use hash::{
-        component::{FastGridHash, GridHash},
+        component::{FastGridHash, GridHash, NoSpatialHash, StaticSpatial},
         map::{GridHashMap, SpatialEntryToEntities},
+        nearby::Nearby,
         partition::{GridPartition, GridPartitionId, GridPartitionMap, GridPartitionPlugin},
         GridHashMapSystem, GridHashPlugin,
     };
+    pub use lod::CellLodDistance;
+    pub use mirror::MirrorPosition;
     pub use plugin::{BigSpacePlugin, FloatingOriginSystem};
     pub use precision::GridPrecision;
-    pub use world_query::{GridTransform, GridTransformOwned, GridTransformReadOnly};
+    pub use track::TrackFloatingOrigin;
+    #[cfg(feature = "velocity")]
+    pub use velocity::{GridVelocity, GridVelocityPlugin};
+    pub use world_query::{
+        GridTransform, GridTransformOwned, GridTransformReadOnly, GridTransformRef,
+    };
 }
@@ -0,0 +1,112 @@
+//! Level of detail selection based on the distance of an entity's [`GridCell`] from the
+//! [`FloatingOrigin`].
+//!
+//! Far from the floating origin, the length of an entity's `GlobalTransform` translation is not
+//! precise enough to select a level of detail reliably. The [`CellLodDistance`] is computed in
+//! whole cells using the grid's integer coordinates, so it stays stable at any distance.
+
+use crate::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::prelude::*;
+use bevy_reflect::prelude::*;
+
+/// Add this component to a high precision entity to have the distance from its [`GridCell`] to the
+/// [`FloatingOrigin`]'s cell updated every frame, measured in cells of the entity's [`Grid`].
+///
+/// This is updated in [`FloatingOriginSystem::PropagateHighPrecision`], after the position of the
+/// floating origin in each grid has been computed.
+#[derive(Component, Default, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct CellLodDistance {
+    /// The largest distance along any axis between the entity's cell and the floating origin's
+    /// cell.
+    pub chebyshev: u128,
+    /// The straight line distance between the entity's cell and the floating origin's cell.
+    pub euclidean: f64,
+}
+
+impl CellLodDistance {
+    /// Compute the distance between two cells.
+    pub fn new<P: GridPrecision>(a: &GridCell<P>, b: &GridCell<P>) -> Self {
+        let delta = [a.x.as_i128(), a.y.as_i128(), a.z.as_i128()]
+            .into_iter()
+            .zip([b.x.as_i128(), b.y.as_i128(), b.z.as_i128()])
+            .map(|(a, b)| a.abs_diff(b));
+        let (chebyshev, squared) = delta.fold((0, 0.0), |(max, squared), d| {
+            (u128::max(max, d), squared + (d as f64).powi(2))
+        });
+        Self {
+            chebyshev,
+            euclidean: squared.sqrt(),
+        }
+    }
+
+    /// Update the [`CellLodDistance`] of all entities.
+    pub fn update<P: GridPrecision>(
+        grids: Query<&Grid<P>>,
+        mut entities: Query<(&mut CellLodDistance, &GridCell<P>, &Parent)>,
+    ) {
+        entities
+            .par_iter_mut()
+            .for_each(|(mut distance, cell, parent)| {
+                let Ok(grid) = grids.get(parent.get()) else {
+                    return;
+                };
+                let origin = grid.local_floating_origin().cell();
+                distance.set_if_neq(Self::new(cell, &origin));
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn lod_distance() {
+        #[derive(Resource)]
+        struct Entities {
+            origin: Entity,
+            near: Entity,
+            far: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i64>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space_default::<i64>(|root| {
+                    let origin = root
+                        .spawn_spatial((FloatingOrigin, GridCell::<i64>::new(10, 0, 0)))
+                        .id();
+                    let near = root
+                        .spawn_spatial((CellLodDistance::default(), GridCell::<i64>::new(13, 4, 0)))
+                        .id();
+                    let far = root
+                        .spawn_spatial((
+                            CellLodDistance::default(),
+                            GridCell::<i64>::new(i64::MIN, 0, 0),
+                        ))
+                        .id();
+                    root.commands()
+                        .insert_resource(Entities { origin, near, far });
+                });
+            });
+        app.update();
+
+        let entities = app.world().resource::<Entities>();
+        let (origin, near, far) = (entities.origin, entities.near, entities.far);
+        let near_distance = *app.world().get::<CellLodDistance>(near).unwrap();
+        assert_eq!(near_distance.chebyshev, 4);
+        assert_eq!(near_distance.euclidean, 5.0);
+        let far_distance = *app.world().get::<CellLodDistance>(far).unwrap();
+        assert_eq!(far_distance.chebyshev, i64::MAX as u128 + 11);
+
+        // The distance follows the floating origin.
+        app.world_mut().get_mut::<GridCell<i64>>(origin).unwrap().y = 4;
+        app.update();
+        let near_distance = *app.world().get::<CellLodDistance>(near).unwrap();
+        assert_eq!(near_distance.chebyshev, 3);
+        assert_eq!(near_distance.euclidean, 3.0);
+    }
+}
@@ -0,0 +1,114 @@
+//! Mirror the position of a high precision entity into another [`BigSpace`].
+//!
+//! Each [`BigSpace`] computes the [`GlobalTransform`]s of its entities relative to its own
+//! [`FloatingOrigin`], and an entity can only be in a single hierarchy, so it only has a single
+//! `GlobalTransform`. When the same object needs to be rendered from multiple big spaces, for
+//! example a star shared between split screen views that each have their own big space, spawn a
+//! proxy entity in each of the other big spaces with a [`MirrorPosition`] pointing at the original.
+
+use crate::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::prelude::*;
+use bevy_reflect::prelude::*;
+use bevy_transform::prelude::*;
+
+/// Add this to a high precision entity to copy the position of the `source` entity every frame.
+///
+/// The position of the source relative to the origin of its [`Grid`] is copied to the same
+/// position relative to the origin of this entity's [`Grid`], including its rotation and scale. The
+/// grids do not need to have the same cell size, and are usually the root grids of two different
+/// [`BigSpace`]s.
+///
+/// The source cannot itself be a mirror. This is updated in
+/// [`FloatingOriginSystem::RecenterLargeTransforms`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component, PartialEq)]
+pub struct MirrorPosition(pub Entity);
+
+impl MirrorPosition {
+    /// Update the position of all mirror entities to match their source.
+    pub fn update<P: GridPrecision>(
+        grids: Query<&Grid<P>>,
+        sources: Query<(&GridCell<P>, &Transform, &Parent), Without<MirrorPosition>>,
+        mut mirrors: Query<(&MirrorPosition, &Parent, &mut GridCell<P>, &mut Transform)>,
+    ) {
+        for (mirror, parent, mut cell, mut transform) in &mut mirrors {
+            let Ok((source_cell, source_transform, source_parent)) = sources.get(mirror.0) else {
+                continue;
+            };
+            let (Ok(source_grid), Ok(grid)) =
+                (grids.get(source_parent.get()), grids.get(parent.get()))
+            else {
+                continue;
+            };
+            let position = source_grid.grid_position_double(source_cell, source_transform);
+            let (new_cell, translation) = grid.translation_to_grid(position);
+            cell.set_if_neq(new_cell);
+            transform.set_if_neq(Transform {
+                translation,
+                ..*source_transform
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn mirror_into_other_big_space() {
+        #[derive(Resource)]
+        struct Entities {
+            star: Entity,
+            mirror: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i64>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                let mut star = Entity::PLACEHOLDER;
+                commands.spawn_big_space::<i64>(Grid::new_uniform(10.0, 0.0), |root| {
+                    root.spawn_spatial(FloatingOrigin);
+                    star = root
+                        .spawn_spatial((
+                            GridCell::<i64>::new(1_000_000_000_000, 0, 0),
+                            Transform::from_xyz(1.0, 2.0, 3.0),
+                        ))
+                        .id();
+                });
+                let mut mirror = Entity::PLACEHOLDER;
+                commands.spawn_big_space::<i64>(Grid::new_uniform(100.0, 0.0), |root| {
+                    root.spawn_spatial((
+                        FloatingOrigin,
+                        GridCell::<i64>::new(100_000_000_000, 0, 0),
+                    ));
+                    mirror = root.spawn_spatial(MirrorPosition(star)).id();
+                });
+                commands.insert_resource(Entities { star, mirror });
+            });
+        app.update();
+
+        let entities = app.world().resource::<Entities>();
+        let (star, mirror) = (entities.star, entities.mirror);
+        let world = app.world();
+        assert_eq!(
+            world.get::<GridCell<i64>>(mirror),
+            Some(&GridCell::new(100_000_000_000, 0, 0))
+        );
+        assert_eq!(
+            world.get::<Transform>(mirror).unwrap().translation,
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+        // Each copy is rendered relative to the floating origin of its own big space.
+        assert_eq!(
+            world.get::<GlobalTransform>(mirror).unwrap().translation(),
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+        assert_ne!(
+            world.get::<GlobalTransform>(star).unwrap().translation(),
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+    }
+}
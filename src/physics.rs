@@ -0,0 +1,153 @@
+//! Helpers for syncing the bodies of a physics engine with high precision entities.
+//!
+//! Physics engines usually simulate bodies with a single double precision transform, while
+//! `big_space` stores positions as a [`GridCell`] and a [`Transform`]. Writing an engine's result
+//! into the [`Transform`] alone fights the recentering of cells, and loses precision far from the
+//! origin. Instead, [`sync_from`] converts the engine's double precision transforms into cells and
+//! transforms directly, and [`sync_to`] reads the double precision transforms back out for the
+//! engine.
+//!
+//! These helpers do not depend on any engine. Call them from your own systems, passing a closure
+//! that reads or writes the engine's bodies:
+//!
+//! ```
+//! # use bevy::{math::DAffine3, prelude::*, utils::HashMap};
+//! # use big_space::{physics, prelude::*};
+//! #[derive(Resource)]
+//! struct Engine {
+//!     bodies: HashMap<Entity, DAffine3>,
+//! }
+//!
+//! fn engine_to_big_space(
+//!     engine: Res<Engine>,
+//!     grids: Query<&Grid<i64>>,
+//!     mut bodies: Query<(Entity, &Parent, GridTransform<i64>)>,
+//! ) {
+//!     physics::sync_from(&grids, &mut bodies, |entity| engine.bodies.get(&entity).copied());
+//! }
+//!
+//! fn big_space_to_engine(
+//!     mut engine: ResMut<Engine>,
+//!     grids: Query<&Grid<i64>>,
+//!     bodies: Query<(Entity, &Parent, GridTransformReadOnly<i64>)>,
+//! ) {
+//!     physics::sync_to(&grids, &bodies, |entity, affine| {
+//!         engine.bodies.insert(entity, affine);
+//!     });
+//! }
+//! ```
+//!
+//! Positions are relative to the origin of each body's [`Grid`]. Run [`sync_from`] before
+//! [`FloatingOriginSystem::RecenterLargeTransforms`], so the [`GlobalTransform`]s are updated in
+//! the same frame.
+//!
+//! [`GlobalTransform`]: bevy_transform::prelude::GlobalTransform
+//! [`Transform`]: bevy_transform::prelude::Transform
+
+use crate::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::prelude::*;
+use bevy_math::DAffine3;
+
+/// Set the [`GridCell`] and [`Transform`] of each body from the double precision transform in its
+/// grid returned by `body_transform`. Bodies where `body_transform` returns `None` are skipped.
+///
+/// Components are only changed if their values differ. See the [module docs](self).
+///
+/// [`Transform`]: bevy_transform::prelude::Transform
+pub fn sync_from<P: GridPrecision>(
+    grids: &Query<&Grid<P>>,
+    bodies: &mut Query<(Entity, &Parent, GridTransform<P>)>,
+    mut body_transform: impl FnMut(Entity) -> Option<DAffine3>,
+) {
+    for (entity, parent, mut grid_transform) in bodies.iter_mut() {
+        let Ok(grid) = grids.get(parent.get()) else {
+            continue;
+        };
+        if let Some(affine) = body_transform(entity) {
+            grid_transform.set_affine_double(grid, affine);
+        }
+    }
+}
+
+/// Call `set_body_transform` with the double precision transform of each body in its grid. See
+/// the [module docs](self).
+pub fn sync_to<P: GridPrecision>(
+    grids: &Query<&Grid<P>>,
+    bodies: &Query<(Entity, &Parent, GridTransformReadOnly<P>)>,
+    mut set_body_transform: impl FnMut(Entity, DAffine3),
+) {
+    for (entity, parent, grid_transform) in bodies.iter() {
+        let Ok(grid) = grids.get(parent.get()) else {
+            continue;
+        };
+        set_body_transform(entity, grid_transform.affine_double(grid));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        math::{DQuat, DVec3},
+        prelude::*,
+        utils::HashMap,
+    };
+
+    #[test]
+    fn sync_round_trip() {
+        #[derive(Resource, Default)]
+        struct Engine(HashMap<Entity, DAffine3>);
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i64>::default())
+            .init_resource::<Engine>()
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space::<i64>(Grid::new_uniform(100.0, 0.0), |root| {
+                    root.spawn_spatial(FloatingOrigin);
+                    let body = root.spawn_spatial(GridCell::<i64>::ZERO).id();
+                    let far = DAffine3::from_rotation_translation(
+                        DQuat::from_rotation_z(0.5),
+                        DVec3::new(1e15 + 12.5, -3.25, 0.0),
+                    );
+                    root.commands()
+                        .insert_resource(Engine([(body, far)].into_iter().collect()));
+                });
+            });
+        app.update();
+
+        let sync_from_engine =
+            |engine: Res<Engine>,
+             grids: Query<&Grid<i64>>,
+             mut bodies: Query<(Entity, &Parent, GridTransform<i64>)>| {
+                sync_from(&grids, &mut bodies, |entity| engine.0.get(&entity).copied());
+            };
+        app.world_mut().run_system_once(sync_from_engine).unwrap();
+
+        let (&body, &expected) = app.world().resource::<Engine>().0.iter().next().unwrap();
+        let cell = *app.world().get::<GridCell<i64>>(body).unwrap();
+        let transform = *app.world().get::<Transform>(body).unwrap();
+        assert_eq!(cell, GridCell::new(10_000_000_000_000, 0, 0));
+        assert!(transform
+            .translation
+            .abs_diff_eq(Vec3::new(12.5, -3.25, 0.0), 1e-4));
+
+        let sync_to_engine =
+            |grids: Query<&Grid<i64>>,
+             bodies: Query<(Entity, &Parent, GridTransformReadOnly<i64>)>| {
+                let mut affine = None;
+                sync_to(&grids, &bodies, |_, a| affine = Some(a));
+                affine
+            };
+        let read_back = app
+            .world_mut()
+            .run_system_once(sync_to_engine)
+            .unwrap()
+            .unwrap();
+        assert!(read_back
+            .translation
+            .abs_diff_eq(expected.translation, 1e-3));
+        assert!(read_back.matrix3.abs_diff_eq(expected.matrix3, 1e-6));
+    }
+}
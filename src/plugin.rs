@@ -11,16 +11,56 @@ use std::marker::PhantomData;
 pub struct BigSpacePlugin<P: GridPrecision> {
     phantom: PhantomData<P>,
     validate_hierarchies: bool,
+    low_precision: bool,
+    max_grid_depth: usize,
+    #[cfg(feature = "bevy_render")]
+    auto_floating_origin: bool,
 }
 
 impl<P: GridPrecision> BigSpacePlugin<P> {
     /// Create a big space plugin, and specify whether hierarchy validation should be enabled.
     pub fn new(validate_hierarchies: bool) -> Self {
         Self {
-            phantom: PhantomData::<P>,
             validate_hierarchies,
+            ..Default::default()
         }
     }
+
+    /// Enable or disable propagation of low precision entities, which have a [`Transform`] but no
+    /// [`GridCell`], and are children of high precision entities. Enabled by default.
+    ///
+    /// Disabling this removes [`Grid::tag_low_precision_roots`] and
+    /// [`Grid::propagate_low_precision`] from the schedule, which saves their per-frame cost in
+    /// scenes that only contain high precision entities. Low precision entities will not have
+    /// their [`GlobalTransform`] updated, so this must stay enabled if you load scenes like GLTFs,
+    /// or otherwise attach `Transform`-only children to entities in a [`BigSpace`].
+    pub fn with_low_precision(mut self, enabled: bool) -> Self {
+        self.low_precision = enabled;
+        self
+    }
+
+    /// Set the maximum depth of the [`FloatingOrigin`]'s [`Grid`] below the root of its
+    /// [`BigSpace`], which defensively prevents infinite looping in degenerate hierarchies.
+    /// Defaults to `1_000`.
+    ///
+    /// If the grid of the floating origin is deeper than this, a [`GridDepthExceeded`] event is
+    /// sent, and the position of the floating origin is not propagated to the rest of its big
+    /// space. Grids that are deeper than the floating origin's grid do not count towards this
+    /// limit.
+    pub fn with_max_grid_depth(mut self, max_depth: usize) -> Self {
+        self.max_grid_depth = max_depth;
+        self
+    }
+
+    /// Automatically make the camera in a [`BigSpace`] the [`FloatingOrigin`], if the big space
+    /// has no floating origin, and exactly one high precision camera, or exactly one entity marked
+    /// with [`FloatingOriginCandidate`]. Disabled by default. See
+    /// [`BigSpace::assign_camera_floating_origin`].
+    #[cfg(feature = "bevy_render")]
+    pub fn with_auto_floating_origin(mut self, enabled: bool) -> Self {
+        self.auto_floating_origin = enabled;
+        self
+    }
 }
 
 impl<P: GridPrecision> Default for BigSpacePlugin<P> {
@@ -28,17 +68,45 @@ impl<P: GridPrecision> Default for BigSpacePlugin<P> {
         Self {
             phantom: PhantomData,
             validate_hierarchies: cfg!(debug_assertions),
+            low_precision: true,
+            max_grid_depth: MaxGridDepth::default().0,
+            #[cfg(feature = "bevy_render")]
+            auto_floating_origin: false,
         }
     }
 }
 
-#[allow(missing_docs)]
+/// System sets for [`BigSpacePlugin`], run in the `PostStartup` and `PostUpdate` schedules, inside
+/// of [`TransformSystem::TransformPropagate`].
+///
+/// These sets always run in the order they are declared. When the [`GridHashPlugin`] is added, the
+/// spatial hashing sets are run between recentering and propagation, resulting in this order:
+///
+/// 1. [`FloatingOriginSystem::Init`]
+/// 2. [`FloatingOriginSystem::RecenterLargeTransforms`]
+/// 3. [`GridHashMapSystem::UpdateHash`]
+/// 4. [`GridHashMapSystem::UpdateMap`]
+/// 5. [`GridHashMapSystem::UpdatePartition`]
+/// 6. [`FloatingOriginSystem::LocalFloatingOrigins`]
+/// 7. [`FloatingOriginSystem::PropagateHighPrecision`]
+/// 8. [`FloatingOriginSystem::PropagateLowPrecision`]
+///
+/// For example, a system that needs the updated [`GridHashMap`], but must run before any
+/// [`GlobalTransform`] is updated, can be added with
+/// `.after(GridHashMapSystem::UpdateMap).before(FloatingOriginSystem::LocalFloatingOrigins)`.
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum FloatingOriginSystem {
+    /// Resets per-frame state, like timing statistics.
     Init,
+    /// Moves entities into a new [`GridCell`] if their [`Transform`] is too far from the cell's
+    /// center, enforces [`GridBounds`] and [`GridWrap`], and finds the [`FloatingOrigin`] of each
+    /// [`BigSpace`].
     RecenterLargeTransforms,
+    /// Computes the position of the floating origin relative to every [`Grid`].
     LocalFloatingOrigins,
+    /// Computes the [`GlobalTransform`] of every high precision entity and [`Grid`].
     PropagateHighPrecision,
+    /// Computes the [`GlobalTransform`] of low precision entities in a [`BigSpace`].
     PropagateLowPrecision,
 }
 
@@ -46,51 +114,117 @@ impl<P: GridPrecision + Reflect + FromReflect + TypePath + bevy_reflect::GetType
     for BigSpacePlugin<P>
 {
     fn build(&self, app: &mut App) {
+        #[cfg(feature = "bevy_render")]
+        if self.auto_floating_origin {
+            let assign_origin = || {
+                BigSpace::assign_camera_floating_origin
+                    .after(FloatingOriginSystem::Init)
+                    .before(FloatingOriginSystem::RecenterLargeTransforms)
+            };
+            app.add_systems(PostStartup, assign_origin())
+                .add_systems(PostUpdate, assign_origin());
+        }
+
         // Silence bevy's built-in error spam about GlobalTransforms in the hierarchy
         app.insert_resource(bevy_hierarchy::ReportHierarchyIssue::<GlobalTransform>::new(false));
 
         // Performance timings
         app.add_plugins(crate::timing::TimingStatsPlugin);
 
+        let set_order = || {
+            (
+                FloatingOriginSystem::Init,
+                FloatingOriginSystem::RecenterLargeTransforms,
+                FloatingOriginSystem::LocalFloatingOrigins,
+                FloatingOriginSystem::PropagateHighPrecision,
+                FloatingOriginSystem::PropagateLowPrecision,
+            )
+                .chain()
+                .in_set(TransformSystem::TransformPropagate)
+        };
+        app.configure_sets(PostStartup, set_order())
+            .configure_sets(PostUpdate, set_order());
+
         let system_set_config = || {
             (
-                Grid::<P>::tag_low_precision_roots // loose ordering on this set
-                    .after(FloatingOriginSystem::Init)
-                    .before(FloatingOriginSystem::PropagateLowPrecision),
                 (
+                    crate::mirror::MirrorPosition::update::<P>
+                        .before(GridCell::<P>::recenter_large_transforms),
+                    crate::track::TrackFloatingOrigin::update::<P>
+                        .before(GridCell::<P>::recenter_large_transforms),
                     GridCell::<P>::recenter_large_transforms,
+                    GridWrap::<P>::enforce
+                        .after(GridCell::<P>::recenter_large_transforms)
+                        .run_if(any_with_component::<GridWrap<P>>),
+                    GridBounds::<P>::enforce
+                        .after(GridWrap::<P>::enforce)
+                        .run_if(any_with_component::<GridBounds<P>>),
                     BigSpace::find_floating_origin,
                 )
                     .in_set(FloatingOriginSystem::RecenterLargeTransforms),
                 LocalFloatingOrigin::<P>::compute_all
-                    .in_set(FloatingOriginSystem::LocalFloatingOrigins)
-                    .after(FloatingOriginSystem::RecenterLargeTransforms),
-                Grid::<P>::propagate_high_precision
-                    .in_set(FloatingOriginSystem::PropagateHighPrecision)
-                    .after(FloatingOriginSystem::LocalFloatingOrigins),
+                    .in_set(FloatingOriginSystem::LocalFloatingOrigins),
+                (
+                    Grid::<P>::propagate_high_precision,
+                    crate::lod::CellLodDistance::update::<P>,
+                )
+                    .in_set(FloatingOriginSystem::PropagateHighPrecision),
+            )
+                .in_set(TransformSystem::TransformPropagate)
+        };
+
+        let low_precision_config = || {
+            (
+                Grid::<P>::tag_low_precision_roots // loose ordering on this set
+                    .after(FloatingOriginSystem::Init)
+                    .before(FloatingOriginSystem::PropagateLowPrecision),
                 Grid::<P>::propagate_low_precision
-                    .in_set(FloatingOriginSystem::PropagateLowPrecision)
-                    .after(FloatingOriginSystem::PropagateHighPrecision),
+                    .in_set(FloatingOriginSystem::PropagateLowPrecision),
             )
                 .in_set(TransformSystem::TransformPropagate)
         };
+        if self.low_precision {
+            app.add_systems(PostStartup, low_precision_config())
+                .add_systems(PostUpdate, low_precision_config());
+        }
 
         app
-            // Reflect
+            // Reflect. Bundles are not registered, their components are registered individually.
             .register_type::<Transform>()
             .register_type::<GlobalTransform>()
             .register_type::<GridCell<P>>()
             .register_type::<GridCellAny>()
             .register_type::<Grid<P>>()
+            .register_type::<GridBounds<P>>()
+            .register_type::<GridWrap<P>>()
             .register_type::<BigSpace>()
             .register_type::<FloatingOrigin>()
+            .register_type::<FloatingOriginCandidate>()
+            .register_type::<CellLodDistance>()
+            .register_type::<MirrorPosition>()
+            .register_type::<TrackFloatingOrigin>()
+            .register_type::<crate::grid::propagation::BigSpacePropagationConfig>()
+            .init_resource::<crate::grid::propagation::BigSpacePropagationConfig>()
+            .init_resource::<crate::grid::propagation::ChangedGridEntities<P>>()
+            .register_type::<crate::scene::BigSpaceSnapshot<P>>()
+            .register_type::<MaxGridDepth>()
+            .insert_resource(MaxGridDepth(self.max_grid_depth))
+            .add_event::<GridDepthExceeded>()
+            .add_event::<OutOfGridBounds>()
+            .add_event::<BigSpaceTransfer>()
+            .add_event::<crate::validation::GridHierarchyCycle>()
             // Meat of the plugin, once on startup, as well as every update
             .add_systems(PostStartup, system_set_config())
             .add_systems(PostUpdate, system_set_config())
             // Validation
             .add_systems(
                 PostUpdate,
-                crate::validation::validate_hierarchy::<crate::validation::SpatialHierarchyRoot<P>>
+                (
+                    crate::validation::validate_hierarchy::<
+                        crate::validation::SpatialHierarchyRoot<P>,
+                    >,
+                    crate::validation::validate_grid_cycles::<P>,
+                )
                     .after(TransformSystem::TransformPropagate)
                     .run_if({
                         let run = self.validate_hierarchies;
@@ -25,6 +25,10 @@ use bevy_reflect::{FromReflect, GetTypeRegistration, Reflect, TypePath, Typed};
 /// where `usable_edge_length = 2^(integer_bits) * cell_edge_length`, resulting in a worst case
 /// precision of 0.5mm in any of these cases.
 ///
+/// If you are unsure what to choose, start with one of the [`Grid`](crate::Grid) presets, like
+/// [`Grid::solar_system`](crate::Grid::solar_system), which document the precision they are
+/// intended to be used with.
+///
 /// This can also be used for small scales. With a cell edge length of `1e-11`, and using `i128`,
 /// there is enough precision to render objects the size of protons anywhere in the observable
 /// universe.
@@ -78,6 +82,10 @@ pub trait GridPrecision:
     fn mul(self, rhs: Self) -> Self;
     /// Casts `self` as a double precision float.
     fn as_f64(self) -> f64;
+    /// Losslessly casts `self` as an `i128`.
+    fn as_i128(self) -> i128;
+    /// Casts an `i128` into `Self`, truncating it if it is out of range.
+    fn from_i128(input: i128) -> Self;
     /// Casts a double precision float into `Self`.
     fn from_f64(input: f64) -> Self;
     /// Casts a single precision float into `Self`.
@@ -109,6 +117,14 @@ impl GridPrecision for i8 {
         self as f64
     }
     #[inline]
+    fn as_i128(self) -> i128 {
+        self as i128
+    }
+    #[inline]
+    fn from_i128(input: i128) -> Self {
+        input as Self
+    }
+    #[inline]
     fn from_f64(input: f64) -> Self {
         input as Self
     }
@@ -143,6 +159,14 @@ impl GridPrecision for i16 {
         self as f64
     }
     #[inline]
+    fn as_i128(self) -> i128 {
+        self as i128
+    }
+    #[inline]
+    fn from_i128(input: i128) -> Self {
+        input as Self
+    }
+    #[inline]
     fn from_f64(input: f64) -> Self {
         input as Self
     }
@@ -177,6 +201,14 @@ impl GridPrecision for i32 {
         self as f64
     }
     #[inline]
+    fn as_i128(self) -> i128 {
+        self as i128
+    }
+    #[inline]
+    fn from_i128(input: i128) -> Self {
+        input as Self
+    }
+    #[inline]
     fn from_f64(input: f64) -> Self {
         input as Self
     }
@@ -211,6 +243,14 @@ impl GridPrecision for i64 {
         self as f64
     }
     #[inline]
+    fn as_i128(self) -> i128 {
+        self as i128
+    }
+    #[inline]
+    fn from_i128(input: i128) -> Self {
+        input as Self
+    }
+    #[inline]
     fn from_f64(input: f64) -> Self {
         input as Self
     }
@@ -245,6 +285,14 @@ impl GridPrecision for i128 {
         self as f64
     }
     #[inline]
+    fn as_i128(self) -> i128 {
+        self
+    }
+    #[inline]
+    fn from_i128(input: i128) -> Self {
+        input as Self
+    }
+    #[inline]
     fn from_f64(input: f64) -> Self {
         input as Self
     }
@@ -0,0 +1,46 @@
+//! Deprecated names from before `ReferenceFrame` was renamed [`Grid`], to make upgrading easier.
+//!
+//! Code written against the old `reference_frame` module will continue to compile, with a
+//! deprecation warning for each use of an old name that points to its replacement in the
+//! [`grid`](crate::grid) module. The old names are not exported from the
+//! [`prelude`](crate::prelude), import them from this module while migrating:
+//!
+//! | Old name                    | New name                                 |
+//! |-----------------------------|------------------------------------------|
+//! | `ReferenceFrame`            | [`Grid`]                                 |
+//! | `ReferenceFrames`           | [`Grids`]                                |
+//! | `ReferenceFramesMut`        | [`GridsMut`]                             |
+//! | `ReferenceFrameCommands`    | [`GridCommands`]                         |
+//! | `BigReferenceFrameBundle`   | [`BigGridBundle`]                        |
+//! | `reference_frame::*`        | [`grid::*`](crate::grid)                 |
+//! | `with_frame`                | [`GridCommands::with_grid`]              |
+//! | `with_frame_default`        | [`GridCommands::with_grid_default`]      |
+//!
+//! Methods can't be aliased, so calls to `with_frame` and `with_frame_default` need to be renamed
+//! by hand. Note that these now return the [`Entity`](bevy_ecs::entity::Entity) of the new grid.
+//!
+//! This module will be removed in a future release.
+
+use crate::prelude::*;
+
+pub use crate::grid::{local_origin, propagation};
+
+/// Renamed to [`Grid`].
+#[deprecated = "`ReferenceFrame` has been renamed `Grid`"]
+pub type ReferenceFrame<P> = Grid<P>;
+
+/// Renamed to [`Grids`].
+#[deprecated = "`ReferenceFrames` has been renamed `Grids`"]
+pub type ReferenceFrames<'w, 's, P> = Grids<'w, 's, P>;
+
+/// Renamed to [`GridsMut`].
+#[deprecated = "`ReferenceFramesMut` has been renamed `GridsMut`"]
+pub type ReferenceFramesMut<'w, 's, P> = GridsMut<'w, 's, P>;
+
+/// Renamed to [`GridCommands`].
+#[deprecated = "`ReferenceFrameCommands` has been renamed `GridCommands`"]
+pub type ReferenceFrameCommands<'a, P> = GridCommands<'a, P>;
+
+/// Renamed to [`BigGridBundle`].
+#[deprecated = "`BigReferenceFrameBundle` has been renamed `BigGridBundle`"]
+pub type BigReferenceFrameBundle<P> = BigGridBundle<P>;
@@ -0,0 +1,250 @@
+//! Helpers for replicating high precision positions over the network.
+//!
+//! Entities in a [`BigSpace`] rarely change cells, and their [`Transform`] never grows much larger
+//! than a single cell. A [`GridTransformDelta`] exploits this by storing the change in cell as a
+//! small integer offset, and the change in translation as a quantized integer offset, which is
+//! usually zero or close to zero, and compresses well with any variable length integer encoding.
+//!
+//! To prevent quantization error from accumulating, deltas should always be computed against the
+//! state the receiver has reconstructed, not the previous exact state on the sender:
+//!
+//! ```
+//! # use big_space::{prelude::*, replication::*};
+//! # use bevy::prelude::*;
+//! let quantum = 0.001; // 1mm
+//! let mut client = GridTransformOwned::<i64> {
+//!     cell: GridCell::ZERO,
+//!     transform: Transform::IDENTITY,
+//! };
+//! let server = GridTransformOwned {
+//!     cell: GridCell::new(1, 0, 0),
+//!     transform: Transform::from_xyz(-10.0, 2.5, 0.0),
+//! };
+//!
+//! // On the server, diff against what the client has.
+//! let delta = diff(&client, &server, quantum).expect("the entity did not teleport");
+//! // On the client, apply the delta to reconstruct the server's state.
+//! client = apply_delta(&client, &delta, quantum);
+//!
+//! assert_eq!(client.cell, server.cell);
+//! assert!(client.transform.translation.distance(server.transform.translation) <= quantum);
+//! ```
+//!
+//! This only provides the diff and patch logic; serializing the delta is left to your networking
+//! library.
+//!
+//! To smoothly render entities between received updates, use [`lerp_grid`], which interpolates
+//! correctly even when the entity has moved between cells.
+
+use crate::prelude::*;
+use bevy_math::{prelude::*, DVec3};
+use bevy_transform::prelude::*;
+
+/// The change between two high precision positions in the same [`Grid`], computed with [`diff`]
+/// and applied with [`apply_delta`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GridTransformDelta {
+    /// The change in [`GridCell`].
+    pub cell: IVec3,
+    /// The change in [`Transform::translation`], in multiples of the quantum used to compute the
+    /// delta.
+    pub translation: IVec3,
+    /// The new [`Transform::rotation`], if it changed.
+    pub rotation: Option<Quat>,
+    /// The new [`Transform::scale`], if it changed.
+    pub scale: Option<Vec3>,
+}
+
+impl GridTransformDelta {
+    /// Returns `true` if applying this delta would not change the position.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Compute the [`GridTransformDelta`] that moves `previous` to `current`, with translations
+/// quantized to multiples of `quantum`.
+///
+/// Returns `None` if the change in cell or the quantized change in translation does not fit in an
+/// [`IVec3`], for example when an entity is teleported across the grid. In this case, replicate the
+/// full [`GridTransformOwned`] instead.
+pub fn diff<P: GridPrecision>(
+    previous: &GridTransformOwned<P>,
+    current: &GridTransformOwned<P>,
+    quantum: f32,
+) -> Option<GridTransformDelta> {
+    let cell = current.cell - previous.cell;
+    let cell = to_ivec3(DVec3::new(
+        cell.x.as_f64(),
+        cell.y.as_f64(),
+        cell.z.as_f64(),
+    ))?;
+
+    let translation = (current.transform.translation.as_dvec3()
+        - previous.transform.translation.as_dvec3())
+        / quantum as f64;
+    let translation = to_ivec3(translation.round())?;
+
+    let rotation = (current.transform.rotation != previous.transform.rotation)
+        .then_some(current.transform.rotation);
+    let scale =
+        (current.transform.scale != previous.transform.scale).then_some(current.transform.scale);
+
+    Some(GridTransformDelta {
+        cell,
+        translation,
+        rotation,
+        scale,
+    })
+}
+
+/// Reconstruct the position that a [`GridTransformDelta`] was computed for, from the `previous`
+/// position it was computed against. The `quantum` must match the one used with [`diff`].
+pub fn apply_delta<P: GridPrecision>(
+    previous: &GridTransformOwned<P>,
+    delta: &GridTransformDelta,
+    quantum: f32,
+) -> GridTransformOwned<P> {
+    let translation =
+        previous.transform.translation.as_dvec3() + delta.translation.as_dvec3() * quantum as f64;
+    GridTransformOwned {
+        cell: previous.cell + delta.cell,
+        transform: Transform {
+            translation: translation.as_vec3(),
+            rotation: delta.rotation.unwrap_or(previous.transform.rotation),
+            scale: delta.scale.unwrap_or(previous.transform.scale),
+        },
+    }
+}
+
+/// Interpolate between two high precision positions in the same `grid`, where `t` is in
+/// `0.0..=1.0`.
+///
+/// The positions are interpolated in double precision relative to the cell of `from`, so this is
+/// precise even if `from` and `to` are in very different cells. The result is recentered into the
+/// nearest cell. Rotation is spherically interpolated, and scale is linearly interpolated.
+///
+/// This is also useful for smoothly rendering entities that are simulated with a fixed timestep,
+/// by interpolating between the last two ticks. Interpolating the [`Transform`] alone would jump
+/// by a cell whenever an entity changes cell between ticks.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use big_space::{prelude::*, replication::lerp_grid};
+/// /// The position at the previous and current fixed timestep ticks.
+/// #[derive(Component)]
+/// struct Ticks {
+///     previous: (GridCell<i64>, Transform),
+///     current: (GridCell<i64>, Transform),
+/// }
+///
+/// fn interpolate(
+///     time: Res<Time<Fixed>>,
+///     grids: Query<&Grid<i64>>,
+///     mut bodies: Query<(&Ticks, &Parent, &mut GridCell<i64>, &mut Transform)>,
+/// ) {
+///     let t = time.overstep_fraction();
+///     for (ticks, parent, mut cell, mut transform) in &mut bodies {
+///         let Ok(grid) = grids.get(parent.get()) else {
+///             continue;
+///         };
+///         (*cell, *transform) = lerp_grid(ticks.previous, ticks.current, grid, t);
+///     }
+/// }
+/// ```
+pub fn lerp_grid<P: GridPrecision>(
+    from: (GridCell<P>, Transform),
+    to: (GridCell<P>, Transform),
+    grid: &Grid<P>,
+    t: f32,
+) -> (GridCell<P>, Transform) {
+    let (from_cell, from_transform) = from;
+    let (to_cell, to_transform) = to;
+
+    let start = from_transform.translation.as_dvec3();
+    let end = grid.cell_to_float(&(to_cell - from_cell)) + to_transform.translation.as_dvec3();
+    let (cell_offset, translation) = grid.translation_to_grid(start.lerp(end, t as f64));
+
+    (
+        from_cell + cell_offset,
+        Transform {
+            translation,
+            rotation: from_transform.rotation.slerp(to_transform.rotation, t),
+            scale: from_transform.scale.lerp(to_transform.scale, t),
+        },
+    )
+}
+
+/// Convert to an [`IVec3`], if the value is in range.
+fn to_ivec3(value: DVec3) -> Option<IVec3> {
+    let range = i32::MIN as f64..=i32::MAX as f64;
+    (range.contains(&value.x) && range.contains(&value.y) && range.contains(&value.z))
+        .then(|| value.as_ivec3())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_drift_when_diffing_against_reconstructed() {
+        let quantum = 0.01;
+        let mut client = GridTransformOwned::<i32> {
+            cell: GridCell::ZERO,
+            transform: Transform::IDENTITY,
+        };
+        let mut server = client;
+        for i in 0..1_000 {
+            server.transform.translation += Vec3::new(0.123_4, -0.005, 0.0);
+            if i % 100 == 0 {
+                server.cell += IVec3::X;
+                server.transform.translation.x -= 2_000.0;
+            }
+            let delta = diff(&client, &server, quantum).unwrap();
+            client = apply_delta(&client, &delta, quantum);
+        }
+        assert_eq!(client.cell, server.cell);
+        let error = client
+            .transform
+            .translation
+            .distance(server.transform.translation);
+        assert!(error <= quantum, "{error}");
+
+        let unchanged = diff(&client, &client, quantum).unwrap();
+        assert!(unchanged.is_empty());
+    }
+
+    #[test]
+    fn lerp_across_cells() {
+        let grid = Grid::<i64>::new_uniform(10.0, 0.0);
+        let from = (
+            GridCell::new(-3, 0, 1_000_000_000_000),
+            Transform::from_xyz(4.0, 0.0, 0.0),
+        );
+        let to = (
+            GridCell::new(7, 2, 1_000_000_000_000),
+            Transform::from_xyz(-4.0, 1.0, 0.0),
+        );
+
+        let (cell, transform) = lerp_grid(from, to, &grid, 0.5);
+        // Halfway between x = -26 and x = 66, and y = 0 and y = 21
+        assert_eq!(cell, GridCell::new(2, 1, 1_000_000_000_000));
+        assert!(transform.translation.distance(Vec3::new(0.0, 0.5, 0.0)) < 1e-4);
+
+        assert_eq!(lerp_grid(from, to, &grid, 0.0).0, from.0);
+        assert_eq!(lerp_grid(from, to, &grid, 1.0).0, to.0);
+    }
+
+    #[test]
+    fn teleport_does_not_fit() {
+        let previous = GridTransformOwned::<i64> {
+            cell: GridCell::ZERO,
+            transform: Transform::IDENTITY,
+        };
+        let current = GridTransformOwned {
+            cell: GridCell::new(i64::MAX / 2, 0, 0),
+            ..previous
+        };
+        assert!(diff(&previous, &current, 0.001).is_none());
+    }
+}
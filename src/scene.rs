@@ -0,0 +1,305 @@
+//! Save and restore an entire [`BigSpace`], preserving high precision positions exactly.
+//!
+//! Bevy's scenes are not aware of how grids are nested, and saving a [`GlobalTransform`] would lose
+//! the precision that [`GridCell`]s provide. Instead, a [`BigSpaceSnapshot`] captures the
+//! [`Grid`]s, [`GridCell`]s, and [`Transform`]s of every spatial entity in a [`BigSpace`], along
+//! with the hierarchy that connects them. Snapshots can be serialized with `bevy_reflect`'s
+//! serializers, and are rebuilt with [`BigSpaceCommands::spawn_big_space`].
+//!
+//! Only spatial components are saved. Use the entities returned by [`BigSpaceSnapshot::spawn`] to
+//! map your own components onto the restored entities.
+//!
+//! [`GlobalTransform`]: bevy_transform::prelude::GlobalTransform
+
+use crate::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::prelude::*;
+use bevy_reflect::prelude::*;
+use bevy_transform::prelude::*;
+
+/// A snapshot of the spatial entities in a [`BigSpace`], see the [module docs](self).
+#[derive(Debug, Clone, Reflect)]
+pub struct BigSpaceSnapshot<P: GridPrecision> {
+    /// The [`Grid`] of the [`BigSpace`] root entity.
+    ///
+    /// The [`LocalFloatingOrigin`] of saved grids is reset, because it is recomputed every frame.
+    pub grid: Grid<P>,
+    /// Every spatial entity in the [`BigSpace`], with parents ordered before their children.
+    pub entities: Vec<SpatialSnapshot<P>>,
+}
+
+/// A spatial entity in a [`BigSpaceSnapshot`].
+#[derive(Debug, Clone, Reflect)]
+pub struct SpatialSnapshot<P: GridPrecision> {
+    /// The index of the parent of this entity in [`BigSpaceSnapshot::entities`], or `None` if this
+    /// entity is a child of the [`BigSpace`] root.
+    pub parent: Option<usize>,
+    /// The cell of this entity, or `None` if this is a low precision entity.
+    pub cell: Option<GridCell<P>>,
+    /// The transform of this entity, relative to its cell or its parent.
+    pub transform: Transform,
+    /// The grid of this entity, if it has one.
+    pub grid: Option<Grid<P>>,
+    /// Whether this entity is the [`FloatingOrigin`].
+    pub floating_origin: bool,
+}
+
+impl<P: GridPrecision> BigSpaceSnapshot<P> {
+    /// Capture a snapshot of the [`BigSpace`] with the `root` entity. Returns `None` if the entity
+    /// is not the root of a [`BigSpace`] with a [`Grid<P>`].
+    ///
+    /// Children without a [`Transform`] are not spatial, and are skipped along with their
+    /// descendants.
+    ///
+    /// Child grids without a [`GridCell`] are saved and restored as they are, but a warning is
+    /// logged, because only the root grid of a [`BigSpace`] is valid without a [`GridCell`].
+    pub fn from_world(world: &World, root: Entity) -> Option<Self> {
+        world.get::<BigSpace>(root)?;
+        let grid = Self::saved_grid(world.get::<Grid<P>>(root)?);
+
+        let mut entities = Vec::new();
+        let mut stack: Vec<(Option<usize>, Entity)> = Self::children(world, root)
+            .rev()
+            .map(|child| (None, child))
+            .collect();
+
+        while let Some((parent, entity)) = stack.pop() {
+            let Some(transform) = world.get::<Transform>(entity) else {
+                continue;
+            };
+            let index = entities.len();
+            let grid = world.get::<Grid<P>>(entity);
+            let cell = world.get::<GridCell<P>>(entity).copied();
+            if grid.is_some() && cell.is_none() {
+                tracing::warn!(
+                    "The grid {entity} in the BigSpace {root} has no GridCell. It will be saved, but only root grids are valid without a GridCell."
+                );
+            }
+            entities.push(SpatialSnapshot {
+                parent,
+                cell,
+                transform: *transform,
+                grid: grid.map(Self::saved_grid),
+                floating_origin: world.get::<FloatingOrigin>(entity).is_some(),
+            });
+            stack.extend(
+                Self::children(world, entity)
+                    .rev()
+                    .map(|child| (Some(index), child)),
+            );
+        }
+
+        Some(Self { grid, entities })
+    }
+
+    /// Clones the `grid`, without the position of the floating origin from the last frame.
+    fn saved_grid(grid: &Grid<P>) -> Grid<P> {
+        let mut grid = grid.clone();
+        *grid.local_floating_origin_mut() = LocalFloatingOrigin::default();
+        grid
+    }
+
+    fn children(world: &World, entity: Entity) -> impl DoubleEndedIterator<Item = Entity> + '_ {
+        world
+            .get::<Children>(entity)
+            .into_iter()
+            .flat_map(|children| children.iter().copied())
+    }
+
+    /// Spawn a new [`BigSpace`] from this snapshot.
+    ///
+    /// Returns the root entity, and the spawned entities, in the same order as
+    /// [`Self::entities`].
+    pub fn spawn(&self, commands: &mut Commands) -> (Entity, Vec<Entity>) {
+        let mut children = vec![Vec::new(); self.entities.len() + 1];
+        for (index, entity) in self.entities.iter().enumerate() {
+            children[entity.parent.unwrap_or(self.entities.len())].push(index);
+        }
+
+        let mut spawned = vec![Entity::PLACEHOLDER; self.entities.len()];
+        let root = commands.spawn_big_space(self.grid.clone(), |grid| {
+            self.spawn_in_grid(
+                grid,
+                &children[self.entities.len()],
+                &children,
+                &mut spawned,
+            );
+        });
+        (root, spawned)
+    }
+
+    fn spawn_in_grid(
+        &self,
+        grid: &mut GridCommands<P>,
+        nodes: &[usize],
+        children: &[Vec<usize>],
+        spawned: &mut [Entity],
+    ) {
+        for &index in nodes {
+            let node = &self.entities[index];
+            match (&node.grid, node.cell) {
+                (Some(child_grid), Some(cell)) => {
+                    let mut child = grid.spawn_grid(child_grid.clone(), (cell, node.transform));
+                    if node.floating_origin {
+                        child.insert(FloatingOrigin);
+                    }
+                    spawned[index] = child.id();
+                    self.spawn_in_grid(&mut child, &children[index], children, spawned);
+                }
+                (None, Some(cell)) => {
+                    let mut child = grid.spawn_spatial((cell, node.transform));
+                    if node.floating_origin {
+                        child.insert(FloatingOrigin);
+                    }
+                    spawned[index] = child.id();
+                    let parent = child.id();
+                    self.spawn_low_precision(
+                        child.commands(),
+                        parent,
+                        &children[index],
+                        children,
+                        spawned,
+                    );
+                }
+                (node_grid, None) => {
+                    let mut child = grid.spawn(node.transform);
+                    if let Some(node_grid) = node_grid {
+                        child.insert(node_grid.clone());
+                    }
+                    spawned[index] = child.id();
+                    let parent = child.id();
+                    self.spawn_low_precision(
+                        child.commands(),
+                        parent,
+                        &children[index],
+                        children,
+                        spawned,
+                    );
+                }
+            }
+        }
+    }
+
+    fn spawn_low_precision(
+        &self,
+        commands: &mut Commands,
+        parent: Entity,
+        nodes: &[usize],
+        children: &[Vec<usize>],
+        spawned: &mut [Entity],
+    ) {
+        for &index in nodes {
+            let node = &self.entities[index];
+            let mut entity = commands.spawn(node.transform);
+            entity.set_parent(parent);
+            if let Some(cell) = node.cell {
+                entity.insert(cell);
+            }
+            if let Some(grid) = &node.grid {
+                entity.insert(grid.clone());
+            }
+            let entity = entity.id();
+            spawned[index] = entity;
+            self.spawn_low_precision(commands, entity, &children[index], children, spawned);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn snapshot_round_trip() {
+        #[derive(Resource)]
+        struct Root(Entity);
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i64>::default());
+
+        let mut commands = app.world_mut().commands();
+        commands.spawn_big_space::<i64>(Grid::new_uniform(10.0, 0.0), |root| {
+            let id = root.id();
+            root.commands().insert_resource(Root(id));
+            root.spawn_spatial((FloatingOrigin, GridCell::<i64>::new(-5, 0, 1)));
+            root.with_grid(Grid::new_uniform(1.0, 0.0), |planet| {
+                planet.insert((
+                    GridCell::<i64>::new(i64::MAX - 7, 3, i64::MIN + 11),
+                    Transform::from_rotation(Quat::from_rotation_y(1.0)),
+                ));
+                planet
+                    .spawn_spatial((
+                        GridCell::<i64>::new(123_456_789_012, 0, 0),
+                        Transform::from_xyz(0.25, 0.5, 0.125),
+                    ))
+                    .with_child(Transform::from_xyz(1.0, 2.0, 3.0));
+            });
+        });
+        app.world_mut().flush();
+        app.update();
+
+        let root = app.world().resource::<Root>().0;
+        let snapshot = BigSpaceSnapshot::<i64>::from_world(app.world(), root).unwrap();
+        assert_eq!(snapshot.entities.len(), 4);
+
+        app.world_mut().entity_mut(root).despawn_recursive();
+        let (restored_root, _) = snapshot.spawn(&mut app.world_mut().commands());
+        app.world_mut().flush();
+        app.update();
+
+        let restored = BigSpaceSnapshot::<i64>::from_world(app.world(), restored_root).unwrap();
+        assert_eq!(restored.entities.len(), snapshot.entities.len());
+        for (a, b) in snapshot.entities.iter().zip(restored.entities.iter()) {
+            assert_eq!(a.parent, b.parent);
+            assert_eq!(a.cell, b.cell);
+            assert_eq!(a.transform, b.transform);
+            assert_eq!(a.floating_origin, b.floating_origin);
+            assert_eq!(
+                a.grid.as_ref().map(Grid::cell_edge_length),
+                b.grid.as_ref().map(Grid::cell_edge_length)
+            );
+        }
+    }
+
+    #[test]
+    fn snapshot_grids() {
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i64>::default());
+
+        let mut commands = app.world_mut().commands();
+        let root = commands.spawn_big_space::<i64>(Grid::new_uniform(10.0, 0.0), |root| {
+            root.spawn_spatial((FloatingOrigin, GridCell::<i64>::new(-5, 0, 1)));
+        });
+        app.world_mut().flush();
+        app.update();
+        let local_origin = app
+            .world()
+            .get::<Grid<i64>>(root)
+            .unwrap()
+            .local_floating_origin();
+        assert_eq!(local_origin.cell(), GridCell::new(-5, 0, 1));
+
+        // A grid without a cell is not a valid child grid, but it should still be saved.
+        app.world_mut()
+            .spawn((Transform::default(), Grid::<i64>::new_uniform(3.0, 0.0)))
+            .set_parent(root);
+
+        // The position of the floating origin is recomputed every frame, and is not saved.
+        let snapshot = BigSpaceSnapshot::<i64>::from_world(app.world(), root).unwrap();
+        assert_eq!(
+            snapshot.grid.local_floating_origin(),
+            &LocalFloatingOrigin::default()
+        );
+
+        let (restored_root, spawned) = snapshot.spawn(&mut app.world_mut().commands());
+        app.world_mut().flush();
+        let grid = app.world().get::<Grid<i64>>(spawned[1]).unwrap();
+        assert_eq!(grid.cell_edge_length(), Vec3::splat(3.0));
+        assert_eq!(app.world().get::<GridCell<i64>>(spawned[1]), None);
+        assert_eq!(
+            app.world().get::<Parent>(spawned[1]).map(Parent::get),
+            Some(restored_root)
+        );
+    }
+}
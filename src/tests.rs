@@ -81,3 +81,652 @@ fn child_global_transforms_are_updated_when_floating_origin_changes() {
 
     assert_eq!(child_transform.translation(), Vec3::new(0.0, 0.0, 600.0));
 }
+
+#[test]
+fn system_sets_run_in_documented_order() {
+    #[derive(Resource, Default)]
+    struct Order(Vec<&'static str>);
+
+    fn record(name: &'static str) -> impl Fn(ResMut<Order>) {
+        move |mut order: ResMut<Order>| order.0.push(name)
+    }
+
+    let mut app = App::new();
+    app.add_plugins((
+        BigSpacePlugin::<i32>::default(),
+        GridHashPlugin::<i32>::default(),
+        GridPartitionPlugin::<i32>::default(),
+    ))
+    .init_resource::<Order>()
+    .add_systems(
+        PostUpdate,
+        (
+            record("propagate_low").in_set(FloatingOriginSystem::PropagateLowPrecision),
+            record("propagate_high").in_set(FloatingOriginSystem::PropagateHighPrecision),
+            record("local_origins").in_set(FloatingOriginSystem::LocalFloatingOrigins),
+            record("partition").in_set(GridHashMapSystem::UpdatePartition),
+            record("map").in_set(GridHashMapSystem::UpdateMap),
+            record("hash").in_set(GridHashMapSystem::UpdateHash),
+            record("recenter").in_set(FloatingOriginSystem::RecenterLargeTransforms),
+            record("init").in_set(FloatingOriginSystem::Init),
+            record("between")
+                .after(GridHashMapSystem::UpdateMap)
+                .before(FloatingOriginSystem::LocalFloatingOrigins),
+        ),
+    );
+
+    app.update();
+
+    let order = &app.world().resource::<Order>().0;
+    let position = |name| order.iter().position(|n| *n == name).unwrap();
+    let expected = [
+        "init",
+        "recenter",
+        "hash",
+        "map",
+        "partition",
+        "local_origins",
+        "propagate_high",
+        "propagate_low",
+    ];
+    for pair in expected.windows(2) {
+        assert!(position(pair[0]) < position(pair[1]), "{order:?}");
+    }
+    assert!(position("map") < position("between"));
+    assert!(position("between") < position("local_origins"));
+}
+
+#[test]
+fn spawn_big_space_returns_root() {
+    let mut app = App::new();
+    app.add_plugins(BigSpacePlugin::<i32>::default());
+
+    let mut commands = app.world_mut().commands();
+    let mut inner = Entity::PLACEHOLDER;
+    let root = commands.spawn_big_space_default::<i32>(|root| {
+        inner = root.id();
+        root.spawn_spatial(FloatingOrigin);
+    });
+    app.world_mut().flush();
+
+    assert_eq!(root, inner);
+    assert!(app.world().get::<BigSpace>(root).is_some());
+    assert!(app.world().get::<Grid<i32>>(root).is_some());
+}
+
+#[test]
+fn spatial_types_are_registered() {
+    use crate::hash::partition::GridPartitionId;
+    use core::any::TypeId;
+
+    let mut app = App::new();
+    app.add_plugins((
+        BigSpacePlugin::<i32>::default(),
+        GridHashPlugin::<i32>::default(),
+        GridPartitionPlugin::<i32>::default(),
+    ));
+
+    let registry = app.world().resource::<AppTypeRegistry>().read();
+    for type_id in [
+        TypeId::of::<GridCell<i32>>(),
+        TypeId::of::<Grid<i32>>(),
+        TypeId::of::<BigSpace>(),
+        TypeId::of::<FloatingOrigin>(),
+        TypeId::of::<GridHash<i32>>(),
+        TypeId::of::<FastGridHash>(),
+        TypeId::of::<GridPartitionId>(),
+    ] {
+        assert!(registry.contains(type_id));
+    }
+}
+
+#[test]
+fn upsert_spatial_does_not_duplicate() {
+    let mut app = App::new();
+    app.add_plugins(BigSpacePlugin::<i32>::default());
+
+    let existing = app.world_mut().spawn(GridCell::<i32>::new(5, 0, 0)).id();
+    let bare = app.world_mut().spawn_empty().id();
+    let root = app
+        .world_mut()
+        .commands()
+        .spawn_big_space_default::<i32>(|root| {
+            root.upsert_spatial(existing, ());
+            root.upsert_spatial(existing, Transform::from_xyz(1.0, 0.0, 0.0));
+            root.upsert_spatial(bare, ());
+        });
+    app.update();
+
+    let world = app.world();
+    assert_eq!(world.get::<Children>(root).unwrap().len(), 2);
+    assert_eq!(world.get::<Parent>(existing).unwrap().get(), root);
+    assert_eq!(
+        world.get::<GridCell<i32>>(existing),
+        Some(&GridCell::new(5, 0, 0))
+    );
+    assert_eq!(
+        world.get::<Transform>(existing),
+        Some(&Transform::from_xyz(1.0, 0.0, 0.0))
+    );
+    assert_eq!(world.get::<GridCell<i32>>(bare), Some(&GridCell::ZERO));
+    assert_eq!(world.get::<Parent>(bare).unwrap().get(), root);
+}
+
+#[test]
+fn spawn_spatial_at_absolute_position() {
+    let mut app = App::new();
+    app.add_plugins(BigSpacePlugin::<i64>::default());
+
+    let mut entities = (Entity::PLACEHOLDER, Entity::PLACEHOLDER);
+    app.world_mut()
+        .commands()
+        .spawn_big_space::<i64>(Grid::new_uniform(10.0, 0.0), |root| {
+            entities.0 = root
+                .spawn_spatial_at(bevy::math::DVec3::new(1e12 + 3.0, -24.0, 0.5), ())
+                .id();
+            entities.1 = root
+                .spawn_spatial_at(
+                    bevy::math::DVec3::new(15.0, 0.0, 0.0),
+                    (
+                        GridCell::<i64>::new(7, 7, 7),
+                        Transform::from_scale(Vec3::splat(2.0)),
+                    ),
+                )
+                .id();
+        });
+    app.update();
+
+    let world = app.world();
+    assert_eq!(
+        world.get::<GridCell<i64>>(entities.0),
+        Some(&GridCell::new(100_000_000_000, -2, 0))
+    );
+    assert_eq!(
+        world.get::<Transform>(entities.0),
+        Some(&Transform::from_xyz(3.0, -4.0, 0.5))
+    );
+    // The position overrides the cell and translation, but the scale is kept.
+    assert_eq!(
+        world.get::<GridCell<i64>>(entities.1),
+        Some(&GridCell::new(2, 0, 0))
+    );
+    assert_eq!(
+        world.get::<Transform>(entities.1),
+        Some(&Transform::from_xyz(-5.0, 0.0, 0.0).with_scale(Vec3::splat(2.0)))
+    );
+}
+
+#[test]
+fn with_grid_returns_child_grid() {
+    #[derive(Resource)]
+    struct Spawned(Entity, Entity);
+
+    let mut app = App::new();
+    app.add_plugins(BigSpacePlugin::<i32>::default())
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn_big_space_default::<i32>(|root| {
+                let planet = root.with_grid_default(|_| {});
+                let moon = root.with_grid(Grid::new_uniform(5.0, 0.0), |moon| {
+                    moon.spawn_spatial(());
+                });
+                root.commands().insert_resource(Spawned(planet, moon));
+            });
+        });
+    app.update();
+
+    let Spawned(planet, moon) = *app.world().resource::<Spawned>();
+    assert!(app.world().get::<Grid<i32>>(planet).is_some());
+    let moon_grid = app.world().get::<Grid<i32>>(moon).unwrap();
+    assert_eq!(moon_grid.cell_edge_length(), Vec3::splat(5.0));
+    assert_eq!(app.world().get::<Children>(moon).unwrap().len(), 1);
+}
+
+#[test]
+fn disabling_low_precision_skips_tagging() {
+    use crate::grid::propagation::LowPrecisionRoot;
+
+    let mut app = App::new();
+    app.add_plugins(BigSpacePlugin::<i32>::default().with_low_precision(false));
+
+    let parent = app
+        .world_mut()
+        .spawn((
+            Transform::from_xyz(1.0, 0.0, 0.0),
+            GridCell::<i32>::ZERO,
+            FloatingOrigin,
+        ))
+        .with_child(Transform::from_xyz(0.0, 5.0, 0.0))
+        .id();
+    app.world_mut()
+        .spawn(BigSpaceRootBundle::<i32>::default())
+        .add_child(parent);
+
+    app.update();
+
+    let parent_transform = app.world().get::<GlobalTransform>(parent).unwrap();
+    assert_eq!(parent_transform.translation(), Vec3::new(1.0, 0.0, 0.0));
+    let child = app.world().get::<Children>(parent).unwrap()[0];
+    assert!(app.world().get::<LowPrecisionRoot>(child).is_none());
+    let child_transform = app.world().get::<GlobalTransform>(child).unwrap();
+    assert_eq!(child_transform.translation(), Vec3::ZERO);
+}
+
+#[test]
+fn transfer_to_big_space_moves_hash() {
+    #[derive(Resource)]
+    struct Spawned {
+        source: Entity,
+        target: Entity,
+        target_grid: Entity,
+        traveler: Entity,
+    }
+
+    let mut app = App::new();
+    app.add_plugins((
+        BigSpacePlugin::<i32>::default(),
+        GridHashPlugin::<i32>::default(),
+    ))
+    .add_systems(Startup, |mut commands: Commands| {
+        let mut traveler = Entity::PLACEHOLDER;
+        let source = commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 0.0), |root| {
+            root.spawn_spatial(FloatingOrigin);
+            traveler = root
+                .spawn_spatial((
+                    GridCell::<i32>::new(5, 0, 0),
+                    Transform::from_rotation(Quat::from_rotation_y(1.0)),
+                ))
+                .with_child(Transform::from_xyz(0.0, 1.0, 0.0))
+                .id();
+        });
+        let mut target_grid = Entity::PLACEHOLDER;
+        let target = commands.spawn_big_space_default::<i32>(|root| {
+            root.spawn_spatial(FloatingOrigin);
+            target_grid = root.with_grid(Grid::new_uniform(10.0, 0.0), |_| {});
+        });
+        commands.insert_resource(Spawned {
+            source,
+            target,
+            target_grid,
+            traveler,
+        });
+    });
+    app.update();
+
+    let spawned = app.world().resource::<Spawned>();
+    let (source, target, target_grid, traveler) = (
+        spawned.source,
+        spawned.target,
+        spawned.target_grid,
+        spawned.traveler,
+    );
+    let old_hash = *app.world().get::<GridHash<i32>>(traveler).unwrap();
+
+    app.world_mut().commands().transfer_to_big_space::<i32>(
+        traveler,
+        target,
+        target_grid,
+        bevy::math::DVec3::new(21.0, 0.0, 0.0),
+    );
+    app.update();
+
+    let world = app.world();
+    assert_eq!(world.get::<Parent>(traveler).unwrap().get(), target_grid);
+    assert_eq!(
+        *world.get::<GridCell<i32>>(traveler).unwrap(),
+        GridCell::new(2, 0, 0)
+    );
+    let transform = world.get::<Transform>(traveler).unwrap();
+    assert_eq!(transform.translation, Vec3::new(1.0, 0.0, 0.0));
+    assert_eq!(transform.rotation, Quat::from_rotation_y(1.0));
+    assert_eq!(world.get::<Children>(traveler).unwrap().len(), 1);
+
+    let new_hash = *world.get::<GridHash<i32>>(traveler).unwrap();
+    assert_eq!(new_hash.grid(), target_grid);
+    let map = world.resource::<GridHashMap<i32>>();
+    assert!(!map.contains(&old_hash));
+    assert!(map.get(&new_hash).unwrap().entities.contains(&traveler));
+
+    let events = world.resource::<Events<BigSpaceTransfer>>();
+    let sent: Vec<_> = events.iter_current_update_events().copied().collect();
+    assert_eq!(
+        sent,
+        vec![BigSpaceTransfer {
+            entity: traveler,
+            from: Some(source),
+            to: target,
+        }]
+    );
+}
+
+#[test]
+fn transfer_floating_origin_to_big_space() {
+    #[derive(Resource)]
+    struct Spawned {
+        source: Entity,
+        target: Entity,
+        camera: Entity,
+        target_origin: Entity,
+    }
+
+    let mut app = App::new();
+    app.add_plugins(BigSpacePlugin::<i32>::default())
+        .add_systems(Startup, |mut commands: Commands| {
+            let mut camera = Entity::PLACEHOLDER;
+            let source = commands.spawn_big_space_default::<i32>(|root| {
+                camera = root.spawn_spatial(FloatingOrigin).id();
+            });
+            let mut target_origin = Entity::PLACEHOLDER;
+            let target = commands.spawn_big_space_default::<i32>(|root| {
+                target_origin = root.spawn_spatial(FloatingOrigin).id();
+            });
+            commands.insert_resource(Spawned {
+                source,
+                target,
+                camera,
+                target_origin,
+            });
+        });
+    app.update();
+
+    let spawned = app.world().resource::<Spawned>();
+    let (source, target, camera, target_origin) = (
+        spawned.source,
+        spawned.target,
+        spawned.camera,
+        spawned.target_origin,
+    );
+    let transfer = |app: &mut App| {
+        app.world_mut().commands().transfer_to_big_space::<i32>(
+            camera,
+            target,
+            target,
+            bevy::math::DVec3::ZERO,
+        );
+        app.update();
+        app.world()
+            .resource::<Events<BigSpaceTransfer>>()
+            .iter_current_update_events()
+            .count()
+    };
+
+    // The target already has a floating origin, so the camera is not moved.
+    assert_eq!(transfer(&mut app), 0);
+    assert_eq!(app.world().get::<Parent>(camera).unwrap().get(), source);
+    let origin_of = |app: &App, root| app.world().get::<BigSpace>(root).unwrap().floating_origin;
+    assert_eq!(origin_of(&app, target), Some(target_origin));
+
+    // Once the target's origin is removed, the camera can move into it.
+    app.world_mut()
+        .entity_mut(target_origin)
+        .remove::<FloatingOrigin>();
+    assert_eq!(transfer(&mut app), 1);
+    assert_eq!(app.world().get::<Parent>(camera).unwrap().get(), target);
+    assert_eq!(origin_of(&app, target), Some(camera));
+}
+
+#[test]
+fn rescale_preserves_positions() {
+    #[derive(Resource)]
+    struct Spawned {
+        root: Entity,
+        before: Entity,
+        after: Entity,
+    }
+
+    let mut app = App::new();
+    app.add_plugins(BigSpacePlugin::<i32>::default())
+        .add_systems(Startup, |mut commands: Commands| {
+            let (mut before, mut after) = (Entity::PLACEHOLDER, Entity::PLACEHOLDER);
+            let root = commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 1.0), |root| {
+                root.spawn_spatial(FloatingOrigin);
+                before = root
+                    .spawn_spatial((
+                        GridCell::<i32>::new(3, 0, -1),
+                        Transform::from_xyz(2.0, 0.5, 0.0),
+                    ))
+                    .id();
+                root.rescale(Vec3::splat(4.0));
+                after = root.spawn_spatial(GridCell::<i32>::new(1, 0, 0)).id();
+            });
+            commands.insert_resource(Spawned {
+                root,
+                before,
+                after,
+            });
+        });
+    app.update();
+
+    let spawned = app.world().resource::<Spawned>();
+    let (root, before, after) = (spawned.root, spawned.before, spawned.after);
+    let grid = app.world().get::<Grid<i32>>(root).unwrap();
+    assert_eq!(grid.cell_edge_length(), Vec3::splat(4.0));
+    assert_eq!(grid.maximum_distance_from_origin(), Vec3::splat(3.0));
+
+    // 32, 0.5, -10 in the old grid
+    assert_eq!(
+        *app.world().get::<GridCell<i32>>(before).unwrap(),
+        GridCell::new(8, 0, -3)
+    );
+    assert_eq!(
+        app.world().get::<Transform>(before).unwrap().translation,
+        Vec3::new(0.0, 0.5, 2.0)
+    );
+    assert_eq!(
+        *app.world().get::<GridCell<i32>>(after).unwrap(),
+        GridCell::new(1, 0, 0)
+    );
+}
+
+#[test]
+fn rescale_existing_grid() {
+    let mut app = App::new();
+    app.add_plugins(BigSpacePlugin::<i32>::default());
+
+    let mut child = Entity::PLACEHOLDER;
+    let root = app.world_mut().commands().spawn_big_space::<i32>(
+        Grid::new(Vec3::new(10.0, 2.0, 10.0), 1.0),
+        |root| {
+            root.spawn_spatial(FloatingOrigin);
+            child = root
+                .spawn_spatial((
+                    GridCell::<i32>::new(3, 1, -1),
+                    Transform::from_xyz(2.0, 0.25, 0.0),
+                ))
+                .id();
+        },
+    );
+    app.update();
+
+    app.world_mut()
+        .commands()
+        .rescale_grid::<i32>(root, Vec3::new(4.0, 1.0, 4.0));
+    app.update();
+
+    let grid = app.world().get::<Grid<i32>>(root).unwrap();
+    assert_eq!(grid.cell_edge_length(), Vec3::new(4.0, 1.0, 4.0));
+    assert_eq!(
+        grid.maximum_distance_from_origin(),
+        Vec3::new(3.0, 1.5, 3.0)
+    );
+
+    // 32, 2.25, -10 in the old grid
+    assert_eq!(
+        *app.world().get::<GridCell<i32>>(child).unwrap(),
+        GridCell::new(8, 2, -3)
+    );
+    assert_eq!(
+        app.world().get::<Transform>(child).unwrap().translation,
+        Vec3::new(0.0, 0.25, 2.0)
+    );
+}
+
+#[test]
+fn big_spaces_finds_origins() {
+    #[derive(Resource)]
+    struct Spawned {
+        with_origin: Entity,
+        origin: Entity,
+        without_origin: Entity,
+    }
+
+    let mut app = App::new();
+    app.add_plugins(BigSpacePlugin::<i32>::default())
+        .add_systems(Startup, |mut commands: Commands| {
+            let mut origin = Entity::PLACEHOLDER;
+            let with_origin = commands.spawn_big_space_default::<i32>(|root| {
+                root.with_grid_default(|grid| {
+                    origin = grid.spawn_spatial(FloatingOrigin).id();
+                });
+            });
+            let without_origin = commands.spawn_big_space_default::<i32>(|_| {});
+            commands.insert_resource(Spawned {
+                with_origin,
+                origin,
+                without_origin,
+            });
+        });
+    app.update();
+
+    let spawned = app.world().resource::<Spawned>();
+    let (with_origin, origin, without_origin) =
+        (spawned.with_origin, spawned.origin, spawned.without_origin);
+    let mut state = bevy::ecs::system::SystemState::<BigSpaces>::new(app.world_mut());
+    let big_spaces = state.get(app.world());
+
+    let mut roots: Vec<_> = big_spaces.roots().collect();
+    roots.sort();
+    assert_eq!(roots, vec![with_origin, without_origin]);
+    assert_eq!(big_spaces.floating_origin(with_origin), Some(origin));
+    assert_eq!(big_spaces.floating_origin(without_origin), None);
+    assert_eq!(big_spaces.floating_origin(origin), None);
+    assert_eq!(big_spaces.iter().count(), 2);
+}
+
+#[test]
+fn split_big_space_preserves_global_transforms() {
+    #[derive(Resource)]
+    struct Spawned {
+        root: Entity,
+        near: Entity,
+        far: Entity,
+        far_child: Entity,
+    }
+
+    let mut app = App::new();
+    app.add_plugins(BigSpacePlugin::<i32>::default())
+        .add_systems(Startup, |mut commands: Commands| {
+            let (mut near, mut far, mut far_child) = (
+                Entity::PLACEHOLDER,
+                Entity::PLACEHOLDER,
+                Entity::PLACEHOLDER,
+            );
+            let root = commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 0.0), |root| {
+                root.spawn_spatial((FloatingOrigin, Transform::from_xyz(3.0, 0.0, 0.0)));
+                near = root
+                    .spawn_spatial((
+                        GridCell::<i32>::new(-5, 0, 0),
+                        Transform::from_xyz(1.0, 2.0, 3.0),
+                    ))
+                    .id();
+                root.with_grid(Grid::new_uniform(1.0, 0.0), |grid| {
+                    far = grid.id();
+                    grid.insert((
+                        GridCell::<i32>::new(5, 1, 0),
+                        Transform::from_rotation(Quat::from_rotation_y(1.0)),
+                    ));
+                    far_child = grid
+                        .spawn_spatial((
+                            GridCell::<i32>::new(2, 0, 0),
+                            Transform::from_xyz(0.5, 0.0, 0.0),
+                        ))
+                        .id();
+                });
+            });
+            commands.insert_resource(Spawned {
+                root,
+                near,
+                far,
+                far_child,
+            });
+        });
+    app.update();
+
+    let spawned = app.world().resource::<Spawned>();
+    let (root, near, far, far_child) = (spawned.root, spawned.near, spawned.far, spawned.far_child);
+    let global = |app: &App, entity| *app.world().get::<GlobalTransform>(entity).unwrap();
+    let before = [near, far, far_child].map(|entity| global(&app, entity));
+
+    let new_root = app.world_mut().commands().split_big_space::<i32>(
+        root,
+        bevy::math::DVec3::new(20.0, 0.0, 0.0),
+        bevy::math::DVec3::X,
+    );
+    app.update();
+    app.update();
+
+    let world = app.world();
+    assert!(world.get::<BigSpace>(new_root).is_some());
+    assert_eq!(world.get::<Parent>(near).unwrap().get(), root);
+    assert_eq!(world.get::<Parent>(far).unwrap().get(), new_root);
+    assert_eq!(world.get::<Parent>(far_child).unwrap().get(), far);
+    // The new root is centered on the cell containing the plane origin, at 2, 0, 0.
+    assert_eq!(
+        *world.get::<GridCell<i32>>(far).unwrap(),
+        GridCell::new(3, 1, 0)
+    );
+
+    let after = [near, far, far_child].map(|entity| global(&app, entity));
+    for (before, after) in before.iter().zip(&after) {
+        assert!(before.translation().abs_diff_eq(after.translation(), 1e-4));
+        assert!(before.rotation().abs_diff_eq(after.rotation(), 1e-6));
+    }
+
+    // The floating origin is still in the old space, and the new space gets its own origin where
+    // the old one was.
+    let origin = world.get::<BigSpace>(root).unwrap().floating_origin;
+    assert!(origin.is_some());
+    let new_origin = world
+        .get::<BigSpace>(new_root)
+        .unwrap()
+        .floating_origin
+        .unwrap();
+    assert_eq!(world.get::<Parent>(new_origin).unwrap().get(), new_root);
+    // The old origin is in cell 0, 0, 0 of the old space.
+    assert_eq!(
+        *world.get::<GridCell<i32>>(new_origin).unwrap(),
+        GridCell::new(-2, 0, 0)
+    );
+
+    // Splitting an entity that is not a root does nothing, and doesn't leave an empty space.
+    let not_root = app.world_mut().commands().split_big_space::<i32>(
+        far,
+        bevy::math::DVec3::ZERO,
+        bevy::math::DVec3::X,
+    );
+    app.update();
+    assert!(app.world().get_entity(not_root).is_err());
+    assert_eq!(app.world().get::<Parent>(far_child).unwrap().get(), far);
+}
+
+#[test]
+#[allow(deprecated)]
+fn reference_frame_names_are_aliases() {
+    use crate::reference_frame::*;
+
+    fn spawn(mut commands: Commands) {
+        commands.spawn_big_space(ReferenceFrame::<i32>::default(), |root| {
+            let _: &mut ReferenceFrameCommands<i32> = root;
+            root.spawn_spatial(FloatingOrigin);
+        });
+        commands.spawn(BigReferenceFrameBundle::<i32>::default());
+    }
+    fn count(frames: ReferenceFrames<i32>) {
+        assert_eq!(frames.roots().count(), 1);
+    }
+
+    let mut app = App::new();
+    app.add_plugins(BigSpacePlugin::<i32>::default())
+        .add_systems(Startup, spawn)
+        .add_systems(Update, count);
+    app.update();
+}
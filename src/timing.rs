@@ -7,6 +7,7 @@ use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
 use bevy_reflect::prelude::*;
 use bevy_transform::TransformSystem;
+use bevy_utils::HashMap;
 
 /// Summarizes plugin performance timings
 pub struct TimingStatsPlugin;
@@ -65,11 +66,56 @@ pub struct PropagationStats {
     pub(crate) low_precision_root_tagging: Duration,
     pub(crate) low_precision_propagation: Duration,
     pub(crate) total: Duration,
+    /// Persists across resets, so the breakdown stays enabled.
+    per_root_enabled: bool,
+    pub(crate) high_precision_propagation_per_root: HashMap<Entity, Duration>,
+    pub(crate) high_precision_skipped: usize,
+    pub(crate) high_precision_recomputed: usize,
+    pub(crate) max_grid_depth: usize,
 }
 
 impl PropagationStats {
     pub(crate) fn reset(mut stats: ResMut<Self>) {
-        *stats = Self::default();
+        *stats = Self {
+            per_root_enabled: stats.per_root_enabled,
+            ..Self::default()
+        };
+    }
+
+    /// Enable or disable [`Self::high_precision_propagation_per_root`]. This is disabled by
+    /// default, because timing each [`BigSpace`] separately prevents high precision propagation
+    /// from running in parallel.
+    pub fn set_per_root_enabled(&mut self, enabled: bool) {
+        self.per_root_enabled = enabled;
+    }
+
+    /// Returns `true` if [`Self::high_precision_propagation_per_root`] is being recorded.
+    pub fn per_root_enabled(&self) -> bool {
+        self.per_root_enabled
+    }
+
+    /// How long it took to run high precision propagation this update for each [`BigSpace`],
+    /// keyed by the root entity. This is empty unless enabled with
+    /// [`Self::set_per_root_enabled`].
+    pub fn high_precision_propagation_per_root(&self) -> &HashMap<Entity, Duration> {
+        &self.high_precision_propagation_per_root
+    }
+
+    /// The number of high precision entities whose `GlobalTransform` was not recomputed during
+    /// high precision propagation this update, because neither the entity nor the floating origin
+    /// moved relative to it, or because its update was deferred by
+    /// [`PropagationPriority`](crate::grid::propagation::PropagationPriority).
+    ///
+    /// A high count for static scenery means the optimization is working. If an entity does not
+    /// visually move when expected, check that it is not being skipped.
+    pub fn high_precision_skipped(&self) -> usize {
+        self.high_precision_skipped
+    }
+
+    /// The number of high precision entities whose `GlobalTransform` was recomputed during high
+    /// precision propagation this update.
+    pub fn high_precision_recomputed(&self) -> usize {
+        self.high_precision_recomputed
     }
 
     /// How long it took to run
@@ -79,6 +125,12 @@ impl PropagationStats {
         self.grid_recentering
     }
 
+    /// The depth of the deepest [`Grid`] hierarchy visited during [`LocalFloatingOrigin`]
+    /// propagation this update, where the root [`Grid`] of a [`BigSpace`] has a depth of zero.
+    pub fn max_grid_depth(&self) -> usize {
+        self.max_grid_depth
+    }
+
     /// How long it took to run [`LocalFloatingOrigin`] propagation this update.
     pub fn local_origin_propagation(&self) -> Duration {
         self.local_origin_propagation
@@ -117,6 +169,15 @@ impl<'a> std::iter::Sum<&'a PropagationStats> for PropagationStats {
             acc.low_precision_propagation += e.low_precision_propagation;
             acc.low_precision_root_tagging += e.low_precision_root_tagging;
             acc.total += e.total;
+            acc.per_root_enabled |= e.per_root_enabled;
+            acc.high_precision_skipped += e.high_precision_skipped;
+            acc.high_precision_recomputed += e.high_precision_recomputed;
+            acc.max_grid_depth += e.max_grid_depth;
+            for (root, duration) in e.high_precision_propagation_per_root.iter() {
+                *acc.high_precision_propagation_per_root
+                    .entry(*root)
+                    .or_default() += *duration;
+            }
             acc
         })
     }
@@ -133,6 +194,15 @@ impl std::ops::Div<u32> for PropagationStats {
             low_precision_root_tagging: self.low_precision_root_tagging.div(rhs),
             low_precision_propagation: self.low_precision_propagation.div(rhs),
             total: self.total.div(rhs),
+            per_root_enabled: self.per_root_enabled,
+            high_precision_propagation_per_root: self
+                .high_precision_propagation_per_root
+                .into_iter()
+                .map(|(root, duration)| (root, duration.div(rhs)))
+                .collect(),
+            high_precision_skipped: self.high_precision_skipped.div(rhs as usize),
+            high_precision_recomputed: self.high_precision_recomputed.div(rhs as usize),
+            max_grid_depth: self.max_grid_depth.div(rhs as usize),
         }
     }
 }
@@ -205,6 +275,43 @@ impl Div<u32> for GridHashStats {
     }
 }
 
+/// Occupancy statistics of the [`GridHashMap`] with the same [`GridHashMapFilter`], updated every
+/// frame in [`GridHashMapSystem::UpdateMap`]. Useful for detecting clustering of entities in a few
+/// cells, which makes neighbor queries slow.
+///
+/// [`GridHashMapFilter`]: crate::hash::GridHashMapFilter
+#[derive(Resource, Debug, Clone)]
+pub struct GridHashOccupancy<P: GridPrecision, F: crate::hash::GridHashMapFilter = ()> {
+    /// The number of cells with at least one entity.
+    pub occupied_cells: usize,
+    /// The number of entities in the map.
+    pub entities: usize,
+    /// The largest number of entities in a single cell.
+    pub max_entities_per_cell: usize,
+    spooky: std::marker::PhantomData<(P, F)>,
+}
+
+impl<P: GridPrecision, F: crate::hash::GridHashMapFilter> Default for GridHashOccupancy<P, F> {
+    fn default() -> Self {
+        Self {
+            occupied_cells: 0,
+            entities: 0,
+            max_entities_per_cell: 0,
+            spooky: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<P: GridPrecision, F: crate::hash::GridHashMapFilter> GridHashOccupancy<P, F> {
+    /// The average number of entities in each occupied cell.
+    pub fn average_entities_per_cell(&self) -> f32 {
+        if self.occupied_cells == 0 {
+            return 0.0;
+        }
+        self.entities as f32 / self.occupied_cells as f32
+    }
+}
+
 /// Smoothed timing statistics
 #[derive(Resource, Debug, Reflect)]
 pub struct SmoothedStat<T>
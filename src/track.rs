@@ -0,0 +1,155 @@
+//! Keep an entity, usually a camera, positioned relative to the [`FloatingOrigin`].
+//!
+//! The floating origin does not need to be the camera. It is often better to put it on a stable
+//! point of interest, like the player's ship, and have the camera follow it. Because the floating
+//! origin's cell defines the rendering origin, this keeps the origin from changing cells as the
+//! camera orbits, zooms, or shakes.
+
+use crate::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::prelude::*;
+use bevy_math::prelude::*;
+use bevy_reflect::prelude::*;
+use bevy_transform::prelude::*;
+
+/// Add this to a high precision entity, like a camera, to keep it at `offset` from the
+/// [`FloatingOrigin`] of its [`BigSpace`] every frame.
+///
+/// The `offset` is in the local space of the floating origin, so the entity follows the origin
+/// as it turns. Only the [`GridCell`] and [`Transform::translation`] of the entity are updated,
+/// its rotation is left for you to control, for example to orbit the floating origin by changing
+/// the offset, while looking towards it:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use big_space::prelude::*;
+/// fn orbit(
+///     time: Res<Time>,
+///     origins: Query<&Transform, (With<FloatingOrigin>, Without<TrackFloatingOrigin>)>,
+///     mut cameras: Query<(&mut TrackFloatingOrigin, &mut Transform)>,
+/// ) {
+///     let Ok(origin) = origins.get_single() else {
+///         return;
+///     };
+///     for (mut track, mut transform) in &mut cameras {
+///         let angle = time.elapsed_secs() * 0.1;
+///         track.offset = Vec3::new(angle.sin(), 0.5, angle.cos()) * 20.0;
+///         let look = Transform::from_translation(track.offset).looking_at(Vec3::ZERO, Vec3::Y);
+///         transform.rotation = origin.rotation * look.rotation;
+///     }
+/// }
+/// ```
+///
+/// The entity must be in the same [`Grid`] as the floating origin, otherwise it is not moved. This
+/// is updated in [`FloatingOriginSystem::RecenterLargeTransforms`].
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct TrackFloatingOrigin {
+    /// The position of this entity in the local space of the floating origin.
+    pub offset: Vec3,
+}
+
+impl TrackFloatingOrigin {
+    /// Update the position of all tracking entities to follow their floating origin.
+    pub fn update<P: GridPrecision>(
+        grids: Query<&Grid<P>>,
+        spaces: Query<&BigSpace>,
+        parents: Query<&Parent>,
+        origins: Query<(&GridCell<P>, &Transform, &Parent), Without<TrackFloatingOrigin>>,
+        mut trackers: Query<(
+            &TrackFloatingOrigin,
+            &Parent,
+            &mut GridCell<P>,
+            &mut Transform,
+        )>,
+    ) {
+        for (track, parent, mut cell, mut transform) in &mut trackers {
+            let root = parents
+                .iter_ancestors(parent.get())
+                .last()
+                .unwrap_or(parent.get());
+            let origin = spaces
+                .get(root)
+                .ok()
+                .and_then(|space| space.floating_origin);
+            let Some((origin_cell, origin_transform, origin_parent)) =
+                origin.and_then(|origin| origins.get(origin).ok())
+            else {
+                continue;
+            };
+            if origin_parent != parent {
+                continue;
+            }
+            let Ok(grid) = grids.get(parent.get()) else {
+                continue;
+            };
+            let (new_cell, new_transform) =
+                grid.offset_from(origin_cell, origin_transform, track.offset);
+            cell.set_if_neq(new_cell);
+            if transform.translation != new_transform.translation {
+                transform.translation = new_transform.translation;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{math::DVec3, prelude::*};
+
+    #[test]
+    fn follows_floating_origin() {
+        #[derive(Resource, Clone, Copy)]
+        struct Entities {
+            ship: Entity,
+            camera: Entity,
+        }
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i64>::default())
+            .add_systems(Startup, |mut commands: Commands| {
+                let (mut ship, mut camera) = (Entity::PLACEHOLDER, Entity::PLACEHOLDER);
+                commands.spawn_big_space::<i64>(Grid::new_uniform(10.0, 0.0), |root| {
+                    ship = root
+                        .spawn_spatial((
+                            FloatingOrigin,
+                            GridCell::<i64>::new(1_000_000_000_000, 0, 0),
+                            Transform::from_rotation(Quat::from_rotation_y(
+                                std::f32::consts::FRAC_PI_2,
+                            )),
+                        ))
+                        .id();
+                    camera = root
+                        .spawn_spatial(TrackFloatingOrigin {
+                            offset: Vec3::new(0.0, 5.0, 20.0),
+                        })
+                        .id();
+                });
+                commands.insert_resource(Entities { ship, camera });
+            });
+        app.update();
+        app.update();
+
+        let Entities { ship, camera } = *app.world().resource::<Entities>();
+        let position = |app: &App, entity| {
+            let world = app.world();
+            let grid = Grid::<i64>::new_uniform(10.0, 0.0);
+            grid.grid_position_double(
+                world.get::<GridCell<i64>>(entity).unwrap(),
+                world.get::<Transform>(entity).unwrap(),
+            )
+        };
+
+        // The offset is rotated into the ship's local space: +Z becomes +X.
+        let expected = position(&app, ship) + DVec3::new(20.0, 5.0, 0.0);
+        assert!(position(&app, camera).distance(expected) < 1e-4);
+        let global = app.world().get::<GlobalTransform>(camera).unwrap();
+        assert!(global.translation().distance(Vec3::new(20.0, 5.0, 0.0)) < 1e-4);
+
+        app.world_mut().get_mut::<GridCell<i64>>(ship).unwrap().x += 3;
+        app.update();
+        let expected = position(&app, ship) + DVec3::new(20.0, 5.0, 0.0);
+        assert!(position(&app, camera).distance(expected) < 1e-4);
+    }
+}
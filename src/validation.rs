@@ -3,10 +3,12 @@
 use std::marker::PhantomData;
 
 use crate::prelude::*;
+use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
 use bevy_hierarchy::prelude::*;
+use bevy_reflect::prelude::*;
 use bevy_transform::prelude::*;
-use bevy_utils::{HashMap, HashSet};
+use bevy_utils::{Duration, HashMap, HashSet, Instant};
 
 use crate::{grid::Grid, precision::GridPrecision, BigSpace, FloatingOrigin, GridCell};
 
@@ -127,6 +129,171 @@ If possible, use commands.spawn_big_space(), which prevents these errors, instea
     world.insert_resource(caches);
 }
 
+/// Reports entities with a [`Transform`] translation that is many times larger than the cells of
+/// their [`Grid`], configured with the [`LargeTransformValidation`] resource.
+///
+/// [`GridCell`]s are recomputed when an entity moves too far from the center of its cell, but if a
+/// very large translation is written directly to a [`Transform`], like `1e20`, precision has
+/// already been lost before that can happen. This usually means the transform is being used to set
+/// an absolute position, instead of using the [`GridCell`].
+pub struct LargeTransformValidationPlugin<P: GridPrecision>(PhantomData<P>);
+
+impl<P: GridPrecision> Default for LargeTransformValidationPlugin<P> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<P: GridPrecision> Plugin for LargeTransformValidationPlugin<P> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LargeTransformValidation>()
+            .register_type::<LargeTransformValidation>()
+            .add_systems(
+                PostStartup,
+                validate_large_transforms::<P>
+                    .before(FloatingOriginSystem::RecenterLargeTransforms),
+            )
+            .add_systems(
+                PostUpdate,
+                validate_large_transforms::<P>
+                    .before(FloatingOriginSystem::RecenterLargeTransforms),
+            );
+    }
+}
+
+/// Configures the [`LargeTransformValidationPlugin`].
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct LargeTransformValidation {
+    /// A warning is logged when the translation of an entity along any axis is larger than this
+    /// many cell edge lengths of its grid. Defaults to `100.0`.
+    pub max_cell_lengths: f32,
+    /// If `true`, offending translations are clamped to the limit, instead of being recentered into
+    /// a far away cell with a translation that has already lost its precision. Defaults to
+    /// `false`.
+    pub clamp: bool,
+    /// The minimum time between warnings for the same entity, so an entity that keeps getting a
+    /// large translation does not flood the log. Defaults to 5 seconds.
+    pub warning_interval: Duration,
+}
+
+impl Default for LargeTransformValidation {
+    fn default() -> Self {
+        Self {
+            max_cell_lengths: 100.0,
+            clamp: false,
+            warning_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+/// A system that reports, and optionally clamps, [`Transform`]s that are much larger than the cells
+/// of their [`Grid`]. Warnings for each entity are throttled to one every
+/// [`LargeTransformValidation::warning_interval`]. See [`LargeTransformValidationPlugin`].
+pub fn validate_large_transforms<P: GridPrecision>(
+    config: Res<LargeTransformValidation>,
+    grids: Query<&Grid<P>>,
+    mut transforms: Query<
+        (Entity, &mut Transform, &Parent),
+        (With<GridCell<P>>, Changed<Transform>),
+    >,
+    cells: Query<(), With<GridCell<P>>>,
+    mut reported: Local<HashMap<Entity, Instant>>,
+) {
+    // Forget entities that were despawned or left their grid.
+    reported.retain(|entity, _| cells.contains(*entity));
+
+    for (entity, mut transform, parent) in &mut transforms {
+        let Ok(grid) = grids.get(parent.get()) else {
+            continue;
+        };
+        let limit = grid.cell_edge_length() * config.max_cell_lengths;
+        let translation = transform.bypass_change_detection().translation;
+        if translation.abs().cmple(limit).all() {
+            reported.remove(&entity);
+            continue;
+        }
+        let now = Instant::now();
+        let throttled = reported
+            .get(&entity)
+            .is_some_and(|last| now.duration_since(*last) < config.warning_interval);
+        if !throttled {
+            reported.insert(entity, now);
+            tracing::warn!(
+                "Entity {entity} has a Transform translation of {translation}, which is more than {} times larger than the cell edge length of its grid ({}). Precision was lost when this translation was set. To place an entity far away, set its GridCell instead, or use Grid::translation_to_grid.",
+                config.max_cell_lengths,
+                grid.cell_edge_length(),
+            );
+        }
+        if config.clamp {
+            transform.translation = translation.clamp(-limit, limit);
+        }
+    }
+}
+
+/// Sent by [`validate_grid_cycles`] when a cycle is found in the parent hierarchy of [`Grid`]s.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct GridHierarchyCycle {
+    /// The entities in the cycle, ordered from child to parent.
+    pub cycle: Vec<Entity>,
+}
+
+/// Reports cycles in the parent hierarchy of [`Grid`]s, logging every entity in the cycle and
+/// sending a [`GridHierarchyCycle`] event. A grid in a cycle has no root, so it is never part of a
+/// [`BigSpace`], and none of its entities will be updated. Each cycle is only reported once, until
+/// it is broken.
+///
+/// Only grids with a changed [`Parent`] are checked, so this is cheap when the hierarchy is not
+/// changing. This runs when hierarchy validation is enabled in the [`BigSpacePlugin`].
+pub fn validate_grid_cycles<P: GridPrecision>(
+    changed: Query<Entity, (With<Grid<P>>, Changed<Parent>)>,
+    parents: Query<&Parent>,
+    mut reported: Local<Vec<Vec<Entity>>>,
+    mut cycles: EventWriter<GridHierarchyCycle>,
+) {
+    // Forget cycles that were broken, so they are reported again if they form again.
+    reported.retain(|cycle| {
+        find_parent_cycle(cycle[0], &parents).is_some_and(|current| {
+            current.len() == cycle.len() && current.iter().all(|entity| cycle.contains(entity))
+        })
+    });
+
+    for entity in &changed {
+        let Some(cycle) = find_parent_cycle(entity, &parents) else {
+            continue;
+        };
+        // An entity only has one parent, so it can only be in a single cycle.
+        if reported.iter().any(|reported| reported.contains(&cycle[0])) {
+            continue;
+        }
+        reported.push(cycle.clone());
+        let chain = cycle
+            .iter()
+            .chain(cycle.first())
+            .map(|entity| format!("{entity}"))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        tracing::error!("The grid {entity} is in a hierarchy cycle, where each entity is the child of the next: {chain}. Entities in this cycle are not in any BigSpace, and will not be updated. Change the parent of one of these entities to break the cycle.");
+        cycles.send(GridHierarchyCycle { cycle });
+    }
+}
+
+/// Walk up the ancestors of `entity`, returning the entities that form a cycle, if there is one.
+/// The cycle is ordered from child to parent, and may not contain `entity` if it is a descendant
+/// of a cycle.
+pub fn find_parent_cycle(entity: Entity, parents: &Query<&Parent>) -> Option<Vec<Entity>> {
+    let mut ancestors = vec![entity];
+    let mut current = entity;
+    while let Ok(parent) = parents.get(current) {
+        current = parent.get();
+        if let Some(start) = ancestors.iter().position(|&e| e == current) {
+            return Some(ancestors.split_off(start));
+        }
+        ancestors.push(current);
+    }
+    None
+}
+
 /// Defines a valid node in the hierarchy: what components it must have, must not have, and what
 /// kinds of nodes its children can be. This can be used recursively to validate an entire entity
 /// hierarchy by starting from the root.
@@ -371,3 +538,101 @@ impl<P: GridPrecision> ValidHierarchyNode for ChildSpatialHighPrecision<P> {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_large_transforms() {
+        let mut app = App::new();
+        app.add_plugins((
+            BigSpacePlugin::<i32>::default(),
+            LargeTransformValidationPlugin::<i32>::default(),
+        ))
+        .insert_resource(LargeTransformValidation {
+            clamp: true,
+            ..Default::default()
+        });
+
+        let root = app
+            .world_mut()
+            .spawn(BigSpaceRootBundle::<i32> {
+                grid: Grid::new_uniform(10.0, 0.0),
+                ..Default::default()
+            })
+            .id();
+        let entity = app
+            .world_mut()
+            .spawn((GridCell::<i32>::ZERO, Transform::from_xyz(1e20, 5.0, 0.0)))
+            .set_parent(root)
+            .id();
+
+        app.update();
+
+        let cell = *app.world().get::<GridCell<i32>>(entity).unwrap();
+        let transform = *app.world().get::<Transform>(entity).unwrap();
+        let position = app
+            .world()
+            .get::<Grid<i32>>(root)
+            .unwrap()
+            .grid_position_double(&cell, &transform);
+        assert!((position.x - 1_000.0).abs() < 1e-3);
+        assert!((position.y - 5.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn grid_cycle() {
+        use bevy_ecs::system::SystemState;
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::new(true));
+
+        let a = app.world_mut().spawn(BigGridBundle::<i32>::default()).id();
+        let b = app.world_mut().spawn(BigGridBundle::<i32>::default()).id();
+        let c = app.world_mut().spawn(BigGridBundle::<i32>::default()).id();
+        let child = app
+            .world_mut()
+            .spawn(BigGridBundle::<i32>::default())
+            .set_parent(a)
+            .id();
+        app.world_mut().entity_mut(a).set_parent(b);
+        app.world_mut().entity_mut(b).set_parent(c);
+        app.world_mut().entity_mut(c).set_parent(a);
+
+        // The cycle does not stop the app from updating.
+        app.update();
+
+        let reported = |app: &App| -> Vec<HashSet<Entity>> {
+            app.world()
+                .resource::<Events<GridHierarchyCycle>>()
+                .iter_current_update_events()
+                .map(|event| event.cycle.iter().copied().collect())
+                .collect()
+        };
+        let cycle = HashSet::from_iter([a, b, c]);
+        assert_eq!(reported(&app), vec![cycle.clone()]);
+        app.update();
+        assert!(reported(&app).is_empty());
+
+        let mut state = SystemState::<Query<&Parent>>::new(app.world_mut());
+        let parents = state.get(app.world());
+        assert_eq!(find_parent_cycle(a, &parents), Some(vec![a, b, c]));
+        assert_eq!(find_parent_cycle(child, &parents), Some(vec![a, b, c]));
+
+        let root = app
+            .world_mut()
+            .spawn(BigSpaceRootBundle::<i32>::default())
+            .id();
+        app.world_mut().entity_mut(c).set_parent(root);
+        let parents = state.get(app.world());
+        assert_eq!(find_parent_cycle(a, &parents), None);
+        app.update();
+        assert!(reported(&app).is_empty());
+
+        // The same cycle is reported again after it was broken.
+        app.world_mut().entity_mut(c).set_parent(a);
+        app.update();
+        assert_eq!(reported(&app), vec![cycle]);
+    }
+}
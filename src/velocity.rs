@@ -0,0 +1,137 @@
+//! Move high precision entities by integrating a velocity, without touching absolute positions.
+//!
+//! Far from the origin, an absolute position can't be represented precisely in an `f32`, and moving
+//! an entity by computing its new absolute position loses precision. Instead, entities should be
+//! moved by adding small relative deltas to their [`Transform`], and letting `big_space` move them
+//! into a new [`GridCell`] when they leave their current one. A [`GridVelocity`] does this for you.
+//!
+//! Add the [`GridVelocityPlugin`] to integrate velocities. This requires the `velocity` feature.
+
+use std::marker::PhantomData;
+
+use crate::prelude::*;
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::DVec3;
+use bevy_reflect::prelude::*;
+use bevy_time::prelude::*;
+use bevy_transform::prelude::*;
+
+/// Integrates the [`GridVelocity`] of high precision entities every frame.
+///
+/// This uses the [`Time`] resource, which must be added by bevy's `TimePlugin`, for example with
+/// `DefaultPlugins` or `MinimalPlugins`. The plugin panics when the app is finished if [`Time`]
+/// does not exist, instead of adding a clock that is never advanced.
+pub struct GridVelocityPlugin<P: GridPrecision>(PhantomData<P>);
+
+impl<P: GridPrecision> Default for GridVelocityPlugin<P> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<P: GridPrecision> Plugin for GridVelocityPlugin<P> {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GridVelocity>().add_systems(
+            PostUpdate,
+            GridVelocity::integrate::<P>
+                .in_set(FloatingOriginSystem::RecenterLargeTransforms)
+                .before(GridCell::<P>::recenter_large_transforms),
+        );
+    }
+
+    fn finish(&self, app: &mut App) {
+        assert!(
+            app.world().contains_resource::<Time>(),
+            "GridVelocityPlugin requires the Time resource, add bevy's TimePlugin before it"
+        );
+    }
+}
+
+/// The velocity of a high precision entity, in units per second in the space of its [`Grid`].
+///
+/// Every frame, the [`GridVelocityPlugin`] adds the velocity scaled by the [`Time`] delta to the
+/// [`Transform::translation`] of the entity, in double precision. This is done in
+/// [`FloatingOriginSystem::RecenterLargeTransforms`], before the entity is recentered, so it is
+/// moved into a new [`GridCell`] in the same frame it leaves its current one.
+///
+/// ```
+/// # use bevy::{math::DVec3, prelude::*};
+/// # use big_space::prelude::*;
+/// # let mut world = World::new();
+/// # let mut commands = world.commands();
+/// commands.spawn_big_space_default::<i64>(|root| {
+///     // Travel at the speed of light.
+///     root.spawn_spatial(GridVelocity(DVec3::X * 299_792_458.0));
+/// });
+/// ```
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct GridVelocity(pub DVec3);
+
+impl GridVelocity {
+    /// Add the velocity of each entity, scaled by the [`Time`] delta, to its [`Transform`].
+    pub fn integrate<P: GridPrecision>(
+        time: Res<Time>,
+        mut entities: Query<(&GridVelocity, &mut Transform), With<GridCell<P>>>,
+    ) {
+        let dt = time.delta_secs_f64();
+        if dt == 0.0 {
+            return;
+        }
+        entities
+            .par_iter_mut()
+            .for_each(|(velocity, mut transform)| {
+                if velocity.0 != DVec3::ZERO {
+                    let translation = transform.translation.as_dvec3() + velocity.0 * dt;
+                    transform.translation = translation.as_vec3();
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::*;
+    use std::time::Duration;
+
+    #[test]
+    fn integrate_across_cells() {
+        let mut app = App::new();
+        app.init_resource::<Time>().add_plugins((
+            BigSpacePlugin::<i64>::default(),
+            GridVelocityPlugin::<i64>::default(),
+        ));
+
+        let mut entity = Entity::PLACEHOLDER;
+        app.world_mut()
+            .commands()
+            .spawn_big_space::<i64>(Grid::new_uniform(10.0, 0.0), |root| {
+                root.spawn_spatial(FloatingOrigin);
+                entity = root
+                    .spawn_spatial((
+                        GridCell::<i64>::new(1_000_000_000_000, 0, 0),
+                        GridVelocity(DVec3::new(3.0, -0.5, 0.0)),
+                    ))
+                    .id();
+            });
+        app.update();
+
+        for _ in 0..4 {
+            app.world_mut()
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs(1));
+            app.update();
+        }
+
+        // Moved by (12, -2, 0) in total, and recentered every frame.
+        let world = app.world();
+        assert_eq!(
+            world.get::<GridCell<i64>>(entity),
+            Some(&GridCell::new(1_000_000_000_001, 0, 0))
+        );
+        let translation = world.get::<Transform>(entity).unwrap().translation;
+        assert!(translation.distance(Vec3::new(2.0, -2.0, 0.0)) < 1e-5);
+    }
+}
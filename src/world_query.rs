@@ -2,8 +2,12 @@
 //! with a [`Transform`].
 
 use crate::prelude::*;
-use bevy_ecs::query::QueryData;
-use bevy_math::{prelude::*, DVec3};
+use bevy_ecs::{
+    change_detection::{DetectChanges, DetectChangesMut},
+    prelude::*,
+    query::QueryData,
+};
+use bevy_math::{prelude::*, DAffine3, DVec3};
 use bevy_transform::prelude::*;
 
 #[derive(QueryData)]
@@ -37,6 +41,30 @@ impl<P: GridPrecision> GridTransformItem<'_, P> {
             cell: *self.cell,
         }
     }
+
+    /// Immediately recompute the cell from the current transform. See [`GridCell::recenter`].
+    pub fn recenter(&mut self, grid: &Grid<P>) {
+        self.cell.recenter(&mut self.transform, grid);
+    }
+
+    /// Compute the double precision affine transform of this entity in its grid.
+    pub fn affine_double(&self, grid: &Grid<P>) -> DAffine3 {
+        self.to_owned().affine_double(grid)
+    }
+
+    /// Set the cell and transform from a double precision affine transform in the grid, computing
+    /// the cell with [`Grid::translation_to_grid`]. The components are only changed if their
+    /// values differ, to avoid triggering change detection.
+    pub fn set_affine_double(&mut self, grid: &Grid<P>, affine: DAffine3) {
+        let (scale, rotation, translation) = affine.to_scale_rotation_translation();
+        let (cell, translation) = grid.translation_to_grid(translation);
+        self.cell.set_if_neq(cell);
+        self.transform.set_if_neq(Transform {
+            translation,
+            rotation: rotation.as_quat(),
+            scale: scale.as_vec3(),
+        });
+    }
 }
 
 impl<P: GridPrecision> GridTransformReadOnlyItem<'_, P> {
@@ -45,6 +73,11 @@ impl<P: GridPrecision> GridTransformReadOnlyItem<'_, P> {
         grid.grid_position_double(self.cell, self.transform)
     }
 
+    /// Compute the double precision affine transform of this entity in its grid.
+    pub fn affine_double(&self, grid: &Grid<P>) -> DAffine3 {
+        self.to_owned().affine_double(grid)
+    }
+
     /// Compute the global position.
     pub fn position(&self, grid: &Grid<P>) -> Vec3 {
         grid.grid_position(self.cell, self.transform)
@@ -59,6 +92,47 @@ impl<P: GridPrecision> GridTransformReadOnlyItem<'_, P> {
     }
 }
 
+#[derive(QueryData)]
+/// A read-only query argument that groups the [`GridCell`], [`Transform`], and [`GlobalTransform`]
+/// of an entity with their change detection, for systems that react to entities moving, like range
+/// checks.
+///
+/// Use [`GridTransformRefItem::is_changed`] to skip entities that have not moved since the system
+/// last ran, and [`GridTransformRefItem::absolute_position`] to compute their precise position.
+pub struct GridTransformRef<P: GridPrecision> {
+    /// Grid local transform
+    pub transform: Ref<'static, Transform>,
+    /// The grid to which `transform` is relative to.
+    pub cell: Ref<'static, GridCell<P>>,
+    /// The transform relative to the floating origin, used for rendering.
+    pub global_transform: Ref<'static, GlobalTransform>,
+}
+
+impl<P: GridPrecision> GridTransformRefItem<'_, P> {
+    /// Returns `true` if the [`GridCell`] or [`Transform`] of this entity changed since the system
+    /// last ran, meaning the entity moved within its grid.
+    ///
+    /// This ignores the [`GlobalTransform`], which also changes whenever the [`FloatingOrigin`]
+    /// moves, even if the entity did not. Use the change detection of
+    /// [`Self::global_transform`] if you need to know when the rendered position changed.
+    pub fn is_changed(&self) -> bool {
+        self.cell.is_changed() || self.transform.is_changed()
+    }
+
+    /// Compute the position of this entity in its grid, with double precision.
+    pub fn absolute_position(&self, grid: &Grid<P>) -> DVec3 {
+        grid.grid_position_double(&self.cell, &self.transform)
+    }
+
+    /// Get a copy of the fields to work with.
+    pub fn to_owned(&self) -> GridTransformOwned<P> {
+        GridTransformOwned {
+            transform: *self.transform,
+            cell: *self.cell,
+        }
+    }
+}
+
 /// A convenience wrapper that allows working with grid and transform easily
 #[derive(Copy, Clone)]
 pub struct GridTransformOwned<P: GridPrecision> {
@@ -104,4 +178,67 @@ impl<P: GridPrecision> GridTransformOwned<P> {
     pub fn position(&self, grid: &Grid<P>) -> Vec3 {
         grid.grid_position(&self.cell, &self.transform)
     }
+
+    /// Compute the double precision affine transform of this entity in its grid.
+    pub fn affine_double(&self, grid: &Grid<P>) -> DAffine3 {
+        DAffine3::from_scale_rotation_translation(
+            self.transform.scale.as_dvec3(),
+            self.transform.rotation.as_dquat(),
+            self.position_double(grid),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn grid_transform_ref_change_detection() {
+        #[derive(Resource, Default)]
+        struct Moved(Vec<DVec3>);
+
+        let mut app = App::new();
+        app.add_plugins(BigSpacePlugin::<i32>::default())
+            .init_resource::<Moved>()
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn_big_space::<i32>(Grid::new_uniform(10.0, 0.0), |root| {
+                    root.spawn_spatial(FloatingOrigin);
+                    root.spawn_spatial((
+                        GridCell::<i32>::new(1_000_000, 0, 0),
+                        Transform::from_xyz(1.0, 0.0, 0.0),
+                    ));
+                });
+            })
+            .add_systems(
+                Update,
+                |grids: Grids<i32>,
+                 query: Query<(Entity, GridTransformRef<i32>), Without<FloatingOrigin>>,
+                 mut moved: ResMut<Moved>| {
+                    for (entity, position) in &query {
+                        if position.is_changed() {
+                            let grid = grids.parent_grid(entity).unwrap();
+                            moved.0.push(position.absolute_position(grid));
+                        }
+                    }
+                },
+            );
+
+        app.update();
+        app.update();
+        assert_eq!(
+            app.world().resource::<Moved>().0,
+            vec![DVec3::new(10_000_001.0, 0.0, 0.0)]
+        );
+
+        // Moving the floating origin changes the global transform, but not the position.
+        let mut origin = app
+            .world_mut()
+            .query_filtered::<&mut Transform, With<FloatingOrigin>>();
+        origin.single_mut(app.world_mut()).translation.x = 3.0;
+        app.update();
+        app.update();
+        assert_eq!(app.world().resource::<Moved>().0.len(), 1);
+    }
 }